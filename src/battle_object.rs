@@ -0,0 +1,115 @@
+use smash::app::{BattleObject, BattleObjectModuleAccessor, lua_bind::DamageModule};
+
+/// The log of the last hit a fighter took, as returned by `DamageModule::damage_log`.
+/// Only the fields that the energies use are named
+#[repr(C)]
+pub struct DamageLog {
+    _x0: [u8; 0x4C],
+    /// The hitstop frames of the hit
+    pub hitstop_frames: i32,
+    _x50: [u8; 0x34],
+    /// The battle object id of the attacker
+    pub attacker_id: u32,
+}
+
+impl DamageLog {
+    /// Gets the damage log of a fighter
+    pub unsafe fn get(boma: &mut BattleObjectModuleAccessor) -> Option<&DamageLog> {
+        (DamageModule::damage_log(boma) as *const DamageLog).as_ref()
+    }
+
+    /// Gets the object that dealt the hit, if it still exists
+    pub unsafe fn attacker(&self) -> Option<BattleObjectRef> {
+        BattleObjectRef::from_id(self.attacker_id)
+    }
+}
+
+/// The category of a battle object, which is stored in the top 4 bits of its id
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ObjectCategory {
+    Fighter,
+    Weapon,
+    Enemy,
+    Gimmick,
+    Item,
+    Other(u32),
+}
+
+impl ObjectCategory {
+    pub fn from_id(id: u32) -> Self {
+        match id >> 28 {
+            0 => ObjectCategory::Fighter,
+            1 => ObjectCategory::Weapon,
+            2 => ObjectCategory::Enemy,
+            3 => ObjectCategory::Gimmick,
+            4 => ObjectCategory::Item,
+            other => ObjectCategory::Other(other)
+        }
+    }
+}
+
+unsafe fn get_battle_object_from_id(id: u32) -> *mut BattleObject {
+    let function: extern "C" fn(u32) -> *mut BattleObject = std::mem::transmute(crate::offsets::required(crate::offsets::Function::GetBattleObjectFromId));
+    function(id)
+}
+
+/// A battle object that was found by its id
+#[derive(Copy, Clone)]
+pub struct BattleObjectRef {
+    id: u32,
+    object: *mut BattleObject,
+}
+
+impl BattleObjectRef {
+    /// Looks up a battle object by its id, returns `None` if there is no object with that id
+    pub unsafe fn from_id(id: u32) -> Option<Self> {
+        let object = get_battle_object_from_id(id);
+        if object.is_null() {
+            None
+        } else {
+            Some(Self { id, object })
+        }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn category(&self) -> ObjectCategory {
+        ObjectCategory::from_id(self.id)
+    }
+
+    pub unsafe fn module_accessor(&self) -> *mut BattleObjectModuleAccessor {
+        (*self.object).module_accessor
+    }
+
+    /// Whether the object is fully set up and can be interacted with.
+    ///
+    /// This is the check the game does before pushing a fighter out of their attacker, it calls the object's
+    /// first virtual method (which returns true while the object is being torn down) and checks that the byte
+    /// at 0x3A (which looks like its current state) is past 3
+    pub unsafe fn is_active(&self) -> bool {
+        let vtable_method: extern "C" fn(*mut BattleObject) -> bool = std::mem::transmute(**(self.object as *const *const u64));
+        !vtable_method(self.object) && *(self.object as *const u8).add(0x3A) > 3
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use memoffset::offset_of;
+
+    #[test]
+    fn layout_check() {
+        assert_eq!(offset_of!(DamageLog, hitstop_frames), 0x4C);
+        assert_eq!(offset_of!(DamageLog, attacker_id), 0x84);
+    }
+
+    #[test]
+    fn category_from_id() {
+        assert_eq!(ObjectCategory::from_id(0x0000_0001), ObjectCategory::Fighter);
+        assert_eq!(ObjectCategory::from_id(0x1000_0010), ObjectCategory::Weapon);
+        assert_eq!(ObjectCategory::from_id(0x4000_0000), ObjectCategory::Item);
+        assert_eq!(ObjectCategory::from_id(0x9000_0000), ObjectCategory::Other(9));
+    }
+}
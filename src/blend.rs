@@ -0,0 +1,116 @@
+use super::{
+    energy::PaddedVec2,
+    fighter::FighterAccessor
+};
+
+/// How the translations of the main and 2nd motions are combined
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u32)]
+pub enum MotionBlendMode {
+    /// Only one of the motions is used, depending on the reset type (AirTrans2nd uses the 2nd motion)
+    Off,
+    /// Interpolates from the main motion (weight 0) to the 2nd motion (weight 1)
+    Lerp,
+    /// Adds the 2nd motion, scaled by the weight, on top of the main motion
+    Additive,
+}
+
+/// Values used by the motion energy to let layered animations both contribute root motion.
+///
+/// Blending only happens for fighters that have `enable_flag` set, so that status scripts can turn it on for the
+/// statuses that layer their animations
+#[derive(Debug, Copy, Clone)]
+#[repr(C)]
+pub struct MotionBlendParams {
+    pub mode: MotionBlendMode,
+    /// The work flag that enables blending, when this is below 0 blending applies to every fighter
+    pub enable_flag: i32,
+    /// The work float that the weight is read from, when this is below 0 `weight` is used instead
+    pub weight_float: i32,
+    pub weight: f32,
+}
+
+impl MotionBlendParams {
+    pub const DEFAULT: Self = Self {
+        mode: MotionBlendMode::Off,
+        enable_flag: -1,
+        weight_float: -1,
+        weight: 0.5,
+    };
+
+    /// Gets the weight of the 2nd motion, if the fighter's translations should be blended
+    pub fn weight<A: FighterAccessor + ?Sized>(&self, boma: &mut A) -> Option<f32> {
+        if self.mode == MotionBlendMode::Off || (self.enable_flag >= 0 && !boma.is_flag(self.enable_flag)) {
+            return None;
+        }
+
+        if self.weight_float >= 0 {
+            Some(boma.get_float(self.weight_float))
+        } else {
+            Some(self.weight)
+        }
+    }
+
+    /// Combines the translations of the two motions
+    /// # Arguments
+    /// * `main` - The translation of the main motion
+    /// * `second` - The translation of the 2nd motion
+    /// * `weight` - The weight of the 2nd motion
+    pub fn blend(&self, main: PaddedVec2, second: PaddedVec2, weight: f32) -> PaddedVec2 {
+        match self.mode {
+            MotionBlendMode::Off => main,
+            MotionBlendMode::Lerp => main.lerp(second, weight.max(0.0).min(1.0)),
+            MotionBlendMode::Additive => main + second * weight
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fighter::stub::*;
+
+    const ENABLE_FLAG: i32 = 0x200000e0;
+    const WEIGHT_FLOAT: i32 = 0x1000005f;
+
+    #[test]
+    fn off_never_blends() {
+        let params = MotionBlendParams::DEFAULT;
+        assert_eq!(params.weight(&mut StubFighter::new()), None);
+    }
+
+    #[test]
+    fn weight_sources() {
+        let mut fighter = StubFighter::new();
+        fighter.floats.insert(WEIGHT_FLOAT, 0.25);
+
+        let params = MotionBlendParams {
+            mode: MotionBlendMode::Lerp,
+            enable_flag: ENABLE_FLAG,
+            ..MotionBlendParams::DEFAULT
+        };
+        assert_eq!(params.weight(&mut fighter), None);
+
+        fighter.flags.insert(ENABLE_FLAG, true);
+        assert_eq!(params.weight(&mut fighter), Some(0.5));
+
+        let params = MotionBlendParams {
+            weight_float: WEIGHT_FLOAT,
+            ..params
+        };
+        assert_eq!(params.weight(&mut fighter), Some(0.25));
+    }
+
+    #[test]
+    fn blend_modes() {
+        let main = PaddedVec2::new(1.0, 0.0);
+        let second = PaddedVec2::new(0.0, 2.0);
+
+        let params = MotionBlendParams { mode: MotionBlendMode::Lerp, ..MotionBlendParams::DEFAULT };
+        assert_eq!(params.blend(main, second, 0.5), PaddedVec2::new(0.5, 1.0));
+        assert_eq!(params.blend(main, second, 2.0), second);
+
+        let params = MotionBlendParams { mode: MotionBlendMode::Additive, ..MotionBlendParams::DEFAULT };
+        assert_eq!(params.blend(main, second, 0.5), PaddedVec2::new(1.0, 1.0));
+    }
+}
@@ -0,0 +1,106 @@
+use super::{
+    blend::MotionBlendParams,
+    glide::GlideParams,
+    grab::GrabParams,
+    handoff::HandOffParams,
+    knockback::KnockBackPushOutParams,
+    ledge::LedgeParams,
+    log::LogConfig,
+    slope::SlopeParams,
+    swim::SwimParams,
+    tether::TetherParams,
+    traction::TractionParams
+};
+
+/// Tunable values for the parts of the energies that energy_id implements on top of
+/// (or in place of) the game's own params
+#[derive(Debug, Copy, Clone)]
+#[repr(C)]
+pub struct EnergyConfig {
+    pub swim: SwimParams,
+    pub glide: GlideParams,
+    pub tether: TetherParams,
+    pub grab: GrabParams,
+    pub knock_back: KnockBackPushOutParams,
+    pub log: LogConfig,
+    pub motion_blend: MotionBlendParams,
+    pub hand_off: HandOffParams,
+    pub slope: SlopeParams,
+    pub traction: TractionParams,
+    pub ledge: LedgeParams,
+    /// Projects grounded speeds with `energy::project_onto_ground` instead of the game's adjust_speed_for_ground_normal.
+    /// The Rust version hasn't been compared against the game's, this is only here so that they can be compared in game
+    pub rust_ground_projection: bool,
+}
+
+impl EnergyConfig {
+    pub const DEFAULT: Self = Self {
+        swim: SwimParams::DEFAULT,
+        glide: GlideParams::DEFAULT,
+        tether: TetherParams::DEFAULT,
+        grab: GrabParams::DEFAULT,
+        knock_back: KnockBackPushOutParams::DEFAULT,
+        log: LogConfig::DEFAULT,
+        motion_blend: MotionBlendParams::DEFAULT,
+        hand_off: HandOffParams::DEFAULT,
+        slope: SlopeParams::DEFAULT,
+        traction: TractionParams::DEFAULT,
+        ledge: LedgeParams::DEFAULT,
+        rust_ground_projection: false,
+    };
+}
+
+static mut CONFIG: EnergyConfig = EnergyConfig::DEFAULT;
+
+#[cfg(test)]
+thread_local! {
+    /// Each test runs on its own thread, so a test can swap in its own configuration without affecting the others
+    static TEST_CONFIG: std::cell::Cell<Option<&'static EnergyConfig>> = std::cell::Cell::new(None);
+}
+
+#[cfg(test)]
+fn test_config() -> Option<&'static EnergyConfig> {
+    TEST_CONFIG.with(|config| config.get())
+}
+
+#[cfg(not(test))]
+fn test_config() -> Option<&'static EnergyConfig> {
+    None
+}
+
+/// Gets the currently active configuration
+pub fn get() -> &'static EnergyConfig {
+    test_config().unwrap_or_else(|| unsafe {
+        &CONFIG
+    })
+}
+
+/// Runs `f` with `config` as the active configuration of the current thread
+#[cfg(test)]
+pub fn with<R>(config: EnergyConfig, f: impl FnOnce() -> R) -> R {
+    let config: &'static EnergyConfig = Box::leak(Box::new(config));
+    let previous = TEST_CONFIG.with(|current| current.replace(Some(config)));
+    let result = f();
+    TEST_CONFIG.with(|current| current.set(previous));
+    result
+}
+
+/// Gets the currently active configuration for modification. Changes take effect on the next
+/// call into the energy that uses them
+pub fn get_mut() -> &'static mut EnergyConfig {
+    unsafe {
+        &mut CONFIG
+    }
+}
+
+/// Exposed so that other plugins (and the dev plugin) can tune the energies at runtime
+#[no_mangle]
+pub extern "Rust" fn energy_id_config() -> &'static mut EnergyConfig {
+    get_mut()
+}
+
+/// Restores every configurable value to its default
+#[no_mangle]
+pub extern "Rust" fn energy_id_reset_config() {
+    *get_mut() = EnergyConfig::DEFAULT;
+}
@@ -2,7 +2,10 @@ use std::ops::{Deref, DerefMut};
 
 use super::{
     *,
-    energy::*
+    energy::*,
+    fighter::FighterAccessor,
+    handlers,
+    log::{Context, Level, Module}
 };
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -46,11 +49,211 @@ pub struct FighterKineticEnergyControl {
     pub unk: [u8; 4]
 }
 
-mod test {
-    use super::FighterKineticEnergyControl;
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+    use crate::fighter::stub::*;
 
     use memoffset::offset_of;
 
+    pub(crate) fn control_energy(reset_type: EnergyControllerResetType) -> FighterKineticEnergyControl {
+        let mut parent = stub_energy();
+        parent.energy_reset_type = reset_type as u32;
+        FighterKineticEnergyControl {
+            parent,
+            lr: 1.0,
+            accel_mul_x: 0.0,
+            accel_add_x: 0.0,
+            accel_mul_y: 0.0,
+            accel_add_y: 0.0,
+            _x9c: 0.0,
+            _xa0: 0.0,
+            unk: [0; 4]
+        }
+    }
+
+    /// The params each reset type is expected to read when initialized, an empty field is a fighter param
+    fn expected_initialize_params(reset_type: EnergyControllerResetType) -> &'static [(&'static str, &'static str)] {
+        use EnergyControllerResetType::*;
+        match reset_type {
+            FallAdjust | FallAdjustNoCap | WallJump => &[
+                ("air_speed_x_stable", ""), ("air_brake_x", ""), ("common", "air_speed_x_limit"),
+                ("air_accel_x_mul", ""), ("air_accel_x_add", "")
+            ],
+            StopCeil => &[
+                ("air_speed_x_stable", ""), ("common", "stop_ceil_speed_x_stable_mul"), ("air_brake_x", ""),
+                ("common", "air_speed_x_limit"), ("air_accel_x_mul", ""), ("air_accel_x_add", "")
+            ],
+            FlyAdjust | MoveAir | TurnNoStopAir | Free | FreeTest | RevolveSlashAir => &[
+                ("air_speed_x_stable", ""), ("common", "air_speed_x_limit"), ("air_brake_x", ""),
+                ("air_accel_x_mul", ""), ("air_accel_x_add", "")
+            ],
+            Dash | TurnRun | DashBack => &[
+                ("common", "ground_speed_limit"), ("run_speed_max", ""), ("ground_brake", ""),
+                ("common", "run_brake_brake_mul"), ("run_accel_mul", ""), ("run_accel_add", "")
+            ],
+            ShootDash | ShootBackDash => &[
+                ("common", "ground_speed_limit"), ("run_speed_max", ""), ("ground_brake", ""),
+                ("common", "run_brake_brake_mul")
+            ],
+            Turn | TurnNoStop => &[
+                ("walk_speed_max", ""), ("common", "ground_speed_limit"), ("ground_brake", ""),
+                ("common", "run_brake_brake_mul"), ("walk_accel_mul", ""), ("walk_accel_add", "")
+            ],
+            ItemLift => &[
+                ("item_lift_speed_max", ""), ("common", "ground_speed_limit"), ("ground_brake", ""),
+                ("common", "run_brake_brake_mul"), ("item_lift_accel_mul", ""), ("item_lift_accel_add", "")
+            ],
+            SwimRise | Swim => &[
+                ("common", "swim_brake"), ("common", "swim_accel_mul")
+            ],
+            SwimDrown => &[
+                ("common", "swim_brake"), ("common", "swim_accel_mul"), ("common", "swim_drown_speed_x_mul")
+            ],
+            MoveGround => &[
+                ("walk_speed_max", ""), ("common", "ground_speed_limit"), ("ground_brake", ""),
+                ("walk_accel_mul", ""), ("walk_accel_add", "")
+            ],
+            Ladder => &[
+                ("common", "ladder_speed_u_max"), ("common", "ladder_speed_d_max")
+            ],
+        }
+    }
+
+    #[test]
+    fn initialize_reads_params_for_every_reset_type() {
+        for raw in 0..=(EnergyControllerResetType::DashBack as u32) {
+            let reset_type: EnergyControllerResetType = unsafe { std::mem::transmute(raw) };
+            let mut energy = control_energy(reset_type);
+            let mut fighter = StubFighter::new();
+            unsafe {
+                initialize_control(&mut energy, &mut fighter);
+            }
+            for (obj, field) in expected_initialize_params(reset_type) {
+                assert!(fighter.was_requested(obj, field), "{:?} did not read {} {}", reset_type, obj, field);
+            }
+        }
+    }
+
+    #[test]
+    fn stop_ceil_scales_stable_speed() {
+        let mut energy = control_energy(EnergyControllerResetType::StopCeil);
+        let mut fighter = StubFighter::new()
+            .with_param("air_speed_x_stable", "", 1.0)
+            .with_param("common", "stop_ceil_speed_x_stable_mul", 0.5);
+        unsafe {
+            initialize_control(&mut energy, &mut fighter);
+        }
+        assert_eq!(energy.speed_max.x, 0.5);
+        assert_eq!(energy.speed_max.y, -1.0);
+    }
+
+    #[test]
+    fn fly_adjust_and_move_air_drift() {
+        for reset_type in [EnergyControllerResetType::FlyAdjust, EnergyControllerResetType::MoveAir].iter() {
+            let mut energy = control_energy(*reset_type);
+            let mut fighter = StubFighter::new()
+                .with_param("air_speed_x_stable", "", 1.0)
+                .with_param("air_brake_x", "", 0.01)
+                .with_param("air_accel_x_mul", "", 0.05)
+                .with_param("air_accel_x_add", "", 0.01);
+            unsafe {
+                initialize_control(&mut energy, &mut fighter);
+            }
+            assert_eq!(energy.speed_max.x, 1.0);
+            assert_eq!(energy.speed_brake.x, 0.01);
+            assert_eq!(energy.accel_mul_x, 0.05);
+            assert_eq!(energy.accel_add_x, 0.01);
+
+            fighter.stick = PaddedVec2::new(1.0, 0.0);
+            unsafe {
+                update_control(&mut energy, &mut fighter);
+            }
+            assert!((energy.accel.x - 0.06).abs() < 1.0e-6);
+        }
+    }
+
+    #[test]
+    fn move_ground_uses_walk_params() {
+        let mut energy = control_energy(EnergyControllerResetType::MoveGround);
+        let mut fighter = StubFighter::new()
+            .with_param("walk_speed_max", "", 1.2)
+            .with_param("ground_brake", "", 0.1)
+            .with_param("walk_accel_mul", "", 0.2);
+        unsafe {
+            initialize_control(&mut energy, &mut fighter);
+        }
+        assert_eq!(energy.speed_max.x, 1.2);
+        assert_eq!(energy.speed_brake.x, 0.1);
+        assert_eq!(energy.accel_mul_x, 0.2);
+    }
+
+    #[test]
+    fn free_test_controls_both_axes() {
+        let mut energy = control_energy(EnergyControllerResetType::FreeTest);
+        let mut fighter = StubFighter::new()
+            .with_param("air_speed_x_stable", "", 1.0)
+            .with_param("common", "air_speed_x_limit", 2.0)
+            .with_param("air_accel_x_mul", "", 0.1);
+        let initial_speed = Vector3f { x: 1.0, y: 1.0, z: 0.0 };
+        unsafe {
+            setup_control(&mut energy, EnergyControllerResetType::FreeTest, &initial_speed, 0, &mut fighter);
+        }
+        assert_eq!(energy.speed.x, 0.0);
+        assert_eq!(energy.speed.y, 0.0);
+
+        fighter.stick = PaddedVec2::new(0.0, -1.0);
+        unsafe {
+            update_control(&mut energy, &mut fighter);
+        }
+        assert!((energy.accel.y + 0.1).abs() < 1.0e-6);
+        assert!(energy.speed.y < 0.0);
+    }
+
+    #[test]
+    fn swim_sets_vertical_accel() {
+        use EnergyControllerResetType::*;
+        let mut config = crate::config::EnergyConfig::DEFAULT;
+        config.swim.enabled = true;
+        config.swim.drown_sink_accel = 0.02;
+        let swim = config.swim;
+        crate::config::with(config, || {
+            for (reset_type, stick_y, accel_y) in [
+                (Swim, 1.0, swim.swim_vertical(1.0).0),
+                (SwimRise, 0.0, swim.rise_vertical().0),
+                (SwimDrown, 0.0, swim.drown_vertical().0)
+            ].iter() {
+                let mut energy = control_energy(*reset_type);
+                let mut fighter = StubFighter::new();
+                // Anything the standard accel writes back over the swim accel would come from here
+                energy.accel.y = 1.0;
+                fighter.stick = PaddedVec2::new(0.0, *stick_y);
+                unsafe {
+                    update_control(&mut energy, &mut fighter);
+                }
+                assert_eq!(energy.accel.y, *accel_y, "{:?}", reset_type);
+            }
+        });
+    }
+
+    #[test]
+    fn swim_leaves_vertical_speed_alone_by_default() {
+        use EnergyControllerResetType::*;
+        for reset_type in [Swim, SwimRise, SwimDrown].iter() {
+            let mut energy = control_energy(*reset_type);
+            let mut fighter = StubFighter::new();
+            energy.accel.y = 1.0;
+            fighter.stick = PaddedVec2::new(0.0, 1.0);
+            unsafe {
+                initialize_control(&mut energy, &mut fighter);
+                update_control(&mut energy, &mut fighter);
+            }
+            assert_eq!(energy.accel.y, 1.0, "{:?}", reset_type);
+            assert_eq!(energy.speed_max.y, -1.0, "{:?}", reset_type);
+            assert_eq!(energy.speed_brake.y, 0.0, "{:?}", reset_type);
+        }
+    }
+
     #[test]
     fn layout_check() {
         assert_eq!(offset_of!(FighterKineticEnergyControl, parent), 0x0);
@@ -79,24 +282,35 @@ impl DerefMut for FighterKineticEnergyControl {
     }
 }
 
-#[skyline::hook(offset = 0x6d3610)]
-unsafe fn update(energy: &mut FighterKineticEnergyControl, boma: &mut BattleObjectModuleAccessor) {
+unsafe extern "C" fn update(energy: &mut FighterKineticEnergyControl, boma: &mut BattleObjectModuleAccessor) {
+    update_control(energy, boma)
+}
+
+unsafe extern "C" fn initialize(energy: &mut FighterKineticEnergyControl, boma: &mut BattleObjectModuleAccessor) {
+    initialize_control(energy, boma)
+}
+
+unsafe extern "C" fn setup(energy: &mut FighterKineticEnergyControl, reset_type: EnergyControllerResetType, initial_speed: &Vector3f, unk: u64, boma: &mut BattleObjectModuleAccessor) {
+    setup_control(energy, reset_type, initial_speed, unk, boma)
+}
+
+pub unsafe fn update_control<A: FighterAccessor>(energy: &mut FighterKineticEnergyControl, boma: &mut A) {
     let reset_type = std::mem::transmute(energy.energy_reset_type);
     let mut stick = Vector2f {
-        x: ControlModule::get_stick_x(boma),
-        y: ControlModule::get_stick_y(boma)
+        x: boma.get_stick_x(),
+        y: boma.get_stick_y()
     };
 
     let backup_max = energy.speed_max;
     let backup_brake = energy.speed_brake;
 
-    if WorkModule::get_float(boma, *FIGHTER_INSTANCE_WORK_ID_FLOAT_DAMAGE_REACTION_FRAME) > 0.0 {
+    if boma.get_float(*FIGHTER_INSTANCE_WORK_ID_FLOAT_DAMAGE_REACTION_FRAME) > 0.0 {
         stick.x = 0.0;
     }
 
-    let accel_add_x = if StatusModule::status_kind(boma) == *FIGHTER_STATUS_KIND_ESCAPE_AIR
-    && WorkModule::is_flag(boma, *FIGHTER_STATUS_ESCAPE_AIR_FLAG_SLIDE)
-    && !WorkModule::is_flag(boma, *FIGHTER_STATUS_ESCAPE_AIR_FLAG_SLIDE_ENABLE_CONTROL)
+    let accel_add_x = if boma.status_kind() == *FIGHTER_STATUS_KIND_ESCAPE_AIR
+    && boma.is_flag(*FIGHTER_STATUS_ESCAPE_AIR_FLAG_SLIDE)
+    && !boma.is_flag(*FIGHTER_STATUS_ESCAPE_AIR_FLAG_SLIDE_ENABLE_CONTROL)
     {
         stick.x = 0.0;
         0.0
@@ -119,11 +333,12 @@ unsafe fn update(energy: &mut FighterKineticEnergyControl, boma: &mut BattleObje
     let mut do_standard_accel = true;
 
     let accel_diff = match reset_type {
-        FallAdjust | FallAdjustNoCap | FlyAdjust | ShootDash | ShootBackDash | RevolveSlashAir | MoveGround | MoveAir => {
+        // StopCeil is the drift after bonking your head on the ceiling, which is the same as falling
+        FallAdjust | FallAdjustNoCap | StopCeil | FlyAdjust | ShootDash | ShootBackDash | RevolveSlashAir | MoveGround | MoveAir => {
             accel_add_x * stick.x.signum() + stick.x * energy.accel_mul_x
         },
         WallJump => {
-            if WorkModule::get_int(boma, *FIGHTER_STATUS_WALL_JUMP_WORK_INT_DISABLE_CONT_FRAME) == 0 {
+            if boma.get_int(*FIGHTER_STATUS_WALL_JUMP_WORK_INT_DISABLE_CONT_FRAME) == 0 {
                 accel_add_x * stick.x.signum() + stick.x * energy.accel_mul_x
             } else {
                 0.0
@@ -131,16 +346,16 @@ unsafe fn update(energy: &mut FighterKineticEnergyControl, boma: &mut BattleObje
         },
         Dash | DashBack => loop {
             // Don't apply or change the speed by any ammount during the first keep frames of dash
-            let keep_frame = WorkModule::get_param_int(boma, smash::hash40("common"), smash::hash40("dash_speed_keep_frame"));
-            if StatusModule::status_kind(boma) == *FIGHTER_STATUS_KIND_DASH || reset_type == DashBack {
-                if WorkModule::get_int(boma, *FIGHTER_STATUS_DASH_WORK_INT_COUNT) < keep_frame {
+            let keep_frame = boma.get_param_int(smash::hash40("common"), smash::hash40("dash_speed_keep_frame"));
+            if boma.status_kind() == *FIGHTER_STATUS_KIND_DASH || reset_type == DashBack {
+                if boma.get_int(*FIGHTER_STATUS_DASH_WORK_INT_COUNT) < keep_frame {
                     energy.speed_max.x = 0.0;
                     energy.speed_brake.x = 0.0;
                     stick.x = accel_add_x; // not sure if this is accurate but it's what I think I saw in the code
                     break 0.0;
                 }
-            } else if StatusModule::status_kind(boma) == *FIGHTER_STATUS_KIND_TURN_DASH {
-                if WorkModule::get_int(boma, *FIGHTER_STATUS_DASH_WORK_INT_TURN_DASH_FROM_DASH_COUNT) < keep_frame {
+            } else if boma.status_kind() == *FIGHTER_STATUS_KIND_TURN_DASH {
+                if boma.get_int(*FIGHTER_STATUS_DASH_WORK_INT_TURN_DASH_FROM_DASH_COUNT) < keep_frame {
                     energy.speed_max.x = 0.0;
                     energy.speed_brake.x = 0.0;
                     stick.x = accel_add_x;
@@ -148,7 +363,7 @@ unsafe fn update(energy: &mut FighterKineticEnergyControl, boma: &mut BattleObje
                 }
             }
 
-            let direction = -PostureModule::lr(boma);
+            let direction = -boma.lr();
             let direction = if reset_type != DashBack {
                 -direction
             } else {
@@ -167,13 +382,11 @@ unsafe fn update(energy: &mut FighterKineticEnergyControl, boma: &mut BattleObje
         },
         TurnRun => {
             let mut mul = stick.x * energy.accel_mul_x + accel_add_x * stick.x.signum();
-            let mut brake = WorkModule::get_param_float(boma, smash::hash40("ground_brake"), 0)
-                                    * WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("run_brake_brake_mul"));
+            let mut brake = boma.get_param_float(smash::hash40("ground_brake"), 0)
+                                    * boma.get_param_float(smash::hash40("common"), smash::hash40("run_brake_brake_mul"));
             
-            let ground_module = *(boma as *const BattleObjectModuleAccessor as *const u64).add(0x88 / 0x8);
-            let some_float = *(ground_module as *const f32).add(0x130 / 0x8);
-            if some_float * energy.lr <= -0.1 {
-                let turn_run_brake = WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("turn_run_stop_brake_mul"));
+            if boma.unk_motion_float() * energy.lr <= -0.1 {
+                let turn_run_brake = boma.get_param_float(smash::hash40("common"), smash::hash40("turn_run_stop_brake_mul"));
                 mul *= turn_run_brake;
                 brake *= turn_run_brake;
             }
@@ -188,68 +401,93 @@ unsafe fn update(energy: &mut FighterKineticEnergyControl, boma: &mut BattleObje
                 mul
             }
         },
-        Free => {
+        // FreeTest is the debug version of Free, it moves exactly the same
+        Free | FreeTest => {
             change_y = accel_add_y * stick.y.signum() + stick.y * energy.accel_mul_y;
             energy.speed_max.y = stick.y * energy.speed_max.y;
             accel_add_x * stick.x.signum() + stick.x * energy.accel_mul_x
         },
         ItemLift => loop {
-            if WorkModule::is_flag(boma, *FIGHTER_STATUS_ITEM_LIFT_WORK_FLAG_STOP) {
+            if boma.is_flag(*FIGHTER_STATUS_ITEM_LIFT_WORK_FLAG_STOP) {
                 stick.x = 0.0;
                 break 0.0;
             }
 
-            if accel_add_x * PostureModule::lr(boma) <= 0.0 {
+            if accel_add_x * boma.lr() <= 0.0 {
                 energy.speed_max.x = 0.0;
                 energy.speed_brake.x = 0.0;
                 stick.x = accel_add_x; // not sure if this is accurate but it's what I think I saw in the code
                 break 0.0;
             }
 
-            let stick_rate = WorkModule::get_float(boma, *FIGHTER_STATUS_ITEM_LIFT_WORK_FLOAT_STICK_RATE);
+            let stick_rate = boma.get_float(*FIGHTER_STATUS_ITEM_LIFT_WORK_FLOAT_STICK_RATE);
             energy.speed_max.x *= stick_rate;
             break (accel_add_x * stick.x.signum() + stick.x * energy.accel_mul_x) * stick_rate;
         },
-        Swim => {
-            let speed_mul = WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("swim_speed_mul"));
+        SwimRise | Swim => {
+            let speed_mul = boma.get_param_float(smash::hash40("common"), smash::hash40("swim_speed_mul"));
             energy.speed_max.x = stick.x.abs() * speed_mul;
-            energy.speed_max.y = -1.0;
+
+            // The game leaves the vertical speed alone here, when enabled we instead either push the fighter up towards
+            // the surface or let the stick steer them
+            let params = &crate::config::get().swim;
+            if params.enabled {
+                let (accel_y, speed_max_y) = if reset_type == SwimRise {
+                    params.rise_vertical()
+                } else {
+                    params.swim_vertical(stick.y)
+                };
+                change_y = accel_y;
+                energy.speed_max.y = speed_max_y;
+            } else {
+                energy.speed_max.y = -1.0;
+            }
             accel_add_x * stick.x.signum() + stick.x * energy.accel_mul_x
         },
         SwimDrown => {
-            let speed_mul = WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("swim_drown_speed_x_mul"))
-                                    * WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("swim_speed_mul")); 
+            let speed_mul = boma.get_param_float(smash::hash40("common"), smash::hash40("swim_drown_speed_x_mul"))
+                                    * boma.get_param_float(smash::hash40("common"), smash::hash40("swim_speed_mul")); 
             energy.speed_max.x = stick.x * speed_mul;
-            energy.speed_max.y = -1.0;
+
+            let params = &crate::config::get().swim;
+            if params.enabled {
+                let (accel_y, speed_max_y) = params.drown_vertical();
+                change_y = accel_y;
+                energy.speed_max.y = speed_max_y;
+            } else {
+                energy.speed_max.y = -1.0;
+            }
             accel_add_x * stick.x.signum() + stick.x * energy.accel_mul_x
         },
         Turn | TurnNoStop | TurnNoStopAir => {
             if reset_type == TurnNoStop || reset_type == TurnNoStopAir {
-                if (!TurnModule::is_turn(boma) || energy.accel_mul_x == 0.0) && energy.speed.x == 0.0 {
+                if (!boma.is_turn() || energy.accel_mul_x == 0.0) && energy.speed.x == 0.0 {
                     energy.parent.enable = false;
                     return;
                 }
-                if ControlModule::reverse_x_frame(boma) != 0 {
+                if boma.reverse_x_frame() != 0 {
                     stick.x = -stick.x;
                 }
             }
 
-            (accel_add_x * stick.x.signum() + stick.x * energy.accel_mul_x) * WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("turn_speed_mul"))
+            (accel_add_x * stick.x.signum() + stick.x * energy.accel_mul_x) * boma.get_param_float(smash::hash40("common"), smash::hash40("turn_speed_mul"))
         },
         Ladder => {
-            let ladder_y = WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("ladder_stick_y"));
+            let ladder_y = boma.get_param_float(smash::hash40("common"), smash::hash40("ladder_stick_y"));
+            // a ladder_stick_y of 1 leaves nothing to lerp over, so only a full tilt climbs and it climbs at full speed
+            let tilt = |stick: f32| if ladder_y < 1.0 { (stick - ladder_y) / (1.0 - ladder_y) } else { 1.0 };
             let (speed_max, accel_y) = if ladder_y <= stick.y.abs() {
                 if stick.y <= 0.0 {
-                    let down_max = WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("ladder_speed_d_max"));
+                    let down_max = boma.get_param_float(smash::hash40("common"), smash::hash40("ladder_speed_d_max"));
                     // lerp the down_max
-                    let down_max = ((stick.y.abs() - ladder_y) / (1.0 - ladder_y)) * down_max;
-                    let attack_mul = WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("ladder_attack_speed_mul"));
+                    let down_max = tilt(stick.y.abs()) * down_max;
+                    let attack_mul = boma.get_param_float(smash::hash40("common"), smash::hash40("ladder_attack_speed_mul"));
                     (down_max * attack_mul, -down_max * attack_mul)
                 } else {
-                    let up_max = WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("ladder_speed_d_max"));
+                    let up_max = boma.get_param_float(smash::hash40("common"), smash::hash40("ladder_speed_d_max"));
                     // lerp the down_max
-                    let up_max = ((stick.y - ladder_y) / (1.0 - ladder_y)) * up_max;
-                    let attack_mul = WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("ladder_attack_speed_mul"));
+                    let up_max = tilt(stick.y) * up_max;
+                    let attack_mul = boma.get_param_float(smash::hash40("common"), smash::hash40("ladder_attack_speed_mul"));
                     (up_max * attack_mul, up_max * attack_mul)
                 }
             } else {
@@ -262,11 +500,18 @@ unsafe fn update(energy: &mut FighterKineticEnergyControl, boma: &mut BattleObje
             energy.speed_max.y = speed_max;
             0.0
         }
-        _ => 0.0
     };
 
     if do_standard_accel {
-        energy.accel.x = dbg!(accel_diff);
+        energy_log!(
+            Level::Trace,
+            Context::new(Module::Control).with_reset_type(reset_type as u32).with_entry_id(boma.entry_id()),
+            "accel_diff: {}", accel_diff
+        );
+        energy.accel.x = accel_diff;
+        // This is only different from the previous accel for Free and the swim reset types, every other reset type leaves
+        // the vertical accel alone
+        energy.accel.y = change_y;
         energy.speed_max.x *= stick.x.abs();
     
         if energy.unk[1] != 0 {
@@ -281,27 +526,46 @@ unsafe fn update(energy: &mut FighterKineticEnergyControl, boma: &mut BattleObje
         }
     }
 
-    energy.process(boma);
+    // Slopes only change the grounded movement that the stick drives, see EnergyConfig::slope
+    let slope = &crate::config::get().slope;
+    if slope.enabled && matches!(reset_type, Dash | DashBack | TurnRun | MoveGround) {
+        let direction = if energy.speed.x != 0.0 { energy.speed.x } else { boma.lr() };
+        let slope = slope.modifiers(boma.ground_normal(), direction);
+        if 0.0 < energy.speed_max.x {
+            energy.speed_max.x *= slope.speed_max_mul;
+        }
+        energy.accel.x += slope.accel_add;
+        energy.speed_brake.x *= slope.brake_mul;
+    }
+
+    // Every grounded reset type is affected by the material it stands on, see EnergyConfig::traction
+    let traction = &crate::config::get().traction;
+    if !traction.is_empty() && boma.situation_kind() == *SITUATION_KIND_GROUND {
+        let traction = traction.get(boma.ground_material());
+        energy.accel.x *= traction.accel_mul;
+        energy.speed_brake.x *= traction.brake_mul;
+    }
+
+    boma.process_energy(energy);
 
-    let status_module = *(boma as *const BattleObjectModuleAccessor as *const u64).add(0x8);
-    if !*(status_module as *const bool).add(0x12a) {
-        if StatusModule::situation_kind(boma) == *SITUATION_KIND_AIR {
-            let horizontal_limit = WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("common_air_speed_x_limit"));
+    if !boma.is_ignore_common_speed_limit() {
+        if boma.situation_kind() == *SITUATION_KIND_AIR {
+            let horizontal_limit = boma.get_param_float(smash::hash40("common"), smash::hash40("common_air_speed_x_limit"));
             let vertical_limit = if energy.speed.y <= 0.0 {
-                WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("air_speed_down_limit"))
+                boma.get_param_float(smash::hash40("common"), smash::hash40("air_speed_down_limit"))
             } else {
-                WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("air_speed_up_limit"))
+                boma.get_param_float(smash::hash40("common"), smash::hash40("air_speed_up_limit"))
             };
 
             if horizontal_limit < energy.speed.x.abs() {
-                energy.speed.x = vertical_limit * energy.speed.x.signum();
+                energy.speed.x = horizontal_limit * energy.speed.x.signum();
             }
 
             if vertical_limit < energy.speed.y.abs() {
                 energy.speed.y = vertical_limit * energy.speed.y.signum();
             }
-        } else if StatusModule::situation_kind(boma) == *SITUATION_KIND_GROUND {
-            let speed_limit = WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("ground_speed_limit"));
+        } else if boma.situation_kind() == *SITUATION_KIND_GROUND {
+            let speed_limit = boma.get_param_float(smash::hash40("common"), smash::hash40("ground_speed_limit"));
             if speed_limit < energy.speed.x.abs() {
                 energy.speed.x = speed_limit * energy.speed.x.signum();
             }
@@ -312,94 +576,100 @@ unsafe fn update(energy: &mut FighterKineticEnergyControl, boma: &mut BattleObje
     energy.speed_brake = backup_brake;
 }
 
-#[skyline::hook(offset = 0x6d4040)]
-unsafe fn initialize(energy: &mut FighterKineticEnergyControl, boma: &mut BattleObjectModuleAccessor) {
+pub unsafe fn initialize_control<A: FighterAccessor>(energy: &mut FighterKineticEnergyControl, boma: &mut A) {
     use EnergyControllerResetType::*;
     let reset_type = std::mem::transmute(energy.energy_reset_type);
+    if let Some(handler) = handlers::find(boma.fighter_kind(), boma.status_kind()) {
+        if handler.initialize_control(energy, reset_type, boma) {
+            return;
+        }
+    }
+
     match reset_type {
         FallAdjust | FallAdjustNoCap | StopCeil | WallJump => {
-            let mut stable_speed = WorkModule::get_param_float(boma, smash::hash40("air_speed_x_stable"), 0);
+            let mut stable_speed = boma.get_param_float(smash::hash40("air_speed_x_stable"), 0);
             if reset_type == StopCeil {
-                stable_speed *= WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("stop_ceil_speed_x_stable_mul"));
+                stable_speed *= boma.get_param_float(smash::hash40("common"), smash::hash40("stop_ceil_speed_x_stable_mul"));
             }
 
             energy.speed_max = PaddedVec2::new(stable_speed, -1.0);
-            energy.speed_brake = PaddedVec2::new(WorkModule::get_param_float(boma, smash::hash40("air_brake_x"), 0), 0.0);
-            let air_x_speed_max = if !WorkModule::is_flag(boma, *FIGHTER_INSTANCE_WORK_ID_FLAG_JUMP_NO_LIMIT) && energy.unk[2] == 0 {
-                WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("air_speed_x_limit"))
+            energy.speed_brake = PaddedVec2::new(boma.get_param_float(smash::hash40("air_brake_x"), 0), 0.0);
+            let air_x_speed_max = if !boma.is_flag(*FIGHTER_INSTANCE_WORK_ID_FLAG_JUMP_NO_LIMIT) && energy.unk[2] == 0 {
+                boma.get_param_float(smash::hash40("common"), smash::hash40("air_speed_x_limit"))
             } else {
                 -1.0
             };
             energy.speed_limit = PaddedVec2::new(air_x_speed_max, 0.0);
-            energy.accel_mul_x = WorkModule::get_param_float(boma, smash::hash40("air_accel_x_mul"), 0);
-            energy.accel_add_x = WorkModule::get_param_float(boma, smash::hash40("air_accel_x_add"), 0);
+            energy.accel_mul_x = boma.get_param_float(smash::hash40("air_accel_x_mul"), 0);
+            energy.accel_add_x = boma.get_param_float(smash::hash40("air_accel_x_add"), 0);
         },
-        FlyAdjust => {
+        // FlyAdjust is free horizontal drift, without any of the jump related speed caps from FallAdjust.
+        // RevolveSlashAir is limited further by the fighters that use it, see handlers::link
+        FlyAdjust | MoveAir | RevolveSlashAir => {
+            energy.speed_max = PaddedVec2::new(
+                boma.get_param_float(smash::hash40("air_speed_x_stable"), 0),
+                -1.0
+            );
+            energy.speed_limit = PaddedVec2::new(
+                boma.get_param_float(smash::hash40("common"), smash::hash40("air_speed_x_limit")),
+                0.0
+            );
+            energy.speed_brake = PaddedVec2::new(
+                boma.get_param_float(smash::hash40("air_brake_x"), 0),
+                0.0
+            );
+            energy.accel_mul_x = boma.get_param_float(smash::hash40("air_accel_x_mul"), 0);
+            energy.accel_add_x = boma.get_param_float(smash::hash40("air_accel_x_add"), 0);
         },
         Dash | TurnRun | DashBack => {
             energy.speed_limit = PaddedVec2::new(
-                WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("ground_speed_limit")),
+                boma.get_param_float(smash::hash40("common"), smash::hash40("ground_speed_limit")),
                 0.0
             );
             energy.speed_max = PaddedVec2::new(
-                WorkModule::get_param_float(boma, smash::hash40("run_speed_max"), 0),
+                boma.get_param_float(smash::hash40("run_speed_max"), 0),
                 -1.0
             );
-            let brake = WorkModule::get_param_float(boma, smash::hash40("ground_brake"), 0)
-                                * WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("run_brake_brake_mul"));
+            let brake = boma.get_param_float(smash::hash40("ground_brake"), 0)
+                                * boma.get_param_float(smash::hash40("common"), smash::hash40("run_brake_brake_mul"));
             energy.speed_brake = PaddedVec2::new(brake, 0.0);
-            energy.accel_mul_x = WorkModule::get_param_float(boma, smash::hash40("run_accel_mul"), 0);
-            energy.accel_add_x = WorkModule::get_param_float(boma, smash::hash40("run_accel_add"), 0);
+            energy.accel_mul_x = boma.get_param_float(smash::hash40("run_accel_mul"), 0);
+            energy.accel_add_x = boma.get_param_float(smash::hash40("run_accel_add"), 0);
         },
         ShootDash | ShootBackDash => {
             energy.speed_limit = PaddedVec2::new(
-                WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("ground_speed_limit")),
+                boma.get_param_float(smash::hash40("common"), smash::hash40("ground_speed_limit")),
                 0.0
             );
             energy.speed_max = PaddedVec2::new(
-                WorkModule::get_param_float(boma, smash::hash40("run_speed_max"), 0),
+                boma.get_param_float(smash::hash40("run_speed_max"), 0),
                 -1.0
             );
-            let brake = WorkModule::get_param_float(boma, smash::hash40("ground_brake"), 0)
-                                * WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("run_brake_brake_mul"));
+            let brake = boma.get_param_float(smash::hash40("ground_brake"), 0)
+                                * boma.get_param_float(smash::hash40("common"), smash::hash40("run_brake_brake_mul"));
             energy.speed_brake = PaddedVec2::new(brake, 0.0);
         },
-        RevolveSlashAir => {
-            let speed_max = WorkModule::get_param_float(boma, smash::hash40("air_speed_x_stable"), 0)
-                                    * WorkModule::get_param_float(boma, smash::hash40("param_special_hi"), smash::hash40("rslash_air_max_x_mul"));
-
-            energy.speed_max = PaddedVec2::new(speed_max, -1.0);
-            energy.speed_brake = PaddedVec2::new(
-                WorkModule::get_param_float(boma, smash::hash40("air_brake_x"), 0),
-                0.0
-            );
-            energy.speed_limit = PaddedVec2::new(
-                WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("air_speed_x_limit")),
-                0.0
-            );
-            energy.accel_mul_x = WorkModule::get_param_float(boma, smash::hash40("param_special_hi"), smash::hash40("rslash_air_max_x_mul"));
-        },
         Turn | TurnNoStop => {
             energy.speed_max = PaddedVec2::new(
-                WorkModule::get_param_float(boma, smash::hash40("walk_speed_max"), 0),
+                boma.get_param_float(smash::hash40("walk_speed_max"), 0),
                 -1.0
             );
             energy.speed_limit = PaddedVec2::new(
-                WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("ground_speed_limit")),
+                boma.get_param_float(smash::hash40("common"), smash::hash40("ground_speed_limit")),
                 0.0
             );
-            let brake = WorkModule::get_param_float(boma, smash::hash40("ground_brake"), 0)
-                                * WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("run_brake_brake_mul"));
+            let brake = boma.get_param_float(smash::hash40("ground_brake"), 0)
+                                * boma.get_param_float(smash::hash40("common"), smash::hash40("run_brake_brake_mul"));
             energy.speed_brake = PaddedVec2::new(brake, 0.0);
-            energy.accel_mul_x = WorkModule::get_param_float(boma, smash::hash40("walk_accel_mul"), 0);
-            energy.accel_add_x = WorkModule::get_param_float(boma, smash::hash40("walk_accel_add"), 0);
+            energy.accel_mul_x = boma.get_param_float(smash::hash40("walk_accel_mul"), 0);
+            energy.accel_add_x = boma.get_param_float(smash::hash40("walk_accel_add"), 0);
         },
-        Free => {
-            let speed_max = WorkModule::get_param_float(boma, smash::hash40("air_speed_x_stable"), 0);
-            let speed_brake = WorkModule::get_param_float(boma, smash::hash40("air_brake_x"), 0);
-            let speed_limit = WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("air_speed_x_limit"));
-            let mul = WorkModule::get_param_float(boma, smash::hash40("air_accel_x_mul"), 0);
-            let add = WorkModule::get_param_float(boma, smash::hash40("air_accel_x_add"), 0);
+        Free | FreeTest => {
+            let speed_max = boma.get_param_float(smash::hash40("air_speed_x_stable"), 0);
+            let speed_brake = boma.get_param_float(smash::hash40("air_brake_x"), 0);
+            let speed_limit = boma.get_param_float(smash::hash40("common"), smash::hash40("air_speed_x_limit"));
+            let mul = boma.get_param_float(smash::hash40("air_accel_x_mul"), 0);
+            let add = boma.get_param_float(smash::hash40("air_accel_x_add"), 0);
             energy.speed_max = PaddedVec2::new(speed_max, speed_max);
             energy.speed_brake = PaddedVec2::new(speed_brake, speed_brake);
             energy.speed_limit = PaddedVec2::new(speed_limit, speed_limit);
@@ -409,63 +679,83 @@ unsafe fn initialize(energy: &mut FighterKineticEnergyControl, boma: &mut Battle
             energy.accel_add_y = add;
         },
         ItemLift => {
-            let scale = PostureModule::scale(boma);
+            let scale = boma.scale();
             energy.speed_max = PaddedVec2::new(
-                scale * WorkModule::get_param_float(boma, smash::hash40("item_lift_speed_max"), 0),
+                scale * boma.get_param_float(smash::hash40("item_lift_speed_max"), 0),
                 -1.0
             );
             energy.speed_limit = PaddedVec2::new(
-                scale * WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("ground_speed_limit")),
+                scale * boma.get_param_float(smash::hash40("common"), smash::hash40("ground_speed_limit")),
                 0.0
             );
-            let brake = WorkModule::get_param_float(boma, smash::hash40("ground_brake"), 0)
-                                * WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("run_brake_brake_mul"));
+            let brake = boma.get_param_float(smash::hash40("ground_brake"), 0)
+                                * boma.get_param_float(smash::hash40("common"), smash::hash40("run_brake_brake_mul"));
             energy.speed_brake = PaddedVec2::new(brake, 0.0);
-            energy.accel_mul_x = scale * WorkModule::get_param_float(boma, smash::hash40("item_lift_accel_mul"), 0);
-            energy.accel_add_x = scale * WorkModule::get_param_float(boma, smash::hash40("item_lift_accel_add"), 0);
+            energy.accel_mul_x = scale * boma.get_param_float(smash::hash40("item_lift_accel_mul"), 0);
+            energy.accel_add_x = scale * boma.get_param_float(smash::hash40("item_lift_accel_add"), 0);
         },
-        Swim => {
+        SwimRise | Swim => {
+            let params = &crate::config::get().swim;
             energy.speed_brake = PaddedVec2::new(
-                WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("swim_brake")),
-                0.0
+                boma.get_param_float(smash::hash40("common"), smash::hash40("swim_brake")),
+                params.vertical_brake()
             );
-            energy.accel_mul_x = WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("swim_accel_mul"));
+            energy.accel_mul_x = boma.get_param_float(smash::hash40("common"), smash::hash40("swim_accel_mul"));
+            if reset_type == SwimRise && params.enabled {
+                energy.accel_mul_x *= params.rise_accel_x_mul;
+            }
         },
         SwimDrown => {
+            let params = &crate::config::get().swim;
             energy.speed_brake = PaddedVec2::new(
-                WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("swim_brake")),
+                boma.get_param_float(smash::hash40("common"), smash::hash40("swim_brake")) * params.drown_brake_mul,
+                params.vertical_brake()
+            );
+            energy.accel_mul_x = boma.get_param_float(smash::hash40("common"), smash::hash40("swim_accel_mul"))
+                                    * boma.get_param_float(smash::hash40("common"), smash::hash40("swim_drown_speed_x_mul"));
+        },
+        // Walking speed, with the normal ground brake since it isn't coming out of a run
+        MoveGround => {
+            energy.speed_max = PaddedVec2::new(
+                boma.get_param_float(smash::hash40("walk_speed_max"), 0),
+                -1.0
+            );
+            energy.speed_limit = PaddedVec2::new(
+                boma.get_param_float(smash::hash40("common"), smash::hash40("ground_speed_limit")),
                 0.0
             );
-            energy.accel_mul_x = WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("swim_accel_mul"))
-                                    * WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("swim_drown_speed_x_mul"));
+            energy.speed_brake = PaddedVec2::new(
+                boma.get_param_float(smash::hash40("ground_brake"), 0),
+                0.0
+            );
+            energy.accel_mul_x = boma.get_param_float(smash::hash40("walk_accel_mul"), 0);
+            energy.accel_add_x = boma.get_param_float(smash::hash40("walk_accel_add"), 0);
         },
         TurnNoStopAir => {
             energy.speed_max = PaddedVec2::new(
-                WorkModule::get_param_float(boma, smash::hash40("air_speed_x_stable"), 0),
+                boma.get_param_float(smash::hash40("air_speed_x_stable"), 0),
                 -1.0
             );
             energy.speed_limit = PaddedVec2::new(
-                WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("air_speed_x_limit")),
+                boma.get_param_float(smash::hash40("common"), smash::hash40("air_speed_x_limit")),
                 0.0
             );
             energy.speed_brake = PaddedVec2::new(
-                WorkModule::get_param_float(boma, smash::hash40("air_brake_x"), 0),
+                boma.get_param_float(smash::hash40("air_brake_x"), 0),
                 0.0
             );
-            energy.accel_mul_x = WorkModule::get_param_float(boma, smash::hash40("air_accel_x_mul"), 0);
-            energy.accel_add_x = WorkModule::get_param_float(boma, smash::hash40("air_accel_x_add"), 0);
+            energy.accel_mul_x = boma.get_param_float(smash::hash40("air_accel_x_mul"), 0);
+            energy.accel_add_x = boma.get_param_float(smash::hash40("air_accel_x_add"), 0);
         },
         Ladder => {
-            let up_speed = WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("ladder_speed_u_max"));
-            let down_speed = WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("ladder_speed_d_max"));
+            let up_speed = boma.get_param_float(smash::hash40("common"), smash::hash40("ladder_speed_u_max"));
+            let down_speed = boma.get_param_float(smash::hash40("common"), smash::hash40("ladder_speed_d_max"));
             energy.speed_brake = PaddedVec2::new(0.0, up_speed.max(down_speed));
         }
-        _ => {}
     }
 }
 
-#[skyline::hook(offset = 0x6d4ba0)]
-unsafe fn setup(energy: &mut FighterKineticEnergyControl, reset_type: EnergyControllerResetType, initial_speed: &Vector3f, unk: u64, boma: &mut BattleObjectModuleAccessor) {
+pub unsafe fn setup_control<A: FighterAccessor>(energy: &mut FighterKineticEnergyControl, reset_type: EnergyControllerResetType, initial_speed: &Vector3f, unk: u64, boma: &mut A) {
     energy.clear_energy();
 
     energy.accel = PaddedVec2::zeros();
@@ -478,76 +768,86 @@ unsafe fn setup(energy: &mut FighterKineticEnergyControl, reset_type: EnergyCont
     energy.accel_add_x = 0.0;
     energy.accel_mul_y = 0.0;
     energy.accel_add_y = 0.0;
-    energy.lr = PostureModule::lr(boma);
+    energy.lr = boma.lr();
     energy.unk[3] = 1;
     
     use EnergyControllerResetType::*;
-    match reset_type {
-        FallAdjust | FallAdjustNoCap | StopCeil | WallJump => {
-            energy.unk[2] = if WorkModule::is_flag(boma, *FIGHTER_INSTANCE_WORK_ID_FLAG_JUMP_NO_LIMIT_ONCE) {
-                1
-            } else {
-                0
-            };
-            if reset_type != FallAdjustNoCap
-            && !WorkModule::is_flag(boma, *FIGHTER_INSTANCE_WORK_ID_FLAG_JUMP_NO_LIMIT)
-            && energy.unk[2] == 0 {
-                let stable_speed = WorkModule::get_param_float(boma, smash::hash40("air_speed_x_stable"), 0);
-                if stable_speed < energy.speed.x.abs() {
-                    energy.speed = PaddedVec2::new(stable_speed * energy.speed.x.signum(), 0.0);
+    let handled = match handlers::find(boma.fighter_kind(), boma.status_kind()) {
+        Some(handler) => handler.setup_control(energy, reset_type, boma),
+        None => false
+    };
+
+    if !handled {
+        match reset_type {
+            FallAdjust | FallAdjustNoCap | StopCeil | WallJump => {
+                energy.unk[2] = if boma.is_flag(*FIGHTER_INSTANCE_WORK_ID_FLAG_JUMP_NO_LIMIT_ONCE) {
+                    1
+                } else {
+                    0
+                };
+                if reset_type != FallAdjustNoCap
+                && !boma.is_flag(*FIGHTER_INSTANCE_WORK_ID_FLAG_JUMP_NO_LIMIT)
+                && energy.unk[2] == 0 {
+                    let stable_speed = boma.get_param_float(smash::hash40("air_speed_x_stable"), 0);
+                    if stable_speed < energy.speed.x.abs() {
+                        energy.speed = PaddedVec2::new(stable_speed * energy.speed.x.signum(), 0.0);
+                    }
                 }
-            }
-            WorkModule::off_flag(boma, *FIGHTER_INSTANCE_WORK_ID_FLAG_JUMP_NO_LIMIT_ONCE);
-        },
-        FlyAdjust => {
-            println!("fly adjust");
-        }, // not reached in game afaik
-        Dash | TurnRun | DashBack => {
-            let dash_speed = if reset_type == DashBack {
-                -energy.lr * WorkModule::get_param_float(boma, smash::hash40("dash_speed"), 0)
-            } else {
-                energy.lr * WorkModule::get_param_float(boma, smash::hash40("dash_speed"), 0)
-            };
-            energy.speed.x = if 0.0 <= energy.speed.x * energy.lr {
-                dash_speed
-            } else {
-                dash_speed + energy.speed.x
-            };
-        },
-        ShootDash => {
-            energy.speed.x = if 0.0 >= energy.speed.x * energy.lr {
-                -energy.lr * WorkModule::get_param_float(boma, smash::hash40("shoot_dash_speed_f"), 0)
-            } else {
-                energy.speed.x - energy.lr * WorkModule::get_param_float(boma, smash::hash40("shoot_dash_speed_f"), 0)
-            };
-        },
-        ShootBackDash => {
-            energy.speed.x = if 0.0 <= energy.speed.x * energy.lr {
-                energy.lr * WorkModule::get_param_float(boma, smash::hash40("shoot_dash_speed_b"), 0)
-            } else {
-                energy.speed.x + energy.lr * WorkModule::get_param_float(boma, smash::hash40("shoot_dash_speed_b"), 0)
-            };
-        },
-        RevolveSlashAir => {
-            energy.speed.x *= WorkModule::get_param_float(boma, smash::hash40("rslash_air_spd_x_mul"), 0);
-        },
-        Free => {
-            energy.speed = PaddedVec2::zeros();
-        },
-        MoveGround => {
-            let new_speed = super::energy::KineticEnergy::adjust_speed_for_ground_normal(&energy.speed, boma);
-            energy.speed = new_speed;
-        }        
-        _ => {}
+                boma.off_flag(*FIGHTER_INSTANCE_WORK_ID_FLAG_JUMP_NO_LIMIT_ONCE);
+            },
+            Dash | TurnRun | DashBack => {
+                let dash_speed = if reset_type == DashBack {
+                    -energy.lr * boma.get_param_float(smash::hash40("dash_speed"), 0)
+                } else {
+                    energy.lr * boma.get_param_float(smash::hash40("dash_speed"), 0)
+                };
+                energy.speed.x = if 0.0 <= energy.speed.x * energy.lr {
+                    dash_speed
+                } else {
+                    dash_speed + energy.speed.x
+                };
+            },
+            ShootDash => {
+                energy.speed.x = if 0.0 >= energy.speed.x * energy.lr {
+                    -energy.lr * boma.get_param_float(smash::hash40("shoot_dash_speed_f"), 0)
+                } else {
+                    energy.speed.x - energy.lr * boma.get_param_float(smash::hash40("shoot_dash_speed_f"), 0)
+                };
+            },
+            ShootBackDash => {
+                energy.speed.x = if 0.0 <= energy.speed.x * energy.lr {
+                    energy.lr * boma.get_param_float(smash::hash40("shoot_dash_speed_b"), 0)
+                } else {
+                    energy.speed.x + energy.lr * boma.get_param_float(smash::hash40("shoot_dash_speed_b"), 0)
+                };
+            },
+            Free | FreeTest => {
+                energy.speed = PaddedVec2::zeros();
+            },
+            MoveGround => {
+                let new_speed = boma.adjust_speed_for_ground_normal(&energy.speed);
+                energy.speed = new_speed;
+            },
+            // FlyAdjust isn't reached in game afaik, but like the rest of these it keeps whatever speed it was given
+            FlyAdjust | Turn | ItemLift | SwimRise | Swim | SwimDrown | MoveAir | TurnNoStop | TurnNoStopAir | Ladder | RevolveSlashAir => {}
+        }
     }
 
-    energy.initialize(boma);
+    // Our initialize hook replaces the one the vtable points to, so call it directly
+    initialize_control(energy, boma);
 }
 
 pub fn install() {
-    skyline::install_hooks!(
-        update,
-        initialize,
-        setup
-    );
+    use crate::offsets::{self, Function::*};
+
+    if !offsets::all_resolved(&[UpdateControl, InitializeControl, SetupControl, ProcessEnergy, AdjustSpeedForGroundNormal]) {
+        energy_log!(Level::Error, Context::new(Module::Control), "Not installing the control energy hooks");
+        return;
+    }
+
+    unsafe {
+        offsets::install_hook(UpdateControl, update as *const ());
+        offsets::install_hook(InitializeControl, initialize as *const ());
+        offsets::install_hook(SetupControl, setup as *const ());
+    }
 }
\ No newline at end of file
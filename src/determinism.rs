@@ -0,0 +1,372 @@
+//! Per-frame fingerprints of every fighter's energies, for catching desyncs between two machines.
+//!
+//! The ruleset calls `energy_id_frame_hash` once per frame with its fighters and logs the line that comes back, then
+//! tools/energy_hash_diff compares the logs of the two machines and reports the first frame that differs. The hash is
+//! worked out from the raw fields without allocating, `energy_id_frame_record` also writes out every field so that the
+//! first one that differs can be found, and is meant for when a desync is being tracked down. See hash_log for the format.
+//!
+//! Every byte that a snapshot captures is hashed, the parts of the structs that haven't been named yet (and the padding
+//! of the vectors) as 32 bit words, so two fighters with the same hash restore to the same energies. The state that is
+//! kept for the fighter outside of its energies (see fighter_state) is hashed with them
+
+use smash::app::{lua_bind::*, BattleObjectModuleAccessor};
+use smash::lib::lua_const::*;
+
+use super::{
+    control::FighterKineticEnergyControl,
+    energy::{KineticEnergy, PaddedVec2},
+    fighter_state::{self, FighterState},
+    glide::GlideState,
+    hash_log::{self, FieldSink, FrameHasher, FrameRecord},
+    motion::FighterKineticEnergyMotion,
+    root_motion::RootMotionModifier,
+    stop::FighterKineticEnergyStop
+};
+
+/// Names the fields of one energy and hands them to a sink
+pub struct Fields<'a> {
+    prefix: &'a [&'a str],
+    sink: &'a mut dyn FieldSink,
+}
+
+impl<'a> Fields<'a> {
+    pub fn new(prefix: &'a [&'a str], sink: &'a mut dyn FieldSink) -> Self {
+        Self {
+            prefix,
+            sink
+        }
+    }
+
+    fn field(&mut self, name: &[&str], value: u32) {
+        self.sink.field(self.prefix, name, value);
+    }
+
+    fn f32(&mut self, name: &str, value: f32) {
+        self.sink.field(self.prefix, &[name], value.to_bits());
+    }
+
+    fn u32(&mut self, name: &str, value: u32) {
+        self.sink.field(self.prefix, &[name], value);
+    }
+
+    fn bool(&mut self, name: &str, value: bool) {
+        self.u32(name, value as u32);
+    }
+
+    fn vec(&mut self, name: &str, vec: PaddedVec2) {
+        self.sink.field(self.prefix, &[name, "x"], vec.x.to_bits());
+        self.sink.field(self.prefix, &[name, "y"], vec.y.to_bits());
+        self.words(&[name, "padding"], &vec.padding.to_le_bytes());
+    }
+
+    /// Adds raw bytes as 32 bit words named by their index, the last one is padded with zeros
+    /// # Arguments
+    /// * `name` - The parts of the name, at most 3 of them
+    /// * `bytes` - At most 16 bytes
+    fn words(&mut self, name: &[&str], bytes: &[u8]) {
+        const INDICES: [&str; 4] = ["0", "1", "2", "3"];
+
+        let mut parts = [""; 4];
+        parts[..name.len()].copy_from_slice(name);
+        for (index, word) in bytes.chunks(4).enumerate() {
+            let mut padded = [0; 4];
+            padded[..word.len()].copy_from_slice(word);
+            parts[name.len()] = INDICES[index];
+            self.sink.field(self.prefix, &parts[..=name.len()], u32::from_le_bytes(padded));
+        }
+    }
+}
+
+/// Adds the fields of an energy to a frame
+pub trait Fingerprint {
+    fn fingerprint(&self, fields: &mut Fields);
+}
+
+impl Fingerprint for KineticEnergy {
+    fn fingerprint(&self, fields: &mut Fields) {
+        fields.words(&["_x8"], &self._x8.to_le_bytes());
+        fields.vec("speed", self.speed);
+        fields.vec("rot_speed", self.rot_speed);
+        fields.bool("enable", self.enable);
+        fields.words(&["unk2"], &self.unk2);
+        fields.vec("accel", self.accel);
+        fields.vec("speed_max", self.speed_max);
+        fields.vec("speed_brake", self.speed_brake);
+        fields.vec("speed_limit", self.speed_limit);
+        fields.u32("_x80", self._x80 as u32);
+        fields.bool("consider_ground_friction", self.consider_ground_friction);
+        fields.bool("active_flag", self.active_flag);
+        fields.u32("_x83", self._x83 as u32);
+        fields.u32("reset_type", self.energy_reset_type);
+    }
+}
+
+impl Fingerprint for FighterKineticEnergyControl {
+    fn fingerprint(&self, fields: &mut Fields) {
+        (**self).fingerprint(fields);
+        fields.f32("lr", self.lr);
+        fields.f32("accel_mul_x", self.accel_mul_x);
+        fields.f32("accel_add_x", self.accel_add_x);
+        fields.f32("accel_mul_y", self.accel_mul_y);
+        fields.f32("accel_add_y", self.accel_add_y);
+        fields.f32("_x9c", self._x9c);
+        fields.f32("_xa0", self._xa0);
+        fields.u32("unk", u32::from_le_bytes(self.unk));
+    }
+}
+
+impl Fingerprint for FighterKineticEnergyMotion {
+    fn fingerprint(&self, fields: &mut Fields) {
+        (**self).fingerprint(fields);
+        fields.f32("lr", self.lr);
+        fields.f32("angle", self.angle);
+        fields.f32("angle_whole", self.angle_whole);
+        fields.f32("angle_intp_end", self.angle_intp_end);
+        fields.u32("angle_intp_frames_remaining", self.angle_intp_frames_remaining as u32);
+        fields.f32("speed_mul", self.speed_mul);
+        fields.vec("prev_speed", self.prev_speed);
+        fields.vec("speed_mul_2nd", self.speed_mul_2nd);
+        fields.bool("update_flag", self.update_flag);
+
+        // the rest of the struct hasn't been named, but the snapshot still captures it
+        let tail = memoffset::offset_of!(FighterKineticEnergyMotion, update_flag) + 1;
+        let bytes = unsafe {
+            std::slice::from_raw_parts(self as *const Self as *const u8, std::mem::size_of::<Self>())
+        };
+        fields.words(&["_tail"], &bytes[tail..]);
+    }
+}
+
+impl Fingerprint for FighterKineticEnergyStop {
+    fn fingerprint(&self, fields: &mut Fields) {
+        (**self).fingerprint(fields);
+        fields.words(&["padding"], &self.padding.to_le_bytes());
+        fields.vec("damage_target_speed", self.damage_target_speed);
+        fields.u32("stop_reset_type", self.reset_type as u32);
+        fields.f32("elapsed_hitstop_frames", self.elapsed_hitstop_frames);
+        fields.f32("hitstop_frames", self.hitstop_frames);
+        fields.f32("_xAC", self._xAC);
+        fields.f32("_xB0", self._xB0);
+        fields.bool("should_sync_damage_speed", self.should_sync_damage_speed);
+        fields.bool("needs_to_sync_damage_speed", self.needs_to_sync_damage_speed);
+        fields.bool("should_start_interpolation", self.should_start_interpolation);
+        fields.u32("interpolation_frames_remaining", self.interpolation_frames_remaining as u32);
+        fields.u32("_xB8", self._xB8 as u32);
+        fields.bool("is_target_pos", self.is_target_pos);
+        fields.bool("_xBA", self._xBA);
+        fields.bool("_xBB", self._xBB);
+        fields.u32("_xBC", self._xBC);
+        fields.vec("_xC0", self._xC0);
+    }
+}
+
+impl Fingerprint for FighterState {
+    fn fingerprint(&self, fields: &mut Fields) {
+        // the parts that aren't in use are hashed as zeros, so that every frame has the same fields
+        let glide = self.glide.unwrap_or(GlideState { angle: 0.0, speed: 0.0 });
+        fields.bool("has_glide", self.glide.is_some());
+        fields.f32("glide_angle", glide.angle);
+        fields.f32("glide_speed", glide.speed);
+        fields.bool("has_tether_caught_speed", self.tether_caught_speed.is_some());
+        fields.vec("tether_caught_speed", self.tether_caught_speed.unwrap_or_else(PaddedVec2::zeros));
+        fields.bool("has_decay", self.decay.is_some());
+        fields.vec("decay", self.decay.unwrap_or_else(PaddedVec2::zeros));
+
+        let (status_kind, modifier) = self.root_motion.unwrap_or((0, RootMotionModifier::IDENTITY));
+        fields.bool("has_root_motion", self.root_motion.is_some());
+        fields.u32("root_motion_status_kind", status_kind as u32);
+        for (name, axis) in [("root_motion_x", modifier.x), ("root_motion_y", modifier.y)].iter() {
+            fields.field(&[name, "mul"], axis.mul.to_bits());
+            fields.field(&[name, "offset"], axis.offset.to_bits());
+            fields.field(&[name, "min"], axis.min.to_bits());
+            fields.field(&[name, "max"], axis.max.to_bits());
+        }
+    }
+}
+
+/// Writes a number into `buffer` without allocating, for the prefix of a fighter's fields
+fn write_entry_id(buffer: &mut [u8; 12], entry_id: i32) -> &str {
+    use std::io::Write;
+
+    let mut cursor = std::io::Cursor::new(&mut buffer[..]);
+    // an i32 is at most 11 characters, so this always fits
+    let _ = write!(cursor, "{}", entry_id);
+    let len = cursor.position() as usize;
+    std::str::from_utf8(&buffer[..len]).unwrap_or("")
+}
+
+/// Adds every energy of a fighter and its state to a frame, prefixed with its entry id
+pub unsafe fn fingerprint_fighter(boma: &mut BattleObjectModuleAccessor, sink: &mut dyn FieldSink) {
+    let mut buffer = [0; 12];
+    let entry_id = write_entry_id(&mut buffer, WorkModule::get_int(boma, *FIGHTER_INSTANCE_WORK_ID_INT_ENTRY_ID));
+    let energy = |boma: &mut BattleObjectModuleAccessor, id: i32| KineticModule::get_energy(boma, id) as *mut KineticEnergy;
+
+    let control = energy(boma, *FIGHTER_KINETIC_ENERGY_ID_CONTROL);
+    if !control.is_null() {
+        (*(control as *const FighterKineticEnergyControl)).fingerprint(&mut Fields::new(&[entry_id, "control"], sink));
+    }
+    let motion = energy(boma, *FIGHTER_KINETIC_ENERGY_ID_MOTION);
+    if !motion.is_null() {
+        (*(motion as *const FighterKineticEnergyMotion)).fingerprint(&mut Fields::new(&[entry_id, "motion"], sink));
+    }
+    let stop = energy(boma, *FIGHTER_KINETIC_ENERGY_ID_STOP);
+    if !stop.is_null() {
+        (*(stop as *const FighterKineticEnergyStop)).fingerprint(&mut Fields::new(&[entry_id, "stop"], sink));
+    }
+    for (id, name) in [
+        (*FIGHTER_KINETIC_ENERGY_ID_GRAVITY, "gravity"),
+        (*FIGHTER_KINETIC_ENERGY_ID_DAMAGE, "damage"),
+        (*FIGHTER_KINETIC_ENERGY_ID_JOSTLE, "jostle"),
+    ].iter() {
+        let energy = energy(boma, *id);
+        if !energy.is_null() {
+            (*energy).fingerprint(&mut Fields::new(&[entry_id, *name], sink));
+        }
+    }
+    fighter_state::get(boma).fingerprint(&mut Fields::new(&[entry_id, "state"], sink));
+}
+
+/// Hashes the energies of every fighter on a frame and returns its log line, which only has the hash. The fighters
+/// should be passed in the same order every frame on both machines
+#[no_mangle]
+pub extern "Rust" fn energy_id_frame_hash(frame: u64, fighters: &mut [&mut BattleObjectModuleAccessor]) -> String {
+    let mut hasher = FrameHasher::new();
+    for boma in fighters.iter_mut() {
+        unsafe {
+            fingerprint_fighter(boma, &mut hasher);
+        }
+    }
+    hash_log::hash_line(frame, hasher.finish())
+}
+
+/// Builds the record of a frame and returns its log line with every field, which has the same hash as
+/// `energy_id_frame_hash`
+#[no_mangle]
+pub extern "Rust" fn energy_id_frame_record(frame: u64, fighters: &mut [&mut BattleObjectModuleAccessor]) -> String {
+    let mut record = FrameRecord::new(frame);
+    for boma in fighters.iter_mut() {
+        unsafe {
+            fingerprint_fighter(boma, &mut record);
+        }
+    }
+    record.to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        control::{test::control_energy, EnergyControllerResetType},
+        fighter::stub::*,
+        stop::{test::stop_energy, EnergyStopResetType}
+    };
+
+    fn hash<E: Fingerprint>(energy: &E) -> u64 {
+        let mut hasher = FrameHasher::new();
+        energy.fingerprint(&mut Fields::new(&["0", "energy"], &mut hasher));
+        hasher.finish()
+    }
+
+    #[test]
+    fn every_field_changes_the_hash() {
+        let mut energy = control_energy(EnergyControllerResetType::Dash);
+        let base = hash(&energy);
+        assert_eq!(hash(&energy), base);
+
+        energy.speed_brake.y = 0.5;
+        let braked = hash(&energy);
+        assert_ne!(braked, base);
+
+        energy._xa0 = 1.0;
+        let changed = hash(&energy);
+        assert_ne!(changed, braked);
+
+        energy.unk[3] = 1;
+        assert_ne!(hash(&energy), changed);
+
+        // -0.0 and 0.0 compare equal but aren't the same bits, which can still be a desync
+        let mut energy = control_energy(EnergyControllerResetType::Dash);
+        energy.speed.x = -0.0;
+        assert_ne!(hash(&energy), base);
+    }
+
+    #[test]
+    fn unnamed_bytes_change_the_hash() {
+        let mut energy = stop_energy(EnergyStopResetType::DamageKnockBack);
+        let mut previous = hash(&energy);
+        let setters: [fn(&mut FighterKineticEnergyStop); 8] = [
+            |energy: &mut FighterKineticEnergyStop| energy._x8 = 1 << 40,
+            |energy: &mut FighterKineticEnergyStop| energy.unk2[14] = 1,
+            |energy: &mut FighterKineticEnergyStop| energy._x80 = 1,
+            |energy: &mut FighterKineticEnergyStop| energy._x83 = 1,
+            |energy: &mut FighterKineticEnergyStop| energy.speed.padding = 1,
+            |energy: &mut FighterKineticEnergyStop| energy.padding = 1,
+            |energy: &mut FighterKineticEnergyStop| energy._xBC = 1,
+            |energy: &mut FighterKineticEnergyStop| energy._xC0.y = 1.0,
+        ];
+        for set in setters.iter() {
+            set(&mut energy);
+            let changed = hash(&energy);
+            assert_ne!(changed, previous);
+            previous = changed;
+        }
+    }
+
+    #[test]
+    fn fighter_state_changes_the_hash() {
+        let mut state = FighterState::default();
+        let base = hash(&state);
+        state.decay = Some(PaddedVec2::zeros());
+        let decaying = hash(&state);
+        assert_ne!(decaying, base);
+        state.root_motion = Some((0, RootMotionModifier::IDENTITY));
+        assert_ne!(hash(&state), decaying);
+
+        let mut record = FrameRecord::new(0);
+        FighterState::default().fingerprint(&mut Fields::new(&["0", "state"], &mut record));
+        assert!(record.fields.iter().any(|(name, _)| name == "0.state.root_motion_y.max"));
+    }
+
+    #[test]
+    fn stop_flags_change_the_hash() {
+        let mut energy = stop_energy(EnergyStopResetType::DamageKnockBack);
+        let mut previous = hash(&energy);
+        let setters: [fn(&mut FighterKineticEnergyStop); 4] = [
+            |energy: &mut FighterKineticEnergyStop| energy.should_sync_damage_speed = true,
+            |energy: &mut FighterKineticEnergyStop| energy.needs_to_sync_damage_speed = true,
+            |energy: &mut FighterKineticEnergyStop| energy.should_start_interpolation = true,
+            |energy: &mut FighterKineticEnergyStop| energy.is_target_pos = true,
+        ];
+        for set in setters.iter() {
+            set(&mut energy);
+            let changed = hash(&energy);
+            assert_ne!(changed, previous);
+            previous = changed;
+        }
+    }
+
+    #[test]
+    fn hash_matches_record() {
+        let energy = stop_energy(EnergyStopResetType::DamageKnockBack);
+        let mut record = FrameRecord::new(0);
+        energy.fingerprint(&mut Fields::new(&["0", "energy"], &mut record));
+        assert_eq!(hash(&energy), record.hash());
+    }
+
+    #[test]
+    fn base_fields() {
+        let mut record = FrameRecord::new(0);
+        stub_energy().fingerprint(&mut Fields::new(&["1", "gravity"], &mut record));
+        assert!(record.fields.iter().any(|(name, _)| name == "1.gravity.speed_limit.y"));
+        assert!(record.fields.iter().any(|(name, _)| name == "1.gravity.reset_type"));
+        assert!(record.fields.iter().any(|(name, _)| name == "1.gravity.unk2.3"));
+        assert!(record.fields.iter().any(|(name, _)| name == "1.gravity.speed.padding.1"));
+    }
+
+    #[test]
+    fn entry_ids_are_written_in_place() {
+        let mut buffer = [0; 12];
+        assert_eq!(write_entry_id(&mut buffer, 3), "3");
+        assert_eq!(write_entry_id(&mut buffer, i32::MIN), "-2147483648");
+    }
+}
@@ -1,3 +1,5 @@
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
 use smash::{
     app::{
         *,
@@ -30,7 +32,7 @@ pub struct KineticEnergyVTable {
 
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 #[repr(C)]
 pub struct PaddedVec2 {
     pub x: f32,
@@ -58,6 +60,152 @@ impl PaddedVec2 {
     pub fn mag(&self) -> f32 {
         (self.x.powi(2) + self.y.powi(2)).sqrt()
     }
+
+    pub fn dot(&self, other: Self) -> f32 {
+        self.x * other.x + self.y * other.y
+    }
+
+    /// Rotates the vector counter-clockwise
+    /// # Arguments
+    /// * `angle` - The angle to rotate by, in radians
+    pub fn rotate(&self, angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self::new(
+            self.x * cos - self.y * sin,
+            self.y * cos + self.x * sin
+        )
+    }
+
+    /// Gets the vector with a magnitude of 1, or zeros if the vector has no magnitude
+    pub fn normalize(&self) -> Self {
+        let mag = self.mag();
+        if mag == 0.0 {
+            Self::zeros()
+        } else {
+            *self / mag
+        }
+    }
+
+    /// Linearly interpolates between this vector (`t` = 0) and `other` (`t` = 1)
+    pub fn lerp(&self, other: Self, t: f32) -> Self {
+        *self + (other - *self) * t
+    }
+
+    /// Scales the vector down so that its magnitude is at most `max`
+    pub fn clamp_magnitude(&self, max: f32) -> Self {
+        let mag = self.mag();
+        if mag > max {
+            *self * (max / mag)
+        } else {
+            *self
+        }
+    }
+
+    pub fn is_finite(&self) -> bool {
+        self.x.is_finite() && self.y.is_finite()
+    }
+
+    /// Multiplies each component by the matching component of `other`
+    pub fn component_mul(&self, other: Self) -> Self {
+        Self::new(self.x * other.x, self.y * other.y)
+    }
+
+    pub fn signum(&self) -> Self {
+        Self::new(self.x.signum(), self.y.signum())
+    }
+
+    pub fn abs(&self) -> Self {
+        Self::new(self.x.abs(), self.y.abs())
+    }
+}
+
+impl Add for PaddedVec2 {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for PaddedVec2 {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Mul<f32> for PaddedVec2 {
+    type Output = Self;
+
+    fn mul(self, scalar: f32) -> Self {
+        Self::new(self.x * scalar, self.y * scalar)
+    }
+}
+
+impl Div<f32> for PaddedVec2 {
+    type Output = Self;
+
+    fn div(self, scalar: f32) -> Self {
+        Self::new(self.x / scalar, self.y / scalar)
+    }
+}
+
+impl Neg for PaddedVec2 {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+impl AddAssign for PaddedVec2 {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for PaddedVec2 {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl MulAssign<f32> for PaddedVec2 {
+    fn mul_assign(&mut self, scalar: f32) {
+        *self = *self * scalar;
+    }
+}
+
+impl DivAssign<f32> for PaddedVec2 {
+    fn div_assign(&mut self, scalar: f32) {
+        *self = *self / scalar;
+    }
+}
+
+impl From<Vector2f> for PaddedVec2 {
+    fn from(vec: Vector2f) -> Self {
+        Self::new(vec.x, vec.y)
+    }
+}
+
+/// Drops the z component
+impl From<Vector3f> for PaddedVec2 {
+    fn from(vec: Vector3f) -> Self {
+        Self::new(vec.x, vec.y)
+    }
+}
+
+impl From<PaddedVec2> for Vector2f {
+    fn from(vec: PaddedVec2) -> Self {
+        Vector2f { x: vec.x, y: vec.y }
+    }
+}
+
+impl From<PaddedVec2> for Vector3f {
+    fn from(vec: PaddedVec2) -> Self {
+        Vector3f { x: vec.x, y: vec.y, z: 0.0 }
+    }
 }
 
 #[repr(C)]
@@ -79,32 +227,6 @@ pub struct KineticEnergy {
     pub energy_reset_type: u32,
 }
 
-mod test {
-    use super::KineticEnergy;
-
-    use memoffset::offset_of;
-
-    #[test]
-    fn layout_check() {
-        assert_eq!(offset_of!(KineticEnergy, vtable), 0x0);
-        assert_eq!(offset_of!(KineticEnergy, _x8), 0x8);
-        assert_eq!(offset_of!(KineticEnergy, speed), 0x10);
-        assert_eq!(offset_of!(KineticEnergy, unk), 0x20);
-        assert_eq!(offset_of!(KineticEnergy, accel), 0x40);
-        assert_eq!(offset_of!(KineticEnergy, speed_max), 0x50);
-        assert_eq!(offset_of!(KineticEnergy, speed_brake), 0x60);
-        assert_eq!(offset_of!(KineticEnergy, speed_limit), 0x70);
-        assert_eq!(offset_of!(KineticEnergy, _x80), 0x80);
-        assert_eq!(offset_of!(KineticEnergy, energy_reset_type), 0x84);
-    }
-}
-
-#[repr(simd)]
-pub struct Vec2 {
-    pub x: f32,
-    pub y: f32
-}
-
 #[repr(simd)]
 pub struct Vec3 {
     pub x: f32,
@@ -122,21 +244,28 @@ pub struct Vec4 {
 }
 
 impl KineticEnergy {
+    /// Projects the speed onto the ground that the fighter is standing on. This uses the game's function unless
+    /// `EnergyConfig::rust_ground_projection` is set
     pub fn adjust_speed_for_ground_normal(speed: &PaddedVec2, boma: &mut BattleObjectModuleAccessor) -> PaddedVec2 {
-        #[skyline::from_offset(0x47b4d0)]        
-        extern "C" fn adjust_speed_for_ground_normal_internal(speed: Vec2, boma: &mut BattleObjectModuleAccessor) -> Vec2;
+        if crate::config::get().rust_ground_projection {
+            return match ground_normal(boma) {
+                Some(normal) => project_onto_ground(*speed, normal),
+                None => *speed
+            };
+        }
 
         unsafe {
-            let result = adjust_speed_for_ground_normal_internal(Vec2 { x: speed.x, y: speed.y }, boma);
+            let adjust_speed_for_ground_normal: extern "C" fn(Vec2, &mut BattleObjectModuleAccessor) -> Vec2 =
+                std::mem::transmute(crate::offsets::required(crate::offsets::Function::AdjustSpeedForGroundNormal));
+            let result = adjust_speed_for_ground_normal(Vec2 { x: speed.x, y: speed.y }, boma);
             PaddedVec2::new(result.x, result.y)
         }
     }
 
     pub fn process(&mut self, boma: &mut BattleObjectModuleAccessor) {
         unsafe {
-            #[skyline::from_offset(0x47bf70)]
-            extern "C" fn process_energy(energy: &mut KineticEnergy, boma: &mut BattleObjectModuleAccessor);
-
+            let process_energy: extern "C" fn(&mut KineticEnergy, &mut BattleObjectModuleAccessor) =
+                std::mem::transmute(crate::offsets::required(crate::offsets::Function::ProcessEnergy));
             process_energy(self, boma)
         }
     }
@@ -201,4 +330,169 @@ impl KineticEnergy {
         }
     }
 
-}
\ No newline at end of file
+}
+
+/// Gets the normal of the ground below the fighter, if it is touching any
+pub fn ground_normal(boma: &mut BattleObjectModuleAccessor) -> Option<PaddedVec2> {
+    unsafe {
+        if !GroundModule::is_touch(boma, *GROUND_TOUCH_FLAG_DOWN as u32) {
+            return None;
+        }
+        Some(PaddedVec2::from(GroundModule::get_touch_normal(boma, *GROUND_TOUCH_FLAG_DOWN as u32)))
+    }
+}
+
+/// Gets the material of the ground below the fighter, if it is touching any
+pub fn ground_material(boma: &mut BattleObjectModuleAccessor) -> Option<i32> {
+    unsafe {
+        if !GroundModule::is_touch(boma, *GROUND_TOUCH_FLAG_DOWN as u32) {
+            return None;
+        }
+        Some(GroundModule::get_touch_material_type(boma, *GROUND_TOUCH_FLAG_DOWN as u32))
+    }
+}
+
+/// Projects a grounded speed onto the ground's surface, by removing the part of it that goes into or out of the ground.
+/// A normal that can't be normalized, or that points downwards, leaves the speed alone.
+///
+/// This is a stand-in for the game's adjust_speed_for_ground_normal, which hasn't been compared against it. It's only
+/// used by the host tests, and in game when `EnergyConfig::rust_ground_projection` is set
+/// # Arguments
+/// * `speed` - The speed of the energy
+/// * `normal` - The normal of the ground, pointing out of it
+pub fn project_onto_ground(speed: PaddedVec2, normal: PaddedVec2) -> PaddedVec2 {
+    let normal = normal.normalize();
+    if normal == PaddedVec2::zeros() || normal.y <= 0.0 {
+        return speed;
+    }
+
+    speed - normal * speed.dot(normal)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use memoffset::offset_of;
+
+    fn assert_close(actual: PaddedVec2, expected: PaddedVec2) {
+        assert!((actual.x - expected.x).abs() < 1.0e-5 && (actual.y - expected.y).abs() < 1.0e-5, "{:?} != {:?}", actual, expected);
+    }
+
+    #[test]
+    fn layout_check() {
+        assert_eq!(offset_of!(KineticEnergy, vtable), 0x0);
+        assert_eq!(offset_of!(KineticEnergy, _x8), 0x8);
+        assert_eq!(offset_of!(KineticEnergy, speed), 0x10);
+        assert_eq!(offset_of!(KineticEnergy, rot_speed), 0x20);
+        assert_eq!(offset_of!(KineticEnergy, accel), 0x40);
+        assert_eq!(offset_of!(KineticEnergy, speed_max), 0x50);
+        assert_eq!(offset_of!(KineticEnergy, speed_brake), 0x60);
+        assert_eq!(offset_of!(KineticEnergy, speed_limit), 0x70);
+        assert_eq!(offset_of!(KineticEnergy, _x80), 0x80);
+        assert_eq!(offset_of!(KineticEnergy, energy_reset_type), 0x84);
+    }
+
+    #[test]
+    fn operators() {
+        let a = PaddedVec2::new(1.0, 2.0);
+        let b = PaddedVec2::new(3.0, -1.0);
+        assert_eq!(a + b, PaddedVec2::new(4.0, 1.0));
+        assert_eq!(a - b, PaddedVec2::new(-2.0, 3.0));
+        assert_eq!(a * 2.0, PaddedVec2::new(2.0, 4.0));
+        assert_eq!(a / 2.0, PaddedVec2::new(0.5, 1.0));
+        assert_eq!(-a, PaddedVec2::new(-1.0, -2.0));
+
+        let mut c = a;
+        c += b;
+        c -= a;
+        c *= 2.0;
+        c /= 4.0;
+        assert_eq!(c, PaddedVec2::new(1.5, -0.5));
+    }
+
+    #[test]
+    fn rotate() {
+        let vec = PaddedVec2::new(1.0, 0.0);
+        assert_close(vec.rotate(std::f32::consts::FRAC_PI_2), PaddedVec2::new(0.0, 1.0));
+        assert_close(vec.rotate(std::f32::consts::PI), PaddedVec2::new(-1.0, 0.0));
+
+        // both components have to be rotated from the original vector
+        let vec = PaddedVec2::new(1.0, 1.0);
+        assert_close(vec.rotate(std::f32::consts::FRAC_PI_2), PaddedVec2::new(-1.0, 1.0));
+        assert!((vec.rotate(0.7).mag() - vec.mag()).abs() < 1.0e-5);
+    }
+
+    #[test]
+    fn normalize() {
+        assert_close(PaddedVec2::new(3.0, 4.0).normalize(), PaddedVec2::new(0.6, 0.8));
+        assert_eq!(PaddedVec2::zeros().normalize(), PaddedVec2::zeros());
+    }
+
+    #[test]
+    fn dot() {
+        assert_eq!(PaddedVec2::new(1.0, 2.0).dot(PaddedVec2::new(3.0, 4.0)), 11.0);
+        assert_eq!(PaddedVec2::new(1.0, 0.0).dot(PaddedVec2::new(0.0, 1.0)), 0.0);
+    }
+
+    #[test]
+    fn lerp() {
+        let a = PaddedVec2::new(0.0, 10.0);
+        let b = PaddedVec2::new(10.0, 0.0);
+        assert_eq!(a.lerp(b, 0.0), a);
+        assert_eq!(a.lerp(b, 1.0), b);
+        assert_eq!(a.lerp(b, 0.5), PaddedVec2::new(5.0, 5.0));
+    }
+
+    #[test]
+    fn clamp_magnitude() {
+        assert_close(PaddedVec2::new(6.0, 8.0).clamp_magnitude(5.0), PaddedVec2::new(3.0, 4.0));
+        assert_eq!(PaddedVec2::new(0.3, 0.4).clamp_magnitude(5.0), PaddedVec2::new(0.3, 0.4));
+    }
+
+    #[test]
+    fn per_axis() {
+        let vec = PaddedVec2::new(-2.0, 3.0);
+        assert_eq!(vec.signum(), PaddedVec2::new(-1.0, 1.0));
+        assert_eq!(vec.abs(), PaddedVec2::new(2.0, 3.0));
+        assert_eq!(vec.component_mul(PaddedVec2::new(2.0, -1.0)), PaddedVec2::new(-4.0, -3.0));
+    }
+
+    #[test]
+    fn ground_projection() {
+        let speed = PaddedVec2::new(2.0, 0.5);
+        assert_eq!(project_onto_ground(speed, PaddedVec2::new(0.0, 1.0)), PaddedVec2::new(2.0, 0.0));
+
+        // 45 degree slope rising to the right, its normal leans left
+        let normal = PaddedVec2::new(-1.0, 1.0);
+        let projected = project_onto_ground(speed, normal);
+        assert_close(projected, PaddedVec2::new(1.25, 1.25));
+        assert!(projected.dot(normal).abs() < 1.0e-5);
+        assert_close(project_onto_ground(-speed, normal), PaddedVec2::new(-1.25, -1.25));
+
+        // projecting twice changes nothing
+        assert_close(project_onto_ground(projected, normal), projected);
+
+        // the length of the normal doesn't matter
+        assert_close(project_onto_ground(speed, normal * 10.0), projected);
+    }
+
+    #[test]
+    fn ground_projection_ignores_bad_normals() {
+        let speed = PaddedVec2::new(2.0, 0.5);
+        assert_eq!(project_onto_ground(speed, PaddedVec2::zeros()), speed);
+        assert_eq!(project_onto_ground(speed, PaddedVec2::new(0.0, -1.0)), speed);
+    }
+
+    #[test]
+    fn conversions() {
+        let vec = PaddedVec2::from(Vector3f { x: 1.0, y: 2.0, z: 3.0 });
+        assert_eq!(vec, PaddedVec2::new(1.0, 2.0));
+        assert_eq!(PaddedVec2::from(Vector2f { x: 1.0, y: 2.0 }), vec);
+
+        let vec2: Vector2f = vec.into();
+        assert_eq!((vec2.x, vec2.y), (1.0, 2.0));
+        let vec3: Vector3f = vec.into();
+        assert_eq!((vec3.x, vec3.y, vec3.z), (1.0, 2.0, 0.0));
+    }
+}
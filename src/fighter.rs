@@ -0,0 +1,745 @@
+use super::{
+    *,
+    energy::*,
+    modules::ModuleAccessorExt
+};
+
+/// The parts of the fighter that the energies read from and write to.
+///
+/// This is implemented for the BattleObjectModuleAccessor, which just forwards to the game, and lets the energy logic
+/// be driven by a stubbed fighter in tests. The names mirror the lua_bind functions they forward to
+pub trait FighterAccessor {
+    fn get_param_float(&mut self, obj: u64, field: u64) -> f32;
+    fn get_param_int(&mut self, obj: u64, field: u64) -> i32;
+    fn get_float(&mut self, id: i32) -> f32;
+    fn get_int(&mut self, id: i32) -> i32;
+    fn is_flag(&mut self, id: i32) -> bool;
+    fn off_flag(&mut self, id: i32);
+    fn get_stick_x(&mut self) -> f32;
+    fn get_stick_y(&mut self) -> f32;
+    fn reverse_x_frame(&mut self) -> i32;
+    fn is_turn(&mut self) -> bool;
+    fn lr(&mut self) -> f32;
+    fn scale(&mut self) -> f32;
+    fn fighter_kind(&mut self) -> i32;
+    fn entry_id(&mut self) -> i32;
+    fn status_kind(&mut self) -> i32;
+    fn situation_kind(&mut self) -> i32;
+
+    fn is_damage(&mut self) -> bool;
+    fn is_touch_pos(&mut self, touch_flag: u32) -> bool;
+    fn is_link(&mut self, link_no: i32) -> bool;
+    fn get_parent_sum_speed(&mut self, link_no: i32, arg: i32) -> PaddedVec2;
+    fn motion_frame(&mut self) -> f32;
+    fn is_main_motion_updating_energy(&mut self) -> bool;
+
+    fn is_motion_2nd_updating_energy(&mut self) -> bool;
+    fn motion_rate(&mut self) -> f32;
+    fn add_float(&mut self, value: f32, id: i32);
+
+    /// Updates and gets the trans move speed of the main motion
+    fn trans_move_speed(&mut self) -> Vector3f;
+
+    /// Updates and gets the trans move speed of the 2nd motion
+    fn trans_move_speed_2nd(&mut self) -> Vector3f;
+
+    /// Gets the translation of the current motion frame, used when getting on and off ladders
+    fn trans_tra(&mut self) -> Vector3f;
+
+    /// Gets the movement of the current cliff motion, after it went through the game's cliff handling
+    fn cliff_trans_speed(&mut self) -> PaddedVec2;
+
+    /// Checks if there is a ledge within `distance` in front of the fighter
+    fn is_near_ledge(&mut self, distance: f32) -> bool;
+
+    /// Adds to the speed of the control energy, returns false if the fighter has no enabled control energy
+    fn add_control_speed(&mut self, speed: PaddedVec2) -> bool;
+
+    /// Gets how far the fighter has to be pushed out of the attacker's jostle area after being hit on the ground,
+    /// and the hitstop frames of the hit that did it
+    fn damage_knock_back_push_out(&mut self) -> Option<(f32, i32)>;
+
+    /// Gets the position that whoever is grabbing the fighter holds them at, relative to the fighter
+    fn capture_offset(&mut self) -> Option<PaddedVec2>;
+
+    /// Gets the position of the ledge that the fighter's tether is attached to, relative to the fighter
+    fn tether_anchor(&mut self) -> Option<PaddedVec2>;
+
+    /// The float at MotionModule + 0x98, read by TurnRun to check if the turn is being held against. The offset is
+    /// unverified, see `ModuleAccessorExt::motion_unk_float`
+    fn unk_motion_float(&mut self) -> f32;
+
+    /// The flag at StatusModule + 0x12a, when this is set the common speed limits are not applied after processing
+    fn is_ignore_common_speed_limit(&mut self) -> bool;
+
+    /// Runs the game's processing of the energy's accel, max speed, brake and limit
+    fn process_energy(&mut self, energy: &mut KineticEnergy);
+
+    /// Gets the normal of the ground below the fighter, if it is touching any
+    fn ground_normal(&mut self) -> Option<PaddedVec2>;
+
+    /// Gets the material of the ground below the fighter, if it is touching any
+    fn ground_material(&mut self) -> Option<i32>;
+
+    /// Projects the speed onto the ground that the fighter is standing on, fighters without the game's function to do it
+    /// use `project_onto_ground` in its place
+    fn adjust_speed_for_ground_normal(&mut self, speed: &PaddedVec2) -> PaddedVec2 {
+        match self.ground_normal() {
+            Some(normal) => crate::energy::project_onto_ground(*speed, normal),
+            None => *speed
+        }
+    }
+
+    /// Gets the game's module accessor of the fighter, handlers registered by other plugins can only be called with one
+    fn module_accessor(&mut self) -> Option<&mut BattleObjectModuleAccessor> {
+        None
+    }
+}
+
+impl FighterAccessor for BattleObjectModuleAccessor {
+    fn get_param_float(&mut self, obj: u64, field: u64) -> f32 {
+        unsafe {
+            WorkModule::get_param_float(self, obj, field)
+        }
+    }
+
+    fn get_param_int(&mut self, obj: u64, field: u64) -> i32 {
+        unsafe {
+            WorkModule::get_param_int(self, obj, field)
+        }
+    }
+
+    fn get_float(&mut self, id: i32) -> f32 {
+        unsafe {
+            WorkModule::get_float(self, id)
+        }
+    }
+
+    fn get_int(&mut self, id: i32) -> i32 {
+        unsafe {
+            WorkModule::get_int(self, id)
+        }
+    }
+
+    fn is_flag(&mut self, id: i32) -> bool {
+        unsafe {
+            WorkModule::is_flag(self, id)
+        }
+    }
+
+    fn off_flag(&mut self, id: i32) {
+        unsafe {
+            WorkModule::off_flag(self, id)
+        }
+    }
+
+    fn get_stick_x(&mut self) -> f32 {
+        unsafe {
+            ControlModule::get_stick_x(self)
+        }
+    }
+
+    fn get_stick_y(&mut self) -> f32 {
+        unsafe {
+            ControlModule::get_stick_y(self)
+        }
+    }
+
+    fn reverse_x_frame(&mut self) -> i32 {
+        unsafe {
+            ControlModule::reverse_x_frame(self)
+        }
+    }
+
+    fn is_turn(&mut self) -> bool {
+        unsafe {
+            TurnModule::is_turn(self)
+        }
+    }
+
+    fn lr(&mut self) -> f32 {
+        unsafe {
+            PostureModule::lr(self)
+        }
+    }
+
+    fn scale(&mut self) -> f32 {
+        unsafe {
+            PostureModule::scale(self)
+        }
+    }
+
+    fn fighter_kind(&mut self) -> i32 {
+        unsafe {
+            smash::app::utility::get_kind(self)
+        }
+    }
+
+    fn entry_id(&mut self) -> i32 {
+        unsafe {
+            WorkModule::get_int(self, *FIGHTER_INSTANCE_WORK_ID_INT_ENTRY_ID)
+        }
+    }
+
+    fn status_kind(&mut self) -> i32 {
+        unsafe {
+            StatusModule::status_kind(self)
+        }
+    }
+
+    fn situation_kind(&mut self) -> i32 {
+        unsafe {
+            StatusModule::situation_kind(self)
+        }
+    }
+
+    fn is_damage(&mut self) -> bool {
+        unsafe {
+            StopModule::is_damage(self)
+        }
+    }
+
+    fn is_touch_pos(&mut self, touch_flag: u32) -> bool {
+        unsafe {
+            GroundModule::get_touch_pos(self, touch_flag) & 1 != 0
+        }
+    }
+
+    fn is_link(&mut self, link_no: i32) -> bool {
+        unsafe {
+            LinkModule::is_link(self, link_no)
+        }
+    }
+
+    fn get_parent_sum_speed(&mut self, link_no: i32, arg: i32) -> PaddedVec2 {
+        crate::stop::FighterKineticEnergyStop::get_parent_sum_speed_correct(self, link_no, arg)
+    }
+
+    fn motion_frame(&mut self) -> f32 {
+        unsafe {
+            MotionModule::frame(self)
+        }
+    }
+
+    fn is_main_motion_updating_energy(&mut self) -> bool {
+        ModuleAccessorExt::is_main_motion_updating_energy(self)
+    }
+
+    fn is_motion_2nd_updating_energy(&mut self) -> bool {
+        ModuleAccessorExt::is_motion_2nd_updating_energy(self)
+    }
+
+    fn motion_rate(&mut self) -> f32 {
+        unsafe {
+            MotionModule::rate(self)
+        }
+    }
+
+    fn add_float(&mut self, value: f32, id: i32) {
+        unsafe {
+            WorkModule::add_float(self, value, id)
+        }
+    }
+
+    fn trans_move_speed(&mut self) -> Vector3f {
+        unsafe {
+            MotionModule::update_trans_move_speed(self);
+        }
+        crate::motion::FighterKineticEnergyMotion::trans_move_speed_correct(self)
+    }
+
+    fn trans_move_speed_2nd(&mut self) -> Vector3f {
+        self.update_trans_move_speed_2nd();
+        crate::motion::FighterKineticEnergyMotion::trans_move_speed_2nd_correct(self)
+    }
+
+    fn trans_tra(&mut self) -> Vector3f {
+        let mut vec = Vector3f { x: 0.0, y: 0.0, z: 0.0 };
+        unsafe {
+            MotionModule::trans_tra(self, &mut vec, true, true);
+        }
+        vec
+    }
+
+    fn cliff_trans_speed(&mut self) -> PaddedVec2 {
+        let vec = self.cliff_trans_move();
+        let vec = unsafe {
+            crate::motion::handle_cliff(self, &vec)
+        };
+        PaddedVec2::new(vec.x, vec.y)
+    }
+
+    fn is_near_ledge(&mut self, distance: f32) -> bool {
+        unsafe {
+            GroundModule::is_ottotto(self, distance)
+        }
+    }
+
+    fn add_control_speed(&mut self, speed: PaddedVec2) -> bool {
+        unsafe {
+            let control = KineticModule::get_energy(self, *FIGHTER_KINETIC_ENERGY_ID_CONTROL) as *mut KineticEnergy;
+            if control.is_null() || !KineticModule::is_enable_energy(self, *FIGHTER_KINETIC_ENERGY_ID_CONTROL) {
+                return false;
+            }
+            (*control).speed += speed;
+        }
+        true
+    }
+
+    fn damage_knock_back_push_out(&mut self) -> Option<(f32, i32)> {
+        unsafe {
+            crate::stop::get_damage_knock_back_push_out(self)
+        }
+    }
+
+    fn capture_offset(&mut self) -> Option<PaddedVec2> {
+        unsafe {
+            crate::stop::get_capture_offset(self)
+        }
+    }
+
+    fn tether_anchor(&mut self) -> Option<PaddedVec2> {
+        unsafe {
+            crate::stop::get_tether_anchor(self)
+        }
+    }
+
+    fn unk_motion_float(&mut self) -> f32 {
+        self.motion_unk_float()
+    }
+
+    fn is_ignore_common_speed_limit(&mut self) -> bool {
+        ModuleAccessorExt::is_ignore_common_speed_limit(self)
+    }
+
+    fn process_energy(&mut self, energy: &mut KineticEnergy) {
+        energy.process(self)
+    }
+
+    fn ground_normal(&mut self) -> Option<PaddedVec2> {
+        crate::energy::ground_normal(self)
+    }
+
+    fn ground_material(&mut self) -> Option<i32> {
+        crate::energy::ground_material(self)
+    }
+
+    fn adjust_speed_for_ground_normal(&mut self, speed: &PaddedVec2) -> PaddedVec2 {
+        KineticEnergy::adjust_speed_for_ground_normal(speed, self)
+    }
+
+    fn module_accessor(&mut self) -> Option<&mut BattleObjectModuleAccessor> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+pub mod stub {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    /// A fighter that answers from plain maps instead of the game, and records every param that gets read
+    pub struct StubFighter {
+        pub params: HashMap<(u64, u64), f32>,
+        pub int_params: HashMap<(u64, u64), i32>,
+        pub floats: HashMap<i32, f32>,
+        pub ints: HashMap<i32, i32>,
+        pub flags: HashMap<i32, bool>,
+        pub stick: PaddedVec2,
+        pub reverse_x_frame: i32,
+        pub is_turn: bool,
+        pub lr: f32,
+        pub scale: f32,
+        pub fighter_kind: i32,
+        pub entry_id: i32,
+        pub status_kind: i32,
+        pub situation_kind: i32,
+        pub unk_motion_float: f32,
+        pub ignore_common_speed_limit: bool,
+        pub is_damage: bool,
+        pub touch_pos: u32,
+        pub ground_normal: Option<PaddedVec2>,
+        pub ground_material: Option<i32>,
+        pub link_speed: Option<PaddedVec2>,
+        pub motion_frame: f32,
+        pub trans_move_speed: Option<Vector3f>,
+        pub trans_move_speed_2nd: Option<Vector3f>,
+        pub motion_rate: f32,
+        pub trans_tra: Vector3f,
+        pub cliff_trans_speed: PaddedVec2,
+        /// How far in front of the fighter the ledge is, if there is one
+        pub near_ledge: Option<f32>,
+        /// The speed of the control energy, if the fighter has one
+        pub control_speed: Option<PaddedVec2>,
+        pub knock_back_push_out: Option<(f32, i32)>,
+        pub capture_offset: Option<PaddedVec2>,
+        pub tether_anchor: Option<PaddedVec2>,
+        /// When set, the params that weren't given a value are picked from this seed and the param's hashes instead of
+        /// being 0, between 0 and 10 for ints and from `random_float` for floats
+        pub random_params: Option<u64>,
+        /// Picks the random float params, between 0 and 4 unless replaced
+        pub random_float: fn(&mut Rng) -> f32,
+        pub requested_params: Vec<(u64, u64)>,
+    }
+
+    impl StubFighter {
+        pub fn new() -> Self {
+            Self {
+                params: HashMap::new(),
+                int_params: HashMap::new(),
+                floats: HashMap::new(),
+                ints: HashMap::new(),
+                flags: HashMap::new(),
+                stick: PaddedVec2::zeros(),
+                reverse_x_frame: 0,
+                is_turn: false,
+                lr: 1.0,
+                scale: 1.0,
+                fighter_kind: -1,
+                entry_id: 0,
+                status_kind: 0,
+                situation_kind: 0,
+                unk_motion_float: 0.0,
+                ignore_common_speed_limit: true,
+                is_damage: false,
+                touch_pos: 0,
+                ground_normal: None,
+                ground_material: None,
+                link_speed: None,
+                motion_frame: 0.0,
+                trans_move_speed: None,
+                trans_move_speed_2nd: None,
+                motion_rate: 1.0,
+                trans_tra: Vector3f { x: 0.0, y: 0.0, z: 0.0 },
+                cliff_trans_speed: PaddedVec2::zeros(),
+                near_ledge: None,
+                control_speed: None,
+                knock_back_push_out: None,
+                capture_offset: None,
+                tether_anchor: None,
+                random_params: None,
+                random_float: |rng| rng.range(0.0, 4.0),
+                requested_params: Vec::new(),
+            }
+        }
+
+        /// Sets a fighter param, `field` is 0 for the params that aren't part of an object
+        pub fn with_param(mut self, obj: &str, field: &str, value: f32) -> Self {
+            self.params.insert(Self::key(obj, field), value);
+            self
+        }
+
+        pub fn with_int_param(mut self, obj: &str, field: &str, value: i32) -> Self {
+            self.int_params.insert(Self::key(obj, field), value);
+            self
+        }
+
+        /// Checks whether or not a param was read since the stub was created
+        pub fn was_requested(&self, obj: &str, field: &str) -> bool {
+            self.requested_params.contains(&Self::key(obj, field))
+        }
+
+        fn random_param(&self, obj: u64, field: u64) -> Option<Rng> {
+            self.random_params.map(|seed| Rng::new(seed ^ obj ^ field.rotate_left(32)))
+        }
+
+        fn key(obj: &str, field: &str) -> (u64, u64) {
+            if field.is_empty() {
+                (smash::hash40(obj), 0)
+            } else {
+                (smash::hash40(obj), smash::hash40(field))
+            }
+        }
+    }
+
+    /// A small xorshift generator for the randomized tests, every case is seeded on its own so that a failure can be
+    /// replayed from the seed in its message
+    pub struct Rng(u64);
+
+    impl Rng {
+        pub fn new(seed: u64) -> Self {
+            // xorshift never leaves 0, and seeds that are close together would start out close together without the mix
+            Self((seed ^ 0x9e3779b97f4a7c15).wrapping_mul(0xbf58476d1ce4e5b9) | 1)
+        }
+
+        pub fn next_u64(&mut self) -> u64 {
+            self.0 ^= self.0 >> 12;
+            self.0 ^= self.0 << 25;
+            self.0 ^= self.0 >> 27;
+            self.0.wrapping_mul(0x2545f4914f6cdd1d)
+        }
+
+        /// Picks a float from `min` up to `max`
+        pub fn range(&mut self, min: f32, max: f32) -> f32 {
+            let unit = (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32;
+            min + (max - min) * unit
+        }
+
+        /// Picks an int from `min` up to, but not including, `max`
+        pub fn int(&mut self, min: i32, max: i32) -> i32 {
+            min + (self.next_u64() % (max - min) as u64) as i32
+        }
+
+        pub fn bool(&mut self) -> bool {
+            self.next_u64() & 1 == 1
+        }
+
+        pub fn vec2(&mut self, min: f32, max: f32) -> PaddedVec2 {
+            PaddedVec2::new(self.range(min, max), self.range(min, max))
+        }
+
+        pub fn pick<T: Copy>(&mut self, items: &[T]) -> T {
+            items[self.int(0, items.len() as i32) as usize]
+        }
+    }
+
+    impl FighterAccessor for StubFighter {
+        fn get_param_float(&mut self, obj: u64, field: u64) -> f32 {
+            self.requested_params.push((obj, field));
+            match self.params.get(&(obj, field)) {
+                Some(value) => *value,
+                None => self.random_param(obj, field).map_or(0.0, |mut rng| (self.random_float)(&mut rng))
+            }
+        }
+
+        fn get_param_int(&mut self, obj: u64, field: u64) -> i32 {
+            self.requested_params.push((obj, field));
+            match self.int_params.get(&(obj, field)) {
+                Some(value) => *value,
+                None => self.random_param(obj, field).map_or(0, |mut rng| rng.int(0, 10))
+            }
+        }
+
+        fn get_float(&mut self, id: i32) -> f32 {
+            self.floats.get(&id).copied().unwrap_or(0.0)
+        }
+
+        fn get_int(&mut self, id: i32) -> i32 {
+            self.ints.get(&id).copied().unwrap_or(0)
+        }
+
+        fn is_flag(&mut self, id: i32) -> bool {
+            self.flags.get(&id).copied().unwrap_or(false)
+        }
+
+        fn off_flag(&mut self, id: i32) {
+            self.flags.insert(id, false);
+        }
+
+        fn get_stick_x(&mut self) -> f32 {
+            self.stick.x
+        }
+
+        fn get_stick_y(&mut self) -> f32 {
+            self.stick.y
+        }
+
+        fn reverse_x_frame(&mut self) -> i32 {
+            self.reverse_x_frame
+        }
+
+        fn is_turn(&mut self) -> bool {
+            self.is_turn
+        }
+
+        fn lr(&mut self) -> f32 {
+            self.lr
+        }
+
+        fn scale(&mut self) -> f32 {
+            self.scale
+        }
+
+        fn fighter_kind(&mut self) -> i32 {
+            self.fighter_kind
+        }
+
+        fn entry_id(&mut self) -> i32 {
+            self.entry_id
+        }
+
+        fn status_kind(&mut self) -> i32 {
+            self.status_kind
+        }
+
+        fn situation_kind(&mut self) -> i32 {
+            self.situation_kind
+        }
+
+        fn is_damage(&mut self) -> bool {
+            self.is_damage
+        }
+
+        fn is_touch_pos(&mut self, touch_flag: u32) -> bool {
+            self.touch_pos & touch_flag != 0
+        }
+
+        fn is_link(&mut self, _link_no: i32) -> bool {
+            self.link_speed.is_some()
+        }
+
+        fn get_parent_sum_speed(&mut self, _link_no: i32, _arg: i32) -> PaddedVec2 {
+            self.link_speed.unwrap_or_else(PaddedVec2::zeros)
+        }
+
+        fn motion_frame(&mut self) -> f32 {
+            self.motion_frame
+        }
+
+        fn is_main_motion_updating_energy(&mut self) -> bool {
+            self.trans_move_speed.is_some()
+        }
+
+        fn is_motion_2nd_updating_energy(&mut self) -> bool {
+            self.trans_move_speed_2nd.is_some()
+        }
+
+        fn motion_rate(&mut self) -> f32 {
+            self.motion_rate
+        }
+
+        fn add_float(&mut self, value: f32, id: i32) {
+            *self.floats.entry(id).or_insert(0.0) += value;
+        }
+
+        fn trans_move_speed(&mut self) -> Vector3f {
+            self.trans_move_speed.unwrap_or(Vector3f { x: 0.0, y: 0.0, z: 0.0 })
+        }
+
+        fn trans_move_speed_2nd(&mut self) -> Vector3f {
+            self.trans_move_speed_2nd.unwrap_or(Vector3f { x: 0.0, y: 0.0, z: 0.0 })
+        }
+
+        fn trans_tra(&mut self) -> Vector3f {
+            self.trans_tra
+        }
+
+        fn cliff_trans_speed(&mut self) -> PaddedVec2 {
+            self.cliff_trans_speed
+        }
+
+        fn is_near_ledge(&mut self, distance: f32) -> bool {
+            self.near_ledge.map_or(false, |ledge| ledge <= distance)
+        }
+
+        fn add_control_speed(&mut self, speed: PaddedVec2) -> bool {
+            match self.control_speed.as_mut() {
+                Some(control) => {
+                    *control += speed;
+                    true
+                },
+                None => false
+            }
+        }
+
+        fn damage_knock_back_push_out(&mut self) -> Option<(f32, i32)> {
+            self.knock_back_push_out
+        }
+
+        fn capture_offset(&mut self) -> Option<PaddedVec2> {
+            self.capture_offset
+        }
+
+        fn tether_anchor(&mut self) -> Option<PaddedVec2> {
+            self.tether_anchor
+        }
+
+        fn unk_motion_float(&mut self) -> f32 {
+            self.unk_motion_float
+        }
+
+        fn is_ignore_common_speed_limit(&mut self) -> bool {
+            self.ignore_common_speed_limit
+        }
+
+        fn process_energy(&mut self, energy: &mut KineticEnergy) {
+            // This is only an approximation of the game's processing, it's enough to check the direction things move in
+            fn process_axis(speed: f32, accel: f32, max: f32, brake: f32, limit: f32) -> f32 {
+                let mut speed = speed + accel;
+                if 0.0 <= max && max < speed.abs() {
+                    speed = (speed.abs() - brake).max(max) * speed.signum();
+                } else if accel == 0.0 {
+                    speed = (speed.abs() - brake).max(0.0) * speed.signum();
+                }
+                if 0.0 <= limit && limit < speed.abs() {
+                    speed = limit * speed.signum();
+                }
+                speed
+            }
+
+            if !energy.enable {
+                return;
+            }
+
+            energy.speed = PaddedVec2::new(
+                process_axis(energy.speed.x, energy.accel.x, energy.speed_max.x, energy.speed_brake.x, energy.speed_limit.x),
+                process_axis(energy.speed.y, energy.accel.y, energy.speed_max.y, energy.speed_brake.y, energy.speed_limit.y)
+            );
+        }
+
+        fn ground_normal(&mut self) -> Option<PaddedVec2> {
+            self.ground_normal
+        }
+
+        fn ground_material(&mut self) -> Option<i32> {
+            self.ground_material
+        }
+    }
+
+    extern "C" fn stub_destructor(_: &mut KineticEnergy) {}
+    extern "C" fn stub_deleter(_: *mut KineticEnergy) {}
+    extern "C" fn stub_boma_fn(_: &mut KineticEnergy, _: &mut BattleObjectModuleAccessor) {}
+    extern "C" fn stub_get_speed(energy: &mut KineticEnergy) -> *mut PaddedVec2 { &mut energy.speed }
+    extern "C" fn stub_get_flag(energy: &mut KineticEnergy) -> bool { energy.active_flag }
+    extern "C" fn stub_set_flag(energy: &mut KineticEnergy, flag: bool) { energy.active_flag = flag; }
+    extern "C" fn stub_setup(_: &mut KineticEnergy, _: u32, _: &Vector3f, _: u64, _: &mut BattleObjectModuleAccessor) {}
+    extern "C" fn stub_clear(energy: &mut KineticEnergy) {
+        energy.speed = PaddedVec2::zeros();
+        energy.rot_speed = PaddedVec2::zeros();
+    }
+    extern "C" fn stub_unk2(_: &mut KineticEnergy) {}
+    extern "C" fn stub_set_speed(energy: &mut KineticEnergy, speed: &Vector2f) { energy.speed = PaddedVec2::new(speed.x, speed.y); }
+    extern "C" fn stub_mul_accel(energy: &mut KineticEnergy, mul: &Vector2f) {
+        energy.accel.x *= mul.x;
+        energy.accel.y *= mul.y;
+    }
+
+    /// A vtable for energies created on the host. None of these call into the game
+    pub static STUB_VTABLE: KineticEnergyVTable = KineticEnergyVTable {
+        destructor: stub_destructor,
+        deleter: stub_deleter,
+        unk: stub_boma_fn,
+        update: stub_boma_fn,
+        get_speed: stub_get_speed,
+        initialize: stub_boma_fn,
+        get_some_flag: stub_get_flag,
+        set_some_flag: stub_set_flag,
+        setup_energy: stub_setup,
+        clear_energy: stub_clear,
+        unk2: stub_unk2,
+        set_speed: stub_set_speed,
+        mul_accel: stub_mul_accel,
+    };
+
+    /// Creates a zeroed out, enabled energy that uses the stub vtable
+    pub fn stub_energy() -> KineticEnergy {
+        KineticEnergy {
+            vtable: &STUB_VTABLE,
+            _x8: 0,
+            speed: PaddedVec2::zeros(),
+            rot_speed: PaddedVec2::zeros(),
+            enable: true,
+            unk2: [0; 0xF],
+            accel: PaddedVec2::zeros(),
+            speed_max: PaddedVec2::zeros(),
+            speed_brake: PaddedVec2::zeros(),
+            speed_limit: PaddedVec2::new(-1.0, -1.0),
+            _x80: 0,
+            consider_ground_friction: false,
+            active_flag: false,
+            _x83: 0,
+            energy_reset_type: 0,
+        }
+    }
+}
@@ -0,0 +1,120 @@
+//! State that energy_id keeps for a fighter outside of the game's energies, since the energies have no fields to spare.
+//!
+//! Every fighter has one entry, keyed by the address of its module accessor. An entry is removed as soon as nothing is
+//! left in it, and cleared when the fighter is initialized or reset, so that a fighter that reuses the address of one
+//! that was despawned never inherits its state
+
+use std::sync::Mutex;
+
+use smash::lua2cpp::L2CFighterCommon;
+
+use super::{
+    energy::PaddedVec2,
+    glide::GlideState,
+    root_motion::RootMotionModifier
+};
+
+/// Everything that is kept for a fighter, `None` when it isn't in use
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct FighterState {
+    /// The glide of the stop energy, while it's set up for GlidStart
+    pub glide: Option<GlideState>,
+    /// The speed the fighter was moving at when their tether caught, while the stop energy is set up for AirLassoHang
+    /// or AirLassoRewind
+    pub tether_caught_speed: Option<PaddedVec2>,
+    /// The brake of the motion energy's decay, from the first frame after the animation stops driving it
+    pub decay: Option<PaddedVec2>,
+    /// The root motion modifier, along with the status it was set in
+    pub root_motion: Option<(i32, RootMotionModifier)>,
+}
+
+static STATES: Mutex<Vec<(usize, FighterState)>> = Mutex::new(Vec::new());
+
+fn key<A: ?Sized>(boma: &A) -> usize {
+    boma as *const A as *const u8 as usize
+}
+
+/// Gets the state of a fighter
+pub fn get<A: ?Sized>(boma: &A) -> FighterState {
+    let key = key(boma);
+    STATES.lock().unwrap().iter().find(|(boma, _)| *boma == key).map_or_else(FighterState::default, |(_, state)| *state)
+}
+
+/// Changes the state of a fighter, removing it if nothing is left in it
+pub fn update<A: ?Sized, R>(boma: &A, f: impl FnOnce(&mut FighterState) -> R) -> R {
+    let key = key(boma);
+    let mut states = STATES.lock().unwrap();
+    let index = match states.iter().position(|(boma, _)| *boma == key) {
+        Some(index) => index,
+        None => {
+            states.push((key, FighterState::default()));
+            states.len() - 1
+        }
+    };
+    let result = f(&mut states[index].1);
+    if states[index].1 == FighterState::default() {
+        states.swap_remove(index);
+    }
+    result
+}
+
+/// Replaces the whole state of a fighter
+pub fn set<A: ?Sized>(boma: &A, state: FighterState) {
+    update(boma, |current| *current = state);
+}
+
+/// Removes the state of a fighter
+pub fn clear<A: ?Sized>(boma: &A) {
+    let key = key(boma);
+    STATES.lock().unwrap().retain(|(boma, _)| *boma != key);
+}
+
+#[smashline::fighter_init]
+fn fighter_init(fighter: &mut L2CFighterCommon) {
+    unsafe {
+        clear(&*fighter.module_accessor);
+    }
+}
+
+#[smashline::fighter_reset]
+fn fighter_reset(fighter: &mut L2CFighterCommon) {
+    unsafe {
+        clear(&*fighter.module_accessor);
+    }
+}
+
+/// Clears a fighter's state whenever it's created or reset
+pub fn install() {
+    smashline::install_agent_init_callbacks!(fighter_init);
+    smashline::install_agent_resets!(fighter_reset);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn states_are_kept_per_fighter() {
+        let (first, second) = (0u64, 0u64);
+        update(&first, |state| state.glide = Some(GlideState { angle: 0.5, speed: 1.0 }));
+        update(&second, |state| state.decay = Some(PaddedVec2::new(0.25, 0.0)));
+        update(&first, |state| state.glide = Some(GlideState { angle: 0.25, speed: 1.5 }));
+        assert_eq!(get(&first).glide, Some(GlideState { angle: 0.25, speed: 1.5 }));
+        assert_eq!(get(&first).decay, None);
+        assert_eq!(get(&second).decay, Some(PaddedVec2::new(0.25, 0.0)));
+
+        clear(&first);
+        assert_eq!(get(&first), FighterState::default());
+        assert_eq!(get(&second).decay, Some(PaddedVec2::new(0.25, 0.0)));
+        clear(&second);
+    }
+
+    #[test]
+    fn empty_states_are_removed() {
+        let fighter = 0u64;
+        update(&fighter, |state| state.decay = Some(PaddedVec2::new(0.25, 0.0)));
+        update(&fighter, |state| state.decay = None);
+        let key = key(&fighter);
+        assert!(STATES.lock().unwrap().iter().all(|(boma, _)| *boma != key));
+    }
+}
@@ -0,0 +1,328 @@
+//! A fuzzing harness for the control, motion and stop energies.
+//!
+//! Every case is built from its own seed like the property tests, but nothing is kept to the ranges the game uses: the
+//! params, work values, animation speeds and the energy's own fields are overwritten with arbitrary values that lean
+//! towards 0, ±1, tiny and large magnitudes, and the int work values towards -1 and the ends of the range. The energy is
+//! then updated for a few frames and any field that isn't finite afterwards, or any panic, fails the case with its seed,
+//! which is enough to replay it with `run_case`. The cases it has found are kept as regression tests at the bottom of
+//! this file
+//!
+//! `fuzz_*` run a fixed set of seeds so that they can run with the rest of the tests, a longer run can be done by setting
+//! ENERGY_FUZZ_CASES to the number of seeds and ENERGY_FUZZ_START to the first one
+//!
+//! Like the property tests these run against the stub's approximation of the game's processing, and so do the regression
+//! tests: a case that only fails because of the stub's processing wouldn't fail in game, and a case that would fail
+//! in game can still pass here. Like the rest of the tests in `src`, they don't build on a PC yet (see the
+//! README)
+
+use std::panic::{self, AssertUnwindSafe};
+
+use smash::lib::lua_const::*;
+use smash::phx::Vector3f;
+
+use super::{
+    control::{EnergyControllerResetType, FighterKineticEnergyControl},
+    energy::PaddedVec2,
+    fighter::stub::{Rng, StubFighter},
+    golden::{Field, GoldenEnergy},
+    motion::{EnergyMotionResetType, FighterKineticEnergyMotion},
+    stop::{EnergyStopResetType, FighterKineticEnergyStop}
+};
+
+const CASES: u64 = 2000;
+const FRAMES: usize = 8;
+
+/// Every field that `GoldenEnergy::field` knows about, the ones an energy doesn't have are skipped
+const FIELDS: &[&str] = &[
+    "speed", "rot_speed", "accel", "speed_max", "speed_brake", "speed_limit", "enable", "active_flag",
+    "lr", "accel_mul_x", "accel_add_x", "accel_mul_y", "accel_add_y",
+    "angle", "angle_whole", "angle_intp_end", "angle_intp_frames_remaining", "speed_mul", "prev_speed", "speed_mul_2nd", "update_flag",
+    "damage_target_speed", "elapsed_hitstop_frames", "hitstop_frames", "interpolation_frames_remaining"
+];
+
+/// Picks a float that's either 0, -0, ±1, or anything with a magnitude from 1e-6 up to 1000
+fn arbitrary(rng: &mut Rng) -> f32 {
+    let sign = rng.pick(&[1.0, -1.0]);
+    match rng.int(0, 8) {
+        0 => 0.0,
+        1 => -0.0,
+        2 => sign,
+        3 => sign * 1.0e-6,
+        _ => sign * 10f32.powf(rng.range(-6.0, 3.0))
+    }
+}
+
+fn arbitrary_int(rng: &mut Rng) -> i32 {
+    match rng.int(0, 6) {
+        0 => -1,
+        1 => 0,
+        2 => i32::MAX,
+        3 => i32::MIN,
+        _ => rng.int(-10, 40)
+    }
+}
+
+fn arbitrary_vec2(rng: &mut Rng) -> PaddedVec2 {
+    PaddedVec2::new(arbitrary(rng), arbitrary(rng))
+}
+
+fn arbitrary_vec3(rng: &mut Rng) -> Option<Vector3f> {
+    if rng.bool() {
+        Some(Vector3f { x: arbitrary(rng), y: arbitrary(rng), z: arbitrary(rng) })
+    } else {
+        None
+    }
+}
+
+fn arbitrary_stick(rng: &mut Rng) -> PaddedVec2 {
+    let mut axis = || match rng.int(0, 4) {
+        0 => 0.0,
+        1 => rng.pick(&[1.0, -1.0]),
+        _ => rng.range(-1.0, 1.0)
+    };
+    PaddedVec2::new(axis(), axis())
+}
+
+fn arbitrary_fighter(rng: &mut Rng) -> StubFighter {
+    let mut fighter = StubFighter::new();
+    fighter.random_params = Some(rng.next_u64());
+    fighter.random_float = arbitrary;
+    fighter.lr = rng.pick(&[1.0, -1.0]);
+    fighter.scale = arbitrary(rng);
+    fighter.situation_kind = rng.pick(&[*SITUATION_KIND_GROUND, *SITUATION_KIND_AIR]);
+    fighter.ignore_common_speed_limit = rng.bool();
+    fighter.is_turn = rng.bool();
+    fighter.is_damage = rng.bool();
+    fighter.reverse_x_frame = arbitrary_int(rng);
+    fighter.motion_frame = arbitrary(rng);
+    fighter.motion_rate = arbitrary(rng);
+    fighter.trans_move_speed = arbitrary_vec3(rng);
+    fighter.trans_move_speed_2nd = arbitrary_vec3(rng);
+    fighter.trans_tra = Vector3f { x: arbitrary(rng), y: arbitrary(rng), z: arbitrary(rng) };
+    fighter.cliff_trans_speed = arbitrary_vec2(rng);
+    if rng.bool() {
+        fighter.ground_normal = Some(arbitrary_vec2(rng));
+    }
+    if rng.bool() {
+        fighter.link_speed = Some(arbitrary_vec2(rng));
+    }
+    if rng.bool() {
+        fighter.capture_offset = Some(arbitrary_vec2(rng));
+    }
+    if rng.bool() {
+        fighter.tether_anchor = Some(arbitrary_vec2(rng));
+    }
+    if rng.bool() {
+        fighter.knock_back_push_out = Some((arbitrary(rng), arbitrary_int(rng)));
+    }
+
+    for id in [
+        0x1000009,
+        *FIGHTER_INSTANCE_WORK_ID_FLOAT_DAMAGE_REACTION_FRAME,
+        *FIGHTER_STATUS_ITEM_LIFT_WORK_FLOAT_STICK_RATE,
+        *FIGHTER_STATUS_LADDER_WORK_FLOAT_LADDER_END_START_Y,
+        *FIGHTER_STATUS_LADDER_WORK_FLOAT_LADDER_END_Y,
+        *FIGHTER_STATUS_WORK_ID_FLOAT_RESERVE_KINETIC_MOTION_SPEED_MUL
+    ].iter() {
+        fighter.floats.insert(*id, arbitrary(rng));
+    }
+    for id in [
+        0x11000005,
+        *FIGHTER_STATUS_DAMAGE_WORK_INT_HIT_STOP_FRAME,
+        *FIGHTER_STATUS_DASH_WORK_INT_COUNT,
+        *FIGHTER_STATUS_DASH_WORK_INT_TURN_DASH_FROM_DASH_COUNT,
+        *FIGHTER_STATUS_WALL_JUMP_WORK_INT_DISABLE_CONT_FRAME
+    ].iter() {
+        fighter.ints.insert(*id, arbitrary_int(rng));
+    }
+    for id in [
+        *FIGHTER_INSTANCE_WORK_ID_FLAG_JUMP_NO_LIMIT,
+        *FIGHTER_INSTANCE_WORK_ID_FLAG_JUMP_NO_LIMIT_ONCE,
+        *FIGHTER_INSTANCE_WORK_ID_FLAG_UP_SPEED_DAMAGE,
+        *FIGHTER_STATUS_DAMAGE_FLAG_ELEC,
+        *FIGHTER_STATUS_ESCAPE_AIR_FLAG_SLIDE,
+        *FIGHTER_STATUS_ESCAPE_AIR_FLAG_SLIDE_ENABLE_CONTROL,
+        *FIGHTER_STATUS_ITEM_LIFT_WORK_FLAG_STOP
+    ].iter() {
+        fighter.flags.insert(*id, rng.bool());
+    }
+    fighter
+}
+
+fn arbitrary_fields<E: GoldenEnergy>(energy: &mut E, rng: &mut Rng) {
+    for name in FIELDS {
+        if !rng.bool() {
+            continue;
+        }
+        match energy.field(name) {
+            // the game never has a negative brake
+            Some(Field::Vec2(value)) if *name == "speed_brake" => *value = arbitrary_vec2(rng).abs(),
+            Some(Field::Vec2(value)) => *value = arbitrary_vec2(rng),
+            Some(Field::Float(value)) => *value = arbitrary(rng),
+            Some(Field::Bool(value)) => *value = rng.bool(),
+            Some(Field::Int(value)) => *value = arbitrary_int(rng),
+            Some(Field::Byte(value)) => *value = rng.int(0, 256) as u8,
+            None => {}
+        }
+    }
+}
+
+/// Finds the first field of the energy that is NaN or infinite
+fn non_finite_field<E: GoldenEnergy>(energy: &mut E) -> Option<&'static str> {
+    FIELDS.iter().copied().find(|name| match energy.field(name) {
+        Some(Field::Vec2(value)) => !value.is_finite(),
+        Some(Field::Float(value)) => !value.is_finite(),
+        _ => false
+    })
+}
+
+fn run_case<E: GoldenEnergy>(seed: u64) -> Result<(), String> {
+    let mut rng = Rng::new(seed);
+    let reset_type = E::reset_type(rng.int(0, E::RESET_TYPES as i32) as u32);
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut fighter = arbitrary_fighter(&mut rng);
+        let mut energy = E::new(reset_type);
+        unsafe {
+            energy.setup(reset_type, arbitrary_vec2(&mut rng), &mut fighter);
+        }
+        arbitrary_fields(&mut energy, &mut rng);
+
+        for frame in 0..FRAMES {
+            fighter.stick = arbitrary_stick(&mut rng);
+            if rng.int(0, 4) == 0 {
+                fighter.trans_move_speed = None;
+            }
+
+            unsafe {
+                energy.update(&mut fighter);
+            }
+            if let Some(name) = non_finite_field(&mut energy) {
+                return Err(format!("frame {}: {} isn't finite", frame + 1, name));
+            }
+        }
+        Ok(())
+    }));
+
+    let result = match result {
+        Ok(result) => result,
+        Err(payload) => {
+            let message = payload.downcast_ref::<&str>().map(|message| message.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_default();
+            Err(format!("panicked: {}", message))
+        }
+    };
+    result.map_err(|error| format!("seed {} ({:?}), {}", seed, reset_type, error))
+}
+
+fn fuzz<E: GoldenEnergy>() {
+    let var = |name: &str, default: u64| std::env::var(name).ok().and_then(|value| value.parse().ok()).unwrap_or(default);
+    let start = var("ENERGY_FUZZ_START", 0);
+    let cases = var("ENERGY_FUZZ_CASES", CASES);
+
+    let failures = (start..start + cases).filter_map(|seed| run_case::<E>(seed).err()).collect::<Vec<_>>();
+    assert!(failures.is_empty(), "{} of {} cases failed:\n{}", failures.len(), cases, failures.join("\n"));
+}
+
+#[test]
+fn fuzz_control() {
+    fuzz::<FighterKineticEnergyControl>();
+}
+
+#[test]
+fn fuzz_motion() {
+    fuzz::<FighterKineticEnergyMotion>();
+}
+
+#[test]
+fn fuzz_stop() {
+    fuzz::<FighterKineticEnergyStop>();
+}
+
+fn assert_finite<E: GoldenEnergy>(energy: &mut E) {
+    if let Some(name) = non_finite_field(energy) {
+        panic!("{} isn't finite", name);
+    }
+}
+
+fn update_once<E: GoldenEnergy>(reset_type: E::ResetType, fighter: &mut StubFighter) -> E {
+    let mut energy = E::new(reset_type);
+    unsafe {
+        energy.setup(reset_type, PaddedVec2::zeros(), fighter);
+        energy.update(fighter);
+    }
+    energy
+}
+
+#[test]
+fn cliff_interpolation_after_last_frame() {
+    // -1 divided by 0, and the end of the range overflowed when adding 1 to it
+    for frame in [-1, i32::MIN, i32::MAX].iter() {
+        let mut fighter = StubFighter::new();
+        fighter.trans_move_speed = Some(Vector3f { x: 0.0, y: 0.0, z: 0.0 });
+        fighter.cliff_trans_speed = PaddedVec2::new(2.0, 4.0);
+        fighter.ints.insert(0x11000005, *frame);
+
+        let mut energy = update_once::<FighterKineticEnergyMotion>(EnergyMotionResetType::CliffTransIntp, &mut fighter);
+        assert_finite(&mut energy);
+    }
+}
+
+#[test]
+fn gekikara_with_tiny_motion_rate() {
+    let mut fighter = StubFighter::new();
+    fighter.trans_move_speed = Some(Vector3f { x: 0.0, y: 0.0, z: 1.0 });
+    fighter.floats.insert(0x1000009, 1.0);
+    fighter.motion_rate = 1.0e-40;
+
+    let mut energy = update_once::<FighterKineticEnergyMotion>(EnergyMotionResetType::GroundTransLoopGekikara, &mut fighter);
+    assert_finite(&mut energy);
+}
+
+#[test]
+fn air_brake_always_at_rest() {
+    // Without any brake the speed was divided by itself, which is 0 / 0 at rest
+    let mut fighter = StubFighter::new();
+    fighter.situation_kind = *SITUATION_KIND_AIR;
+
+    let mut energy = update_once::<FighterKineticEnergyStop>(EnergyStopResetType::AirBrakeAlways, &mut fighter);
+    assert_finite(&mut energy);
+    assert_eq!((energy.speed.x, energy.speed.y), (0.0, 0.0));
+}
+
+#[test]
+fn ladder_without_deadzone_range() {
+    // A ladder_stick_y of 1 with a full tilt lerped over nothing, which is 0 / 0
+    for stick_y in [1.0, -1.0].iter() {
+        let mut fighter = StubFighter::new()
+            .with_param("common", "ladder_stick_y", 1.0)
+            .with_param("common", "ladder_speed_d_max", 2.0)
+            .with_param("common", "ladder_attack_speed_mul", 1.0);
+        fighter.stick = PaddedVec2::new(0.0, *stick_y);
+
+        let mut energy = update_once::<FighterKineticEnergyControl>(EnergyControllerResetType::Ladder, &mut fighter);
+        assert_finite(&mut energy);
+        assert_eq!(energy.speed_max.y, 2.0);
+    }
+}
+
+#[test]
+fn damage_interpolation_over_every_frame_count() {
+    // The interpolation divides by the frames remaining, which can't be 0 when it divides
+    for frames in 0..=255u8 {
+        let mut fighter = StubFighter::new();
+        fighter.situation_kind = *SITUATION_KIND_GROUND;
+
+        let mut energy = FighterKineticEnergyStop::new(EnergyStopResetType::DamageGround);
+        unsafe {
+            energy.setup(EnergyStopResetType::DamageGround, PaddedVec2::new(1.0, 0.0), &mut fighter);
+        }
+        energy.damage_target_speed = PaddedVec2::new(1000.0, -1000.0);
+        energy.interpolation_frames_remaining = frames;
+        unsafe {
+            energy.update(&mut fighter);
+        }
+        assert_finite(&mut energy);
+    }
+}
@@ -0,0 +1,167 @@
+use super::energy::PaddedVec2;
+
+/// Values used by the stop energy for gliding (GlidStart) and landing out of a glide (GlidLanding).
+/// Angles are in radians, with positive angles pointing the fighter upwards
+///
+/// The game only processes the stop energy for both reset types, so none of this is used until `enabled` is set
+#[derive(Debug, Copy, Clone)]
+#[repr(C)]
+pub struct GlideParams {
+    /// Steers and pitches gliding fighters, and carries the glide's speed into the landing
+    pub enabled: bool,
+    /// How quickly the angle changes at full stick tilt, per frame
+    pub pitch_speed: f32,
+    /// How quickly the angle returns to `angle_neutral` when the stick is released, per frame
+    pub angle_return_speed: f32,
+    /// The stick y value that needs to be exceeded before the angle is changed
+    pub stick_y_deadzone: f32,
+    pub angle_min: f32,
+    pub angle_max: f32,
+    pub angle_neutral: f32,
+    /// The speed a glide starts with if the fighter was moving slower than it
+    pub start_speed: f32,
+    pub speed_min: f32,
+    pub speed_max: f32,
+    /// How much speed is gained when pointed straight down (and lost when pointed straight up)
+    pub gravity_accel: f32,
+    /// The fraction of the speed that is lost every frame
+    pub drag: f32,
+    /// How much of the fall speed is cancelled out per unit of forwards speed
+    pub lift: f32,
+    /// The speed the fighter sinks at while gliding with no forwards speed
+    pub fall_speed: f32,
+    /// The amount of horizontal glide speed that is kept on landing
+    pub landing_speed_x_mul: f32,
+    /// The amount of vertical glide speed that is turned into horizontal speed on landing
+    pub landing_transfer_y_mul: f32,
+    /// Multiplier on `ground_brake` while landing out of a glide
+    pub landing_brake_mul: f32,
+}
+
+impl GlideParams {
+    pub const DEFAULT: Self = Self {
+        enabled: false,
+        pitch_speed: 0.035,
+        angle_return_speed: 0.01,
+        stick_y_deadzone: 0.2,
+        angle_min: -1.22,
+        angle_max: 0.87,
+        angle_neutral: -0.17,
+        start_speed: 1.0,
+        speed_min: 0.4,
+        speed_max: 2.6,
+        gravity_accel: 0.06,
+        drag: 0.01,
+        lift: 0.3,
+        fall_speed: 0.6,
+        landing_speed_x_mul: 0.8,
+        landing_transfer_y_mul: 0.3,
+        landing_brake_mul: 1.0,
+    };
+
+    /// Creates the glide state from the speed the fighter had going into it
+    /// # Arguments
+    /// * `speed` - The speed of the fighter when the glide starts
+    pub fn start(&self, speed: PaddedVec2) -> GlideState {
+        let angle = if speed.x == 0.0 && speed.y == 0.0 {
+            self.angle_neutral
+        } else {
+            speed.y.atan2(speed.x.abs())
+        };
+
+        GlideState {
+            angle: angle.max(self.angle_min).min(self.angle_max),
+            speed: speed.mag().max(self.start_speed).min(self.speed_max)
+        }
+    }
+
+    /// Advances the glide state by a single frame
+    /// # Arguments
+    /// * `state` - The glide state from the previous frame
+    /// * `stick_y` - The vertical stick value of the fighter
+    pub fn step(&self, state: GlideState, stick_y: f32) -> GlideState {
+        let angle = if stick_y.abs() > self.stick_y_deadzone {
+            state.angle + stick_y * self.pitch_speed
+        } else {
+            // move back towards the neutral angle without overshooting it
+            let diff = self.angle_neutral - state.angle;
+            state.angle + diff.signum() * diff.abs().min(self.angle_return_speed)
+        };
+        let angle = angle.max(self.angle_min).min(self.angle_max);
+
+        // diving turns height into speed, climbing does the opposite
+        let speed = state.speed - angle.sin() * self.gravity_accel;
+        let speed = speed - speed * self.drag;
+
+        GlideState {
+            angle,
+            speed: speed.max(self.speed_min).min(self.speed_max)
+        }
+    }
+
+    /// Gets the speed of the fighter for a glide state
+    /// # Arguments
+    /// * `state` - The current glide state
+    /// * `lr` - The direction the fighter is facing
+    pub fn speed(&self, state: GlideState, lr: f32) -> PaddedVec2 {
+        let fall = (self.fall_speed - self.lift * state.speed * state.angle.cos()).max(0.0);
+        PaddedVec2::new(
+            lr * state.speed * state.angle.cos(),
+            state.speed * state.angle.sin() - fall
+        )
+    }
+
+    /// Gets the grounded speed to land with after a glide
+    /// # Arguments
+    /// * `speed` - The speed of the fighter when they touched the ground
+    pub fn landing_speed(&self, speed: PaddedVec2) -> PaddedVec2 {
+        let speed_x = speed.x * self.landing_speed_x_mul;
+        PaddedVec2::new(
+            speed_x + speed.y.abs() * self.landing_transfer_y_mul * speed.x.signum(),
+            0.0
+        )
+    }
+}
+
+/// The angle and forwards speed of a glide
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct GlideState {
+    pub angle: f32,
+    pub speed: f32,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn diving_gains_speed() {
+        let params = GlideParams::DEFAULT;
+        let mut state = GlideState { angle: params.angle_min, speed: 1.0 };
+        for _ in 0..10 {
+            let next = params.step(state, -1.0);
+            assert!(next.speed >= state.speed);
+            state = next;
+        }
+    }
+
+    #[test]
+    fn climbing_loses_speed() {
+        let params = GlideParams::DEFAULT;
+        let mut state = GlideState { angle: params.angle_max, speed: 2.0 };
+        for _ in 0..10 {
+            let next = params.step(state, 1.0);
+            assert!(next.speed <= state.speed);
+            assert!(next.speed >= params.speed_min);
+            state = next;
+        }
+    }
+
+    #[test]
+    fn landing_keeps_direction() {
+        let params = GlideParams::DEFAULT;
+        let landing = params.landing_speed(PaddedVec2::new(-2.0, -1.0));
+        assert!(landing.x < 0.0);
+        assert_eq!(landing.y, 0.0);
+    }
+}
@@ -0,0 +1,465 @@
+//! Golden-vector conformance tests for the control, motion and stop energies.
+//!
+//! The vectors live in tests/golden, one file per energy. Each vector sets up a stubbed fighter and an energy, runs the
+//! energy's setup (which also initializes it) and then updates it once per frame, checking the energy after each frame:
+//! ```text
+//! {
+//!   "name": "dash accelerates towards run_speed_max",
+//!   "source": "derived",
+//!   "reset_type": "Dash",
+//!   "fighter": { "params": [["run_speed_max", "", 2.0]], "stick": [1.0, 0.0] },
+//!   "energy": { "lr": 1.0 },
+//!   "initial_speed": [0.0, 0.0],
+//!   "after_setup": { "speed": [1.5, 0.0] },
+//!   "frames": [
+//!     { "expect": { "speed": [1.875, 0.0], "accel": [0.375, 0.0] } },
+//!     { "fighter": { "stick": [-1.0, 0.0] }, "energy": { "speed_brake": [0.25, 0.0] }, "expect": { "speed": [1.75, 0.0] } }
+//!   ]
+//! }
+//! ```
+//! - `fighter` sets fields of the `StubFighter`. Params are `[obj, field, value]` with an empty field for the params
+//!   that aren't part of an object, and work ids, status kinds and situation kinds can be written as numbers, as hex
+//!   strings or as the name of the lua const (see `constant`)
+//! - `energy` sets fields of the energy before setup, and in a frame before that frame's update
+//! - `expect` only checks the fields it lists, `floats` checks the fighter's work floats instead of the energy's fields
+//! - `tolerance` overrides the default tolerance of the vector's float comparisons
+//! - `source` says where the expected values came from. `"derived"` values were worked out from energy_id's own logic,
+//!   by hand or by running it against the stub, so they only catch changes to that logic and not differences from the
+//!   game. `"capture"` values were recorded from the game, and need a `capture` that says which game version, fighter and
+//!   situation they were recorded in
+//!
+//! The fighter's processing of the energy is the stub's approximation of the game's (see `StubFighter::process_energy`),
+//! so these lock down the energy logic and not the game's processing. Every reset type needs at least one vector.
+//!
+//! Every vector is derived for now, some of them were rewritten when the logic they cover was changed. The ones with
+//! frames also depend on the stub's processing, and need to be replaced with captures before they can say anything
+//! about the game
+
+use std::ops::DerefMut;
+
+use smash::lib::lua_const::*;
+use smash::phx::Vector3f;
+
+use super::{
+    control::{self, EnergyControllerResetType, FighterKineticEnergyControl},
+    energy::{KineticEnergy, PaddedVec2},
+    fighter::stub::StubFighter,
+    json::{self, Value},
+    motion::{self, EnergyMotionResetType, FighterKineticEnergyMotion},
+    stop::{self, EnergyStopResetType, FighterKineticEnergyStop}
+};
+
+const DEFAULT_TOLERANCE: f32 = 1.0e-4;
+
+macro_rules! constants {
+    ($name:expr, $($constant:ident),* $(,)?) => {
+        match $name {
+            $(stringify!($constant) => Some(*$constant as i32),)*
+            _ => None
+        }
+    };
+}
+
+/// The lua consts that vectors can refer to by name
+fn constant(name: &str) -> Option<i32> {
+    constants!(
+        name,
+        SITUATION_KIND_GROUND,
+        SITUATION_KIND_AIR,
+        GROUND_TOUCH_FLAG_LEFT,
+        GROUND_TOUCH_FLAG_RIGHT,
+        FIGHTER_STATUS_KIND_DASH,
+        FIGHTER_STATUS_KIND_TURN_DASH,
+        FIGHTER_STATUS_KIND_DAMAGE_FLY,
+        FIGHTER_INSTANCE_WORK_ID_FLAG_JUMP_NO_LIMIT,
+        FIGHTER_INSTANCE_WORK_ID_FLAG_JUMP_NO_LIMIT_ONCE,
+        FIGHTER_INSTANCE_WORK_ID_FLAG_UP_SPEED_DAMAGE,
+        FIGHTER_STATUS_DASH_WORK_INT_COUNT,
+        FIGHTER_STATUS_WALL_JUMP_WORK_INT_DISABLE_CONT_FRAME,
+        FIGHTER_STATUS_ITEM_LIFT_WORK_FLAG_STOP,
+        FIGHTER_STATUS_ITEM_LIFT_WORK_FLOAT_STICK_RATE,
+        FIGHTER_STATUS_WORK_ID_FLOAT_RESERVE_KINETIC_MOTION_SPEED_MUL,
+        FIGHTER_STATUS_LADDER_WORK_FLOAT_LADDER_END_Y,
+        FIGHTER_STATUS_LADDER_WORK_FLOAT_LADDER_END_START_Y,
+    )
+}
+
+fn id(value: &str) -> i32 {
+    if let Some(hex) = value.strip_prefix("0x") {
+        return i32::from_str_radix(hex, 16).unwrap_or_else(|_| panic!("invalid hex id {}", value));
+    }
+    value.parse()
+        .ok()
+        .or_else(|| constant(value))
+        .unwrap_or_else(|| panic!("unknown constant {}, add it to golden::constant", value))
+}
+
+fn id_value(value: &Value) -> i32 {
+    match value {
+        Value::String(name) => id(name),
+        value => value.as_i64().expect("ids are numbers or strings") as i32
+    }
+}
+
+fn float(value: &Value) -> f32 {
+    value.as_f32().unwrap_or_else(|| panic!("expected a number, got {}", value))
+}
+
+fn vec2(value: &Value) -> PaddedVec2 {
+    match value.as_array() {
+        Some([x, y]) => PaddedVec2::new(float(x), float(y)),
+        _ => panic!("expected [x, y], got {}", value)
+    }
+}
+
+fn vec3(value: &Value) -> Vector3f {
+    match value.as_array() {
+        Some([x, y, z]) => Vector3f { x: float(x), y: float(y), z: float(z) },
+        _ => panic!("expected [x, y, z], got {}", value)
+    }
+}
+
+fn fields(value: &Value) -> &[(String, Value)] {
+    match value {
+        Value::Object(fields) => fields,
+        value => panic!("expected an object, got {}", value)
+    }
+}
+
+fn apply_fighter(fighter: &mut StubFighter, value: &Value) {
+    for (name, value) in fields(value) {
+        match name.as_str() {
+            "params" | "int_params" => {
+                for param in value.as_array().expect("params are a list") {
+                    let (obj, field, value) = match param.as_array() {
+                        Some([obj, field, value]) => (obj.as_str().unwrap(), field.as_str().unwrap(), value),
+                        _ => panic!("params are [obj, field, value], got {}", param)
+                    };
+                    let key = if field.is_empty() {
+                        (smash::hash40(obj), 0)
+                    } else {
+                        (smash::hash40(obj), smash::hash40(field))
+                    };
+                    if name == "params" {
+                        fighter.params.insert(key, float(value));
+                    } else {
+                        fighter.int_params.insert(key, id_value(value));
+                    }
+                }
+            },
+            "floats" => fields(value).iter().for_each(|(work, value)| { fighter.floats.insert(id(work), float(value)); }),
+            "ints" => fields(value).iter().for_each(|(work, value)| { fighter.ints.insert(id(work), id_value(value)); }),
+            "flags" => fields(value).iter().for_each(|(work, value)| { fighter.flags.insert(id(work), value.as_bool().unwrap()); }),
+            "stick" => fighter.stick = vec2(value),
+            "reverse_x_frame" => fighter.reverse_x_frame = id_value(value),
+            "is_turn" => fighter.is_turn = value.as_bool().unwrap(),
+            "lr" => fighter.lr = float(value),
+            "scale" => fighter.scale = float(value),
+            "fighter_kind" => fighter.fighter_kind = id_value(value),
+            "status_kind" => fighter.status_kind = id_value(value),
+            "situation_kind" => fighter.situation_kind = id_value(value),
+            "unk_motion_float" => fighter.unk_motion_float = float(value),
+            "ignore_common_speed_limit" => fighter.ignore_common_speed_limit = value.as_bool().unwrap(),
+            "is_damage" => fighter.is_damage = value.as_bool().unwrap(),
+            "touch_pos" => fighter.touch_pos = id_value(value) as u32,
+            "ground_normal" => fighter.ground_normal = optional(value, vec2),
+            "ground_material" => fighter.ground_material = optional(value, id_value),
+            "link_speed" => fighter.link_speed = optional(value, vec2),
+            "motion_frame" => fighter.motion_frame = float(value),
+            "motion_rate" => fighter.motion_rate = float(value),
+            "trans_move_speed" => fighter.trans_move_speed = optional(value, vec3),
+            "trans_move_speed_2nd" => fighter.trans_move_speed_2nd = optional(value, vec3),
+            "trans_tra" => fighter.trans_tra = vec3(value),
+            "cliff_trans_speed" => fighter.cliff_trans_speed = vec2(value),
+            "near_ledge" => fighter.near_ledge = optional(value, float),
+            "control_speed" => fighter.control_speed = optional(value, vec2),
+            "capture_offset" => fighter.capture_offset = optional(value, vec2),
+            "tether_anchor" => fighter.tether_anchor = optional(value, vec2),
+            "knock_back_push_out" => fighter.knock_back_push_out = optional(value, |value| {
+                let push_out = vec2(value);
+                (push_out.x, push_out.y as i32)
+            }),
+            name => panic!("unknown fighter field {}", name)
+        }
+    }
+}
+
+fn optional<T>(value: &Value, parse: impl FnOnce(&Value) -> T) -> Option<T> {
+    match value {
+        Value::Null => None,
+        value => Some(parse(value))
+    }
+}
+
+/// A field of an energy that vectors can set and check
+pub(crate) enum Field<'a> {
+    Vec2(&'a mut PaddedVec2),
+    Float(&'a mut f32),
+    Bool(&'a mut bool),
+    Int(&'a mut i32),
+    Byte(&'a mut u8),
+}
+
+fn base_field<'a>(energy: &'a mut KineticEnergy, name: &str) -> Option<Field<'a>> {
+    let field = match name {
+        "speed" => Field::Vec2(&mut energy.speed),
+        "rot_speed" => Field::Vec2(&mut energy.rot_speed),
+        "accel" => Field::Vec2(&mut energy.accel),
+        "speed_max" => Field::Vec2(&mut energy.speed_max),
+        "speed_brake" => Field::Vec2(&mut energy.speed_brake),
+        "speed_limit" => Field::Vec2(&mut energy.speed_limit),
+        "enable" => Field::Bool(&mut energy.enable),
+        "active_flag" => Field::Bool(&mut energy.active_flag),
+        _ => return None
+    };
+    Some(field)
+}
+
+/// The parts of an energy that the runner needs, the property tests drive the energies through this as well
+pub(crate) trait GoldenEnergy: DerefMut<Target = KineticEnergy> {
+    type ResetType: Copy + PartialEq + std::fmt::Debug;
+
+    /// The number of reset types, they have to go from 0 up to this
+    const RESET_TYPES: u32;
+
+    fn reset_type(raw: u32) -> Self::ResetType;
+    fn new(reset_type: Self::ResetType) -> Self;
+    fn field(&mut self, name: &str) -> Option<Field<'_>>;
+    unsafe fn setup(&mut self, reset_type: Self::ResetType, initial_speed: PaddedVec2, fighter: &mut StubFighter);
+    unsafe fn update(&mut self, fighter: &mut StubFighter);
+}
+
+impl GoldenEnergy for FighterKineticEnergyControl {
+    type ResetType = EnergyControllerResetType;
+    const RESET_TYPES: u32 = EnergyControllerResetType::DashBack as u32 + 1;
+
+    fn reset_type(raw: u32) -> Self::ResetType {
+        unsafe { std::mem::transmute(raw) }
+    }
+
+    fn new(reset_type: Self::ResetType) -> Self {
+        control::test::control_energy(reset_type)
+    }
+
+    fn field(&mut self, name: &str) -> Option<Field<'_>> {
+        let field = match name {
+            "lr" => Field::Float(&mut self.lr),
+            "accel_mul_x" => Field::Float(&mut self.accel_mul_x),
+            "accel_add_x" => Field::Float(&mut self.accel_add_x),
+            "accel_mul_y" => Field::Float(&mut self.accel_mul_y),
+            "accel_add_y" => Field::Float(&mut self.accel_add_y),
+            name => return base_field(self, name)
+        };
+        Some(field)
+    }
+
+    unsafe fn setup(&mut self, reset_type: Self::ResetType, initial_speed: PaddedVec2, fighter: &mut StubFighter) {
+        let initial_speed = Vector3f { x: initial_speed.x, y: initial_speed.y, z: 0.0 };
+        control::setup_control(self, reset_type, &initial_speed, 0, fighter);
+    }
+
+    unsafe fn update(&mut self, fighter: &mut StubFighter) {
+        control::update_control(self, fighter);
+    }
+}
+
+impl GoldenEnergy for FighterKineticEnergyMotion {
+    type ResetType = EnergyMotionResetType;
+    const RESET_TYPES: u32 = EnergyMotionResetType::LadderTrans as u32 + 1;
+
+    fn reset_type(raw: u32) -> Self::ResetType {
+        unsafe { std::mem::transmute(raw) }
+    }
+
+    fn new(reset_type: Self::ResetType) -> Self {
+        motion::test::motion_energy(reset_type)
+    }
+
+    fn field(&mut self, name: &str) -> Option<Field<'_>> {
+        let field = match name {
+            "lr" => Field::Float(&mut self.lr),
+            "angle" => Field::Float(&mut self.angle),
+            "angle_whole" => Field::Float(&mut self.angle_whole),
+            "angle_intp_end" => Field::Float(&mut self.angle_intp_end),
+            "angle_intp_frames_remaining" => Field::Int(&mut self.angle_intp_frames_remaining),
+            "speed_mul" => Field::Float(&mut self.speed_mul),
+            "prev_speed" => Field::Vec2(&mut self.prev_speed),
+            "speed_mul_2nd" => Field::Vec2(&mut self.speed_mul_2nd),
+            "update_flag" => Field::Bool(&mut self.update_flag),
+            name => return base_field(self, name)
+        };
+        Some(field)
+    }
+
+    unsafe fn setup(&mut self, reset_type: Self::ResetType, initial_speed: PaddedVec2, fighter: &mut StubFighter) {
+        let initial_speed = Vector3f { x: initial_speed.x, y: initial_speed.y, z: 0.0 };
+        motion::setup_motion(self, reset_type, &initial_speed, 0, fighter);
+    }
+
+    unsafe fn update(&mut self, fighter: &mut StubFighter) {
+        motion::update_motion(self, fighter);
+    }
+}
+
+impl GoldenEnergy for FighterKineticEnergyStop {
+    type ResetType = EnergyStopResetType;
+    const RESET_TYPES: u32 = EnergyStopResetType::DamageAirOrbit as u32 + 1;
+
+    fn reset_type(raw: u32) -> Self::ResetType {
+        unsafe { std::mem::transmute(raw) }
+    }
+
+    fn new(reset_type: Self::ResetType) -> Self {
+        stop::test::stop_energy(reset_type)
+    }
+
+    fn field(&mut self, name: &str) -> Option<Field<'_>> {
+        let field = match name {
+            "damage_target_speed" => Field::Vec2(&mut self.damage_target_speed),
+            "elapsed_hitstop_frames" => Field::Float(&mut self.elapsed_hitstop_frames),
+            "hitstop_frames" => Field::Float(&mut self.hitstop_frames),
+            "interpolation_frames_remaining" => Field::Byte(&mut self.interpolation_frames_remaining),
+            name => return base_field(self, name)
+        };
+        Some(field)
+    }
+
+    unsafe fn setup(&mut self, reset_type: Self::ResetType, initial_speed: PaddedVec2, fighter: &mut StubFighter) {
+        stop::setup_stop_energy(self, reset_type, &initial_speed, 0, fighter);
+    }
+
+    unsafe fn update(&mut self, fighter: &mut StubFighter) {
+        stop::update_stop_energy(self, fighter);
+    }
+}
+
+fn reset_type<E: GoldenEnergy>(name: &str) -> E::ResetType {
+    (0..E::RESET_TYPES)
+        .map(E::reset_type)
+        .find(|reset_type| format!("{:?}", reset_type) == name)
+        .unwrap_or_else(|| panic!("unknown reset type {}", name))
+}
+
+fn apply_energy<E: GoldenEnergy>(energy: &mut E, value: &Value) {
+    for (name, value) in fields(value) {
+        match energy.field(name) {
+            Some(Field::Vec2(field)) => *field = vec2(value),
+            Some(Field::Float(field)) => *field = float(value),
+            Some(Field::Bool(field)) => *field = value.as_bool().unwrap(),
+            Some(Field::Int(field)) => *field = id_value(value),
+            Some(Field::Byte(field)) => *field = id_value(value) as u8,
+            None => panic!("unknown energy field {}", name)
+        }
+    }
+}
+
+/// Checks the fields listed in `expect`, returning the mismatches
+fn check<E: GoldenEnergy>(energy: &mut E, fighter: &StubFighter, expect: &Value, tolerance: f32) -> Vec<String> {
+    let close = |actual: f32, expected: f32| (actual - expected).abs() <= tolerance;
+    let mut errors = Vec::new();
+    for (name, expected) in fields(expect) {
+        if name == "floats" {
+            for (work, expected) in fields(expected) {
+                let actual = fighter.floats.get(&id(work)).copied().unwrap_or(0.0);
+                if !close(actual, float(expected)) {
+                    errors.push(format!("float {} is {} instead of {}", work, actual, expected));
+                }
+            }
+            continue;
+        }
+
+        let (matches, actual) = match energy.field(name) {
+            Some(Field::Vec2(actual)) => {
+                let expected = vec2(expected);
+                (close(actual.x, expected.x) && close(actual.y, expected.y), format!("[{}, {}]", actual.x, actual.y))
+            },
+            Some(Field::Float(actual)) => (close(*actual, float(expected)), actual.to_string()),
+            Some(Field::Bool(actual)) => (Some(*actual) == expected.as_bool(), actual.to_string()),
+            Some(Field::Int(actual)) => (*actual == id_value(expected), actual.to_string()),
+            Some(Field::Byte(actual)) => (*actual as i32 == id_value(expected), actual.to_string()),
+            None => panic!("unknown energy field {}", name)
+        };
+        if !matches {
+            errors.push(format!("{} is {} instead of {}", name, actual, expected));
+        }
+    }
+    errors
+}
+
+/// Runs a single vector and returns its reset type
+fn run_vector<E: GoldenEnergy>(vector: &Value) -> E::ResetType {
+    let name = vector.get("name").and_then(Value::as_str).expect("every vector needs a name");
+    match vector.get("source").and_then(Value::as_str) {
+        Some("derived") => {},
+        Some("capture") => assert!(vector.get("capture").and_then(Value::as_str).is_some(), "{}: captured vectors need to say where they were captured", name),
+        _ => panic!("{}: every vector needs a source of \"derived\" or \"capture\"", name)
+    }
+    let reset_type = reset_type::<E>(vector.get("reset_type").and_then(Value::as_str).expect("every vector needs a reset type"));
+    let tolerance = vector.get("tolerance").map_or(DEFAULT_TOLERANCE, float);
+
+    let mut fighter = StubFighter::new();
+    if let Some(value) = vector.get("fighter") {
+        apply_fighter(&mut fighter, value);
+    }
+
+    let mut energy = E::new(reset_type);
+    if let Some(value) = vector.get("energy") {
+        apply_energy(&mut energy, value);
+    }
+
+    let initial_speed = vector.get("initial_speed").map_or_else(PaddedVec2::zeros, vec2);
+    unsafe {
+        energy.setup(reset_type, initial_speed, &mut fighter);
+    }
+
+    let mut errors = Vec::new();
+    if let Some(expect) = vector.get("after_setup") {
+        errors.extend(check(&mut energy, &fighter, expect, tolerance).into_iter().map(|error| format!("after setup: {}", error)));
+    }
+
+    let frames = vector.get("frames").and_then(Value::as_array).unwrap_or(&[]);
+    for (index, frame) in frames.iter().enumerate() {
+        if let Some(value) = frame.get("fighter") {
+            apply_fighter(&mut fighter, value);
+        }
+        if let Some(value) = frame.get("energy") {
+            apply_energy(&mut energy, value);
+        }
+        unsafe {
+            energy.update(&mut fighter);
+        }
+        if let Some(expect) = frame.get("expect") {
+            errors.extend(check(&mut energy, &fighter, expect, tolerance).into_iter().map(|error| format!("frame {}: {}", index + 1, error)));
+        }
+    }
+
+    assert!(errors.is_empty(), "{} ({:?}):\n{}", name, reset_type, errors.join("\n"));
+    reset_type
+}
+
+fn run_corpus<E: GoldenEnergy>(corpus: &str) {
+    let corpus = json::parse(corpus).unwrap_or_else(|error| panic!("invalid corpus: {}", error));
+    let vectors = corpus.get("vectors").and_then(Value::as_array).expect("the corpus needs a list of vectors");
+
+    let covered = vectors.iter().map(run_vector::<E>).map(|reset_type| format!("{:?}", reset_type)).collect::<Vec<_>>();
+    let missing = (0..E::RESET_TYPES)
+        .map(|raw| format!("{:?}", E::reset_type(raw)))
+        .filter(|reset_type| !covered.contains(reset_type))
+        .collect::<Vec<_>>();
+    assert!(missing.is_empty(), "reset types without a vector: {}", missing.join(", "));
+}
+
+#[test]
+fn control() {
+    run_corpus::<FighterKineticEnergyControl>(include_str!("../tests/golden/control.json"));
+}
+
+#[test]
+fn motion() {
+    run_corpus::<FighterKineticEnergyMotion>(include_str!("../tests/golden/motion.json"));
+}
+
+#[test]
+fn stop() {
+    run_corpus::<FighterKineticEnergyStop>(include_str!("../tests/golden/stop.json"));
+}
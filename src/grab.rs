@@ -0,0 +1,133 @@
+use super::energy::PaddedVec2;
+
+/// Values used by the stop energy for grab related movement
+#[derive(Debug, Copy, Clone)]
+#[repr(C)]
+pub struct GrabParams {
+    /// The joint of the grabber that the captured fighter is held at, the grabber's position is used if they don't have it
+    pub hold_joint: u64,
+    /// The fraction of the distance to the hold position that the captured fighter covers each frame (Capture). When this
+    /// is 0 the fighter is held in place like the game does
+    pub pull_in_rate: f32,
+    /// The fastest the captured fighter can be pulled in
+    pub pull_in_speed_max: f32,
+    /// Once the captured fighter is closer than this they are snapped to the hold position
+    pub pull_in_snap_distance: f32,
+    /// The speed the fighter is pushed backwards with when a grab is broken (CatchCut). When this is 0 the fighter keeps
+    /// the speed they had
+    pub release_speed: f32,
+    /// How far a dash grab slides before stopping (CatchDash). When this is 0 the game's `catch_dash_brake_mul` is used
+    pub catch_dash_slide_distance: f32,
+    /// Multiplier on the speed that is inherited from the grabber's link (CaptureBeetle)
+    pub beetle_follow_mul: f32,
+}
+
+impl GrabParams {
+    pub const DEFAULT: Self = Self {
+        hold_joint: 0x54f934137,
+        pull_in_rate: 0.0,
+        pull_in_speed_max: 1.0,
+        pull_in_snap_distance: 0.5,
+        release_speed: 0.0,
+        catch_dash_slide_distance: 0.0,
+        beetle_follow_mul: 1.0,
+    };
+
+    /// Whether captured fighters are pulled towards the hold position
+    pub fn is_pull_in_enabled(&self) -> bool {
+        self.pull_in_rate > 0.0
+    }
+
+    /// Gets the speed that the captured fighter is pulled towards the hold position with
+    /// # Arguments
+    /// * `offset` - The hold position relative to the captured fighter
+    pub fn pull_in_speed(&self, offset: PaddedVec2) -> PaddedVec2 {
+        let distance = offset.mag();
+        if distance <= self.pull_in_snap_distance {
+            return offset;
+        }
+
+        offset.normalize() * (distance * self.pull_in_rate).min(self.pull_in_speed_max)
+    }
+
+    /// Gets the speed of a fighter after their grab is broken, if a release speed is set
+    /// # Arguments
+    /// * `speed` - The speed of the fighter when the grab was broken
+    /// * `lr` - The direction the fighter is facing
+    pub fn release_speed(&self, speed: PaddedVec2, lr: f32) -> Option<PaddedVec2> {
+        if self.release_speed == 0.0 {
+            return None;
+        }
+
+        Some(PaddedVec2::new(speed.x - lr * self.release_speed, speed.y))
+    }
+
+    /// Gets the brake needed for a dash grab starting at `speed_x` to slide `catch_dash_slide_distance`,
+    /// if a distance is set
+    pub fn catch_dash_brake(&self, speed_x: f32) -> Option<f32> {
+        if self.catch_dash_slide_distance <= 0.0 {
+            return None;
+        }
+
+        // the fighter moves by speed_x first and then brakes, so that first frame isn't part of the slide
+        let slide = (self.catch_dash_slide_distance - speed_x.abs()).max(1.0e-3);
+        Some(speed_x.powi(2) / (2.0 * slide))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const PULL_IN: GrabParams = GrabParams {
+        pull_in_rate: 0.2,
+        ..GrabParams::DEFAULT
+    };
+
+    #[test]
+    fn pull_in_snaps_when_close() {
+        let params = PULL_IN;
+        let offset = PaddedVec2::new(0.1, 0.1);
+        let speed = params.pull_in_speed(offset);
+        assert_eq!(speed.x, offset.x);
+        assert_eq!(speed.y, offset.y);
+    }
+
+    #[test]
+    fn pull_in_is_capped() {
+        assert!(!GrabParams::DEFAULT.is_pull_in_enabled());
+        let params = PULL_IN;
+        let speed = params.pull_in_speed(PaddedVec2::new(-50.0, 0.0));
+        assert!((speed.x + params.pull_in_speed_max).abs() < 1.0e-5);
+    }
+
+    #[test]
+    fn release_needs_a_speed() {
+        let params = GrabParams::DEFAULT;
+        assert!(params.release_speed(PaddedVec2::zeros(), 1.0).is_none());
+
+        let params = GrabParams {
+            release_speed: 0.6,
+            ..GrabParams::DEFAULT
+        };
+        let speed = params.release_speed(PaddedVec2::new(0.5, 1.0), 1.0).unwrap();
+        assert!((speed.x + 0.1).abs() < 1.0e-5);
+        assert_eq!(speed.y, 1.0);
+    }
+
+    #[test]
+    fn catch_dash_slides_distance() {
+        let params = GrabParams {
+            catch_dash_slide_distance: 10.0,
+            ..GrabParams::DEFAULT
+        };
+        let brake = params.catch_dash_brake(2.0).unwrap();
+        let mut speed = 2.0f32;
+        let mut distance = 0.0;
+        while speed > 0.0 {
+            distance += speed;
+            speed = (speed - brake).max(0.0);
+        }
+        assert!((distance - 10.0).abs() < 2.0);
+    }
+}
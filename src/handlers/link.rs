@@ -0,0 +1,63 @@
+use super::*;
+
+/// The aerial Spin Attack, which limits the horizontal drift and speed that is carried into it
+struct RevolveSlash;
+
+impl EnergyHandler for RevolveSlash {
+    fn initialize_control(&self, energy: &mut FighterKineticEnergyControl, reset_type: EnergyControllerResetType, boma: &mut dyn FighterAccessor) -> bool {
+        if reset_type != EnergyControllerResetType::RevolveSlashAir {
+            return false;
+        }
+
+        let max_x_mul = boma.get_param_float(smash::hash40("param_special_hi"), smash::hash40("rslash_air_max_x_mul"));
+        let speed_max = boma.get_param_float(smash::hash40("air_speed_x_stable"), 0) * max_x_mul;
+
+        energy.speed_max = PaddedVec2::new(speed_max, -1.0);
+        energy.speed_brake = PaddedVec2::new(
+            boma.get_param_float(smash::hash40("air_brake_x"), 0),
+            0.0
+        );
+        energy.speed_limit = PaddedVec2::new(
+            boma.get_param_float(smash::hash40("common"), smash::hash40("air_speed_x_limit")),
+            0.0
+        );
+        energy.accel_mul_x = max_x_mul;
+        true
+    }
+
+    fn setup_control(&self, energy: &mut FighterKineticEnergyControl, reset_type: EnergyControllerResetType, boma: &mut dyn FighterAccessor) -> bool {
+        if reset_type != EnergyControllerResetType::RevolveSlashAir {
+            return false;
+        }
+
+        energy.speed.x *= boma.get_param_float(smash::hash40("rslash_air_spd_x_mul"), 0);
+        true
+    }
+}
+
+static REVOLVE_SLASH: RevolveSlash = RevolveSlash;
+
+pub fn install() {
+    for kind in [*FIGHTER_KIND_LINK, *FIGHTER_KIND_YOUNGLINK, *FIGHTER_KIND_TOONLINK].iter() {
+        register(*kind, None, &REVOLVE_SLASH);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fighter::stub::*;
+
+    #[test]
+    fn revolve_slash_limits_drift() {
+        let mut energy = crate::control::test::control_energy(EnergyControllerResetType::RevolveSlashAir);
+        let mut fighter = StubFighter::new()
+            .with_param("air_speed_x_stable", "", 1.0)
+            .with_param("param_special_hi", "rslash_air_max_x_mul", 0.5);
+
+        assert!(REVOLVE_SLASH.initialize_control(&mut energy, EnergyControllerResetType::RevolveSlashAir, &mut fighter));
+        assert_eq!(energy.speed_max.x, 0.5);
+        assert_eq!(energy.accel_mul_x, 0.5);
+        assert!(!REVOLVE_SLASH.initialize_control(&mut energy, EnergyControllerResetType::FallAdjust, &mut fighter));
+    }
+}
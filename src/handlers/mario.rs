@@ -0,0 +1,49 @@
+use super::*;
+
+/// Super Jump Punch (AirTransAngleSuperJumpPunch), which adjusts the momentum depending on where the stick is held.
+/// The angle can only grow throughout the animation, so the move can be steered further but never back
+struct SuperJumpPunch;
+
+impl EnergyHandler for SuperJumpPunch {
+    fn motion_speed(&self, energy: &mut FighterKineticEnergyMotion, reset_type: EnergyMotionResetType, move_speed: PaddedVec2, boma: &mut dyn FighterAccessor) -> Option<PaddedVec2> {
+        if reset_type != EnergyMotionResetType::AirTransAngleSuperJumpPunch {
+            return None;
+        }
+
+        let stick_x = boma.get_stick_x();
+        let dir = boma.get_float(*FIGHTER_STATUS_SUPER_JUMP_PUNCH_WORK_FLOAT_CONST_DIR_STICK_X);
+        let angle = if stick_x.abs() <= dir {
+            -energy.angle
+        } else {
+            let interp = (stick_x.abs() - dir) / (1.0 - dir);
+            let interp = interp * boma.get_float(*FIGHTER_STATUS_SUPER_JUMP_PUNCH_WORK_FLOAT_CONST_DIR_MUL);
+            let new_angle = -interp.to_radians();
+            if energy.angle.abs() < new_angle.abs() {
+                energy.angle = new_angle;
+            }
+            energy.angle
+        };
+        Some(move_speed.rotate(-angle))
+    }
+
+    fn setup_motion(&self, energy: &mut FighterKineticEnergyMotion, reset_type: EnergyMotionResetType, boma: &mut dyn FighterAccessor) -> bool {
+        if reset_type != EnergyMotionResetType::AirTransAngleSuperJumpPunch {
+            return false;
+        }
+
+        energy.speed_mul = if boma.is_flag(*FIGHTER_STATUS_SUPER_JUMP_PUNCH_FLAG_AIR_MOTION) {
+            boma.get_float(*FIGHTER_STATUS_SUPER_JUMP_PUNCH_WORK_FLOAT_CONST_AIR_PASS_MUL)
+        } else {
+            boma.get_float(*FIGHTER_STATUS_SUPER_JUMP_PUNCH_WORK_FLOAT_CONST_PASS_MUL)
+        };
+        true
+    }
+}
+
+static SUPER_JUMP_PUNCH: SuperJumpPunch = SuperJumpPunch;
+
+pub fn install() {
+    for kind in [*FIGHTER_KIND_MARIO, *FIGHTER_KIND_MARIOD, *FIGHTER_KIND_LUIGI].iter() {
+        register(*kind, None, &SUPER_JUMP_PUNCH);
+    }
+}
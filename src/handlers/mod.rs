@@ -0,0 +1,204 @@
+//! Energy logic that only applies to specific fighters.
+//!
+//! Handlers are registered for a fighter kind (and optionally a status kind) and get the first chance to handle a
+//! reset type before the shared logic does. Only the parts of the energies that are actually fighter specific
+//! can be handled here, returning `false`/`None` falls back to the shared logic
+
+use std::sync::RwLock;
+
+use super::{
+    *,
+    control::{EnergyControllerResetType, FighterKineticEnergyControl},
+    energy::PaddedVec2,
+    fighter::FighterAccessor,
+    motion::{EnergyMotionResetType, FighterKineticEnergyMotion}
+};
+
+mod link;
+mod mario;
+
+#[allow(unused_variables)]
+pub trait EnergyHandler: Sync {
+    /// Sets up the control energy's params, returns true if the reset type was handled
+    fn initialize_control(&self, energy: &mut FighterKineticEnergyControl, reset_type: EnergyControllerResetType, boma: &mut dyn FighterAccessor) -> bool {
+        false
+    }
+
+    /// Sets up the control energy's speed when it's reset, returns true if the reset type was handled
+    fn setup_control(&self, energy: &mut FighterKineticEnergyControl, reset_type: EnergyControllerResetType, boma: &mut dyn FighterAccessor) -> bool {
+        false
+    }
+
+    /// Gets the speed of the motion energy from the speed of the animation, returns `None` if the reset type wasn't handled
+    fn motion_speed(&self, energy: &mut FighterKineticEnergyMotion, reset_type: EnergyMotionResetType, move_speed: PaddedVec2, boma: &mut dyn FighterAccessor) -> Option<PaddedVec2> {
+        None
+    }
+
+    /// Sets up the motion energy when it's reset, returns true if the reset type was handled
+    fn setup_motion(&self, energy: &mut FighterKineticEnergyMotion, reset_type: EnergyMotionResetType, boma: &mut dyn FighterAccessor) -> bool {
+        false
+    }
+}
+
+/// The C ABI version of `EnergyHandler`, for handlers registered by other plugins. Trait objects don't have a stable
+/// layout across separately built plugins, so they register a table of functions instead. Any function can be left
+/// out, which is the same as not handling the reset type. They are only called for fighters of the game, and get the
+/// fighter's module accessor
+#[repr(C)]
+pub struct EnergyHandlerVTable {
+    /// Sets up the control energy's params, returns true if the reset type was handled
+    pub initialize_control: Option<unsafe extern "C" fn(&mut FighterKineticEnergyControl, EnergyControllerResetType, &mut BattleObjectModuleAccessor) -> bool>,
+    /// Sets up the control energy's speed when it's reset, returns true if the reset type was handled
+    pub setup_control: Option<unsafe extern "C" fn(&mut FighterKineticEnergyControl, EnergyControllerResetType, &mut BattleObjectModuleAccessor) -> bool>,
+    /// Writes the speed of the motion energy from the speed of the animation into the last argument, returns true if the
+    /// reset type was handled
+    pub motion_speed: Option<unsafe extern "C" fn(&mut FighterKineticEnergyMotion, EnergyMotionResetType, PaddedVec2, &mut BattleObjectModuleAccessor, &mut PaddedVec2) -> bool>,
+    /// Sets up the motion energy when it's reset, returns true if the reset type was handled
+    pub setup_motion: Option<unsafe extern "C" fn(&mut FighterKineticEnergyMotion, EnergyMotionResetType, &mut BattleObjectModuleAccessor) -> bool>,
+}
+
+impl EnergyHandler for EnergyHandlerVTable {
+    fn initialize_control(&self, energy: &mut FighterKineticEnergyControl, reset_type: EnergyControllerResetType, boma: &mut dyn FighterAccessor) -> bool {
+        match (self.initialize_control, boma.module_accessor()) {
+            (Some(function), Some(boma)) => unsafe { function(energy, reset_type, boma) },
+            _ => false
+        }
+    }
+
+    fn setup_control(&self, energy: &mut FighterKineticEnergyControl, reset_type: EnergyControllerResetType, boma: &mut dyn FighterAccessor) -> bool {
+        match (self.setup_control, boma.module_accessor()) {
+            (Some(function), Some(boma)) => unsafe { function(energy, reset_type, boma) },
+            _ => false
+        }
+    }
+
+    fn motion_speed(&self, energy: &mut FighterKineticEnergyMotion, reset_type: EnergyMotionResetType, move_speed: PaddedVec2, boma: &mut dyn FighterAccessor) -> Option<PaddedVec2> {
+        match (self.motion_speed, boma.module_accessor()) {
+            (Some(function), Some(boma)) => {
+                let mut speed = move_speed;
+                if unsafe { function(energy, reset_type, move_speed, boma, &mut speed) } {
+                    Some(speed)
+                } else {
+                    None
+                }
+            },
+            _ => None
+        }
+    }
+
+    fn setup_motion(&self, energy: &mut FighterKineticEnergyMotion, reset_type: EnergyMotionResetType, boma: &mut dyn FighterAccessor) -> bool {
+        match (self.setup_motion, boma.module_accessor()) {
+            (Some(function), Some(boma)) => unsafe { function(energy, reset_type, boma) },
+            _ => false
+        }
+    }
+}
+
+struct Registration {
+    fighter_kind: i32,
+    status_kind: Option<i32>,
+    handler: &'static dyn EnergyHandler,
+}
+
+pub struct HandlerRegistry {
+    registrations: Vec<Registration>,
+}
+
+impl HandlerRegistry {
+    pub const fn new() -> Self {
+        Self {
+            registrations: Vec::new()
+        }
+    }
+
+    /// Registers a handler, when `status_kind` is `None` the handler is used in every status
+    pub fn register(&mut self, fighter_kind: i32, status_kind: Option<i32>, handler: &'static dyn EnergyHandler) {
+        self.registrations.push(Registration {
+            fighter_kind,
+            status_kind,
+            handler
+        });
+    }
+
+    /// Finds the handler for a fighter, handlers registered for the status take priority over ones that aren't
+    pub fn find(&self, fighter_kind: i32, status_kind: i32) -> Option<&'static dyn EnergyHandler> {
+        let mut for_fighter = self.registrations.iter().filter(|registration| registration.fighter_kind == fighter_kind);
+        for_fighter
+            .clone()
+            .find(|registration| registration.status_kind == Some(status_kind))
+            .or_else(|| for_fighter.find(|registration| registration.status_kind.is_none()))
+            .map(|registration| registration.handler)
+    }
+}
+
+/// Handlers can be registered by other plugins at any time, including while the hooks are reading it
+static REGISTRY: RwLock<HandlerRegistry> = RwLock::new(HandlerRegistry::new());
+
+/// Finds the handler for a fighter in the global registry
+pub fn find(fighter_kind: i32, status_kind: i32) -> Option<&'static dyn EnergyHandler> {
+    REGISTRY.read().unwrap().find(fighter_kind, status_kind)
+}
+
+/// Registers a handler in the global registry
+pub fn register(fighter_kind: i32, status_kind: Option<i32>, handler: &'static dyn EnergyHandler) {
+    REGISTRY.write().unwrap().register(fighter_kind, status_kind, handler);
+}
+
+/// Exposed so that other plugins can add energy logic for their own fighters. A `status_kind` below 0 uses the handler
+/// in every status
+#[no_mangle]
+pub extern "C" fn energy_id_register_handler(fighter_kind: i32, status_kind: i32, handler: &'static EnergyHandlerVTable) {
+    let status_kind = if status_kind < 0 { None } else { Some(status_kind) };
+    register(fighter_kind, status_kind, handler);
+}
+
+/// Registers the handlers for the vanilla fighters
+pub fn install() {
+    mario::install();
+    link::install();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // not zero sized, so that the two statics have different addresses
+    struct Handler(u8);
+    impl EnergyHandler for Handler {}
+
+    static FIGHTER: Handler = Handler(0);
+    static STATUS: Handler = Handler(1);
+
+    fn is(handler: Option<&'static dyn EnergyHandler>, expected: &'static Handler) -> bool {
+        handler.map_or(false, |handler| std::ptr::eq(handler as *const dyn EnergyHandler as *const u8, expected as *const Handler as *const u8))
+    }
+
+    #[test]
+    fn status_handlers_take_priority() {
+        let mut registry = HandlerRegistry::new();
+        registry.register(1, None, &FIGHTER);
+        registry.register(1, Some(5), &STATUS);
+
+        assert!(is(registry.find(1, 5), &STATUS));
+        assert!(is(registry.find(1, 6), &FIGHTER));
+        assert!(registry.find(2, 5).is_none());
+    }
+
+    #[test]
+    fn vtables_need_a_module_accessor() {
+        unsafe extern "C" fn setup_motion(_: &mut FighterKineticEnergyMotion, _: EnergyMotionResetType, _: &mut BattleObjectModuleAccessor) -> bool {
+            panic!("called without a module accessor")
+        }
+
+        let handler = EnergyHandlerVTable {
+            initialize_control: None,
+            setup_control: None,
+            motion_speed: None,
+            setup_motion: Some(setup_motion)
+        };
+        let mut energy = crate::motion::test::motion_energy(EnergyMotionResetType::AirTransAngleSuperJumpPunch);
+        let mut fighter = crate::fighter::stub::StubFighter::new();
+        assert!(!handler.setup_motion(&mut energy, EnergyMotionResetType::AirTransAngleSuperJumpPunch, &mut fighter));
+        assert!(handler.motion_speed(&mut energy, EnergyMotionResetType::AirTransAngleSuperJumpPunch, PaddedVec2::zeros(), &mut fighter).is_none());
+    }
+}
@@ -0,0 +1,117 @@
+use super::energy::PaddedVec2;
+
+/// What happens to the motion energy's speed once the animation stops driving it
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[repr(C)]
+pub enum HandOffPolicy {
+    /// What the game does, ladders stop immediately and everything else brakes down to 0
+    Vanilla,
+    /// Stops immediately
+    Stop,
+    /// Keeps the speed and brakes down to 0 with the energy's brake. This is what `Vanilla` does for everything but
+    /// ladders, so it only differs from it on them
+    KeepAndBrake,
+    /// Moves the speed into the control energy, so that the fighter can steer out of it
+    TransferToControl,
+    /// Brakes down to 0 over the given number of frames, the ground's traction doesn't change how long that takes
+    Decay(u32),
+}
+
+/// Selects a policy for a reset type and/or a status, a value below 0 matches anything
+#[derive(Debug, Copy, Clone)]
+#[repr(C)]
+pub struct HandOffRule {
+    pub reset_type: i32,
+    pub status_kind: i32,
+    pub policy: HandOffPolicy,
+}
+
+impl HandOffRule {
+    pub fn matches(&self, reset_type: u32, status_kind: i32) -> bool {
+        (self.reset_type < 0 || self.reset_type as u32 == reset_type)
+            && (self.status_kind < 0 || self.status_kind == status_kind)
+    }
+}
+
+/// Values used by the motion energy when the animation stops driving the fighter's movement
+#[derive(Debug, Copy, Clone)]
+#[repr(C)]
+pub struct HandOffParams {
+    /// Checked in order, the first rule that matches is used. Without a match the policy is `Vanilla`
+    pub rules: [Option<HandOffRule>; 16],
+}
+
+impl HandOffParams {
+    pub const DEFAULT: Self = Self {
+        rules: [None; 16],
+    };
+
+    /// Gets the policy to use
+    /// # Arguments
+    /// * `reset_type` - The reset type of the motion energy
+    /// * `status_kind` - The status of the fighter
+    pub fn policy(&self, reset_type: u32, status_kind: i32) -> HandOffPolicy {
+        self.rules
+            .iter()
+            .flatten()
+            .find(|rule| rule.matches(reset_type, status_kind))
+            .map_or(HandOffPolicy::Vanilla, |rule| rule.policy)
+    }
+
+    /// Adds a rule after the existing ones, returns false if there is no room left
+    pub fn push(&mut self, rule: HandOffRule) -> bool {
+        match self.rules.iter_mut().find(|slot| slot.is_none()) {
+            Some(slot) => {
+                *slot = Some(rule);
+                true
+            },
+            None => false
+        }
+    }
+}
+
+/// Gets the brake needed to stop `speed` in `frames` frames, per axis so that the direction is kept while braking
+pub fn decay_brake(speed: PaddedVec2, frames: u32) -> PaddedVec2 {
+    speed.abs() / frames.max(1) as f32
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn first_matching_rule_wins() {
+        let mut params = HandOffParams::DEFAULT;
+        assert_eq!(params.policy(4, 10), HandOffPolicy::Vanilla);
+
+        assert!(params.push(HandOffRule { reset_type: 4, status_kind: 10, policy: HandOffPolicy::Stop }));
+        assert!(params.push(HandOffRule { reset_type: 4, status_kind: -1, policy: HandOffPolicy::Decay(5) }));
+        assert!(params.push(HandOffRule { reset_type: -1, status_kind: 11, policy: HandOffPolicy::TransferToControl }));
+
+        assert_eq!(params.policy(4, 10), HandOffPolicy::Stop);
+        assert_eq!(params.policy(4, 11), HandOffPolicy::Decay(5));
+        assert_eq!(params.policy(2, 11), HandOffPolicy::TransferToControl);
+        assert_eq!(params.policy(2, 10), HandOffPolicy::Vanilla);
+    }
+
+    #[test]
+    fn rules_are_limited() {
+        let mut params = HandOffParams::DEFAULT;
+        let rule = HandOffRule { reset_type: -1, status_kind: -1, policy: HandOffPolicy::Stop };
+        assert!((0..16).all(|_| params.push(rule)));
+        assert!(!params.push(rule));
+    }
+
+    #[test]
+    fn decay_stops_in_frames() {
+        let mut speed = PaddedVec2::new(-3.0, 1.5);
+        let brake = decay_brake(speed, 3);
+        for _ in 0..3 {
+            speed = PaddedVec2::new(
+                speed.x + brake.x.min(speed.x.abs()) * -speed.x.signum(),
+                speed.y + brake.y.min(speed.y.abs()) * -speed.y.signum()
+            );
+        }
+        assert!(speed.mag() < 1.0e-5);
+    }
+}
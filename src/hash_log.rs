@@ -0,0 +1,283 @@
+//! The format of the per-frame energy hash logs, and how two of them are compared.
+//!
+//! Each frame is one line: the frame number, the hash of every field, and then every field with the bits of its value
+//! in hex, so that the field that diverged first can be found without having to log the full state twice:
+//! ```text
+//! frame=120 hash=9c1f0a3e5b7d2c41 0.control.speed.x=3f800000 0.control.speed.y=00000000 ...
+//! ```
+//! The fields can be left out to only log the hash, which can be worked out with `FrameHasher` without building the
+//! record. Logs like that can still tell which frame diverged, but not which field.
+//!
+//! This file only uses std, since the host tool in tools/energy_hash_diff builds it on its own
+
+use std::fmt;
+
+/// The fields of every fighter's energies on one frame
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FrameRecord {
+    pub frame: u64,
+    pub fields: Vec<(String, u32)>,
+}
+
+const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, byte| (hash ^ *byte as u64).wrapping_mul(FNV_PRIME))
+}
+
+/// Receives the fields of a frame, in the same order on every machine. A field's name is its parts joined with `.`,
+/// and is split into the prefix and the rest so that neither has to be put together to hash it
+pub trait FieldSink {
+    fn field(&mut self, prefix: &[&str], name: &[&str], value: u32);
+}
+
+/// Hashes the fields of a frame as they come in, which is the same as `FrameRecord::hash` without building the record
+pub struct FrameHasher {
+    hash: u64,
+}
+
+impl FrameHasher {
+    pub fn new() -> Self {
+        Self {
+            hash: FNV_OFFSET
+        }
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.hash
+    }
+}
+
+impl FieldSink for FrameHasher {
+    fn field(&mut self, prefix: &[&str], name: &[&str], value: u32) {
+        for (index, part) in prefix.iter().chain(name).enumerate() {
+            if index != 0 {
+                self.hash = fnv1a(self.hash, b".");
+            }
+            self.hash = fnv1a(self.hash, part.as_bytes());
+        }
+        self.hash = fnv1a(self.hash, &[0]);
+        self.hash = fnv1a(self.hash, &value.to_le_bytes());
+    }
+}
+
+impl FieldSink for FrameRecord {
+    fn field(&mut self, prefix: &[&str], name: &[&str], value: u32) {
+        let name = prefix.iter().chain(name).copied().collect::<Vec<_>>().join(".");
+        self.push(name, value);
+    }
+}
+
+/// Writes the line of a frame that only has its hash
+pub fn hash_line(frame: u64, hash: u64) -> String {
+    format!("frame={} hash={:016x}", frame, hash)
+}
+
+impl FrameRecord {
+    pub fn new(frame: u64) -> Self {
+        Self {
+            frame,
+            fields: Vec::new()
+        }
+    }
+
+    /// Adds a field, with its value as bits
+    pub fn push(&mut self, name: String, value: u32) {
+        self.fields.push((name, value));
+    }
+
+    /// The fingerprint of the frame, this depends on the order the fields were pushed in
+    pub fn hash(&self) -> u64 {
+        self.fields.iter().fold(FNV_OFFSET, |hash, (name, value)| {
+            let hash = fnv1a(hash, name.as_bytes());
+            let hash = fnv1a(hash, &[0]);
+            fnv1a(hash, &value.to_le_bytes())
+        })
+    }
+
+    /// Parses a line written by `Display`
+    pub fn parse(line: &str) -> Option<(Self, u64)> {
+        let mut parts = line.split_whitespace();
+        let frame = parts.next()?.strip_prefix("frame=")?.parse().ok()?;
+        let hash = u64::from_str_radix(parts.next()?.strip_prefix("hash=")?, 16).ok()?;
+        let fields = parts.map(|part| {
+            let (name, value) = part.split_at(part.rfind('=')?);
+            Some((name.to_owned(), u32::from_str_radix(&value[1..], 16).ok()?))
+        }).collect::<Option<Vec<_>>>()?;
+        Some((Self { frame, fields }, hash))
+    }
+}
+
+impl fmt::Display for FrameRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "frame={} hash={:016x}", self.frame, self.hash())?;
+        for (name, value) in self.fields.iter() {
+            write!(f, " {}={:08x}", name, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Where two logs stopped agreeing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Divergence {
+    /// A field has a different value, or is only in one of the logs
+    Field { frame: u64, field: String, left: Option<u32>, right: Option<u32> },
+    /// The hashes are different but every field matches, which means that only the hashes were logged or that a line was
+    /// edited or corrupted
+    Hash { frame: u64 },
+    /// One log has a frame that the other doesn't
+    MissingFrame { frame: u64, in_left: bool },
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let value = |value: &Option<u32>| value.map_or_else(|| "missing".to_owned(), |value| format!("{:08x} ({})", value, f32::from_bits(value)));
+        match self {
+            Divergence::Field { frame, field, left, right } => write!(f, "frame {}: {} is {} on the left and {} on the right", frame, field, value(left), value(right)),
+            Divergence::Hash { frame } => write!(f, "frame {}: the hashes differ but every logged field matches (log the fields to find the one that diverged)", frame),
+            Divergence::MissingFrame { frame, in_left: true } => write!(f, "frame {} is only in the left log", frame),
+            Divergence::MissingFrame { frame, in_left: false } => write!(f, "frame {} is only in the right log", frame)
+        }
+    }
+}
+
+/// Parses a whole log, skipping the lines that aren't frame records so that it can be pulled out of a full plugin log
+pub fn parse_log(text: &str) -> Vec<(FrameRecord, u64)> {
+    text.lines()
+        .filter_map(|line| line.find("frame=").and_then(|start| FrameRecord::parse(&line[start..])))
+        .collect()
+}
+
+fn compare_frame(left: &(FrameRecord, u64), right: &(FrameRecord, u64)) -> Option<Divergence> {
+    let (left, left_hash) = left;
+    let (right, right_hash) = right;
+    if left_hash == right_hash {
+        return None;
+    }
+
+    let frame = left.frame;
+    for (name, value) in left.fields.iter() {
+        let other = right.fields.iter().find(|(other, _)| other == name).map(|(_, value)| *value);
+        if other != Some(*value) {
+            return Some(Divergence::Field { frame, field: name.clone(), left: Some(*value), right: other });
+        }
+    }
+    for (name, value) in right.fields.iter() {
+        if !left.fields.iter().any(|(other, _)| other == name) {
+            return Some(Divergence::Field { frame, field: name.clone(), left: None, right: Some(*value) });
+        }
+    }
+    Some(Divergence::Hash { frame })
+}
+
+/// Finds the first frame, and the first field on it, where two logs disagree. Frames are matched up by their number,
+/// so the logs don't need to start on the same frame
+pub fn first_divergence(left: &[(FrameRecord, u64)], right: &[(FrameRecord, u64)]) -> Option<Divergence> {
+    let mut left = left.iter().peekable();
+    let mut right = right.iter().peekable();
+    let start = match (left.peek(), right.peek()) {
+        (Some(left), Some(right)) => left.0.frame.max(right.0.frame),
+        _ => return None
+    };
+
+    // frames before both logs started can't be compared
+    let mut left = left.skip_while(|record| record.0.frame < start).peekable();
+    let mut right = right.skip_while(|record| record.0.frame < start).peekable();
+    loop {
+        match (left.peek(), right.peek()) {
+            (Some(l), Some(r)) if l.0.frame == r.0.frame => {
+                if let Some(divergence) = compare_frame(l, r) {
+                    return Some(divergence);
+                }
+                left.next();
+                right.next();
+            },
+            (Some(l), Some(r)) => {
+                return Some(Divergence::MissingFrame { frame: l.0.frame.min(r.0.frame), in_left: l.0.frame < r.0.frame });
+            },
+            // one log just ended before the other, which isn't a desync
+            _ => return None
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn record(frame: u64, speed: f32) -> FrameRecord {
+        let mut record = FrameRecord::new(frame);
+        record.push("0.control.speed.x".to_owned(), speed.to_bits());
+        record.push("0.stop.reset_type".to_owned(), -1i32 as u32);
+        record.push("0.motion.update_flag".to_owned(), true as u32);
+        record
+    }
+
+    fn log(records: &[FrameRecord]) -> Vec<(FrameRecord, u64)> {
+        parse_log(&records.iter().map(|record| format!("[energy_id] {}\n", record)).collect::<String>())
+    }
+
+    #[test]
+    fn line_round_trip() {
+        let record = record(7, 1.5);
+        let (parsed, hash) = FrameRecord::parse(&record.to_string()).unwrap();
+        assert_eq!(parsed, record);
+        assert_eq!(hash, record.hash());
+        assert_ne!(record.hash(), self::record(7, 1.25).hash());
+        assert!(FrameRecord::parse("frame=1 hash=zz").is_none());
+    }
+
+    #[test]
+    fn hasher_matches_record() {
+        let mut record = FrameRecord::new(7);
+        let mut hasher = FrameHasher::new();
+        for sink in [&mut record as &mut dyn FieldSink, &mut hasher].iter_mut() {
+            sink.field(&["0", "control"], &["speed", "x"], 1.5f32.to_bits());
+            sink.field(&["0", "stop"], &["reset_type"], 3);
+        }
+        assert_eq!(record.fields[0].0, "0.control.speed.x");
+        assert_eq!(hasher.finish(), record.hash());
+
+        // a log with only the hashes still finds the frame
+        let (parsed, hash) = FrameRecord::parse(&hash_line(7, hasher.finish())).unwrap();
+        assert_eq!(hash, record.hash());
+        assert!(parsed.fields.is_empty());
+        let other = FrameRecord::parse(&hash_line(7, 0)).unwrap();
+        assert_eq!(first_divergence(&[(parsed, hash)], &[other]), Some(Divergence::Hash { frame: 7 }));
+    }
+
+    #[test]
+    fn finds_first_divergent_field() {
+        let left = log(&[record(1, 1.0), record(2, 2.0), record(3, 3.0)]);
+        let right = log(&[record(1, 1.0), record(2, 2.5), record(3, 4.0)]);
+        assert_eq!(first_divergence(&left, &left), None);
+        assert_eq!(first_divergence(&left, &right), Some(Divergence::Field {
+            frame: 2,
+            field: "0.control.speed.x".to_owned(),
+            left: Some(2.0f32.to_bits()),
+            right: Some(2.5f32.to_bits())
+        }));
+    }
+
+    #[test]
+    fn frames_are_matched_by_number() {
+        let left = log(&[record(1, 1.0), record(2, 2.0), record(3, 3.0)]);
+        let right = log(&[record(2, 2.0), record(3, 3.0), record(4, 4.0)]);
+        assert_eq!(first_divergence(&left, &right), None);
+
+        let right = log(&[record(1, 1.0), record(3, 3.0)]);
+        assert_eq!(first_divergence(&left, &right), Some(Divergence::MissingFrame { frame: 2, in_left: true }));
+
+        let mut extra = record(1, 1.0);
+        extra.push("1.control.reset_type".to_owned(), 3);
+        let right = log(&[extra]);
+        assert_eq!(first_divergence(&left, &right), Some(Divergence::Field {
+            frame: 1,
+            field: "1.control.reset_type".to_owned(),
+            left: None,
+            right: Some(3)
+        }));
+    }
+}
@@ -0,0 +1,365 @@
+//! A small JSON reader and writer for the snapshot and test vector formats, so that the plugin doesn't need to pull in
+//! a serialization framework for a handful of flat documents
+
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    /// The fields are kept in the order they were written in
+    Object(Vec<(String, Value)>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// The byte offset that the error was found at
+    pub offset: usize,
+    pub message: &'static str,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at byte {}", self.message, self.offset)
+    }
+}
+
+impl Value {
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Object(fields) => fields.iter().find(|(name, _)| name == key).map(|(_, value)| value),
+            _ => None
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Number(number) => Some(*number),
+            _ => None
+        }
+    }
+
+    pub fn as_f32(&self) -> Option<f32> {
+        self.as_f64().map(|number| number as f32)
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        self.as_f64().filter(|number| number.fract() == 0.0).map(|number| number as i64)
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(value) => Some(*value),
+            _ => None
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(string) => Some(string),
+            _ => None
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Value::Array(values) => Some(values),
+            _ => None
+        }
+    }
+
+    /// Builds an object from its fields
+    pub fn object<'a>(fields: impl IntoIterator<Item = (&'a str, Value)>) -> Value {
+        Value::Object(fields.into_iter().map(|(name, value)| (name.to_owned(), value)).collect())
+    }
+}
+
+impl From<f32> for Value {
+    fn from(number: f32) -> Self {
+        Value::Number(number as f64)
+    }
+}
+
+impl From<i32> for Value {
+    fn from(number: i32) -> Self {
+        Value::Number(number as f64)
+    }
+}
+
+impl From<u32> for Value {
+    fn from(number: u32) -> Self {
+        Value::Number(number as f64)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(string: &str) -> Self {
+        Value::String(string.to_owned())
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter, string: &str) -> fmt::Result {
+    f.write_str("\"")?;
+    for c in string.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?
+        }
+    }
+    f.write_str("\"")
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Null => f.write_str("null"),
+            Value::Bool(value) => write!(f, "{}", value),
+            // JSON has no way to write these, null at least keeps the document readable
+            Value::Number(number) if !number.is_finite() => f.write_str("null"),
+            Value::Number(number) => write!(f, "{}", number),
+            Value::String(string) => write_string(f, string),
+            Value::Array(values) => {
+                f.write_str("[")?;
+                for (index, value) in values.iter().enumerate() {
+                    if index != 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                f.write_str("]")
+            },
+            Value::Object(fields) => {
+                f.write_str("{")?;
+                for (index, (name, value)) in fields.iter().enumerate() {
+                    if index != 0 {
+                        f.write_str(",")?;
+                    }
+                    write_string(f, name)?;
+                    write!(f, ":{}", value)?;
+                }
+                f.write_str("}")
+            }
+        }
+    }
+}
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error<T>(&self, message: &'static str) -> Result<T, ParseError> {
+        Err(ParseError { offset: self.offset, message })
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ') | Some(b'\n') | Some(b'\r') | Some(b'\t') = self.bytes.get(self.offset) {
+            self.offset += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_whitespace();
+        self.bytes.get(self.offset).copied()
+    }
+
+    fn expect(&mut self, byte: u8, message: &'static str) -> Result<(), ParseError> {
+        if self.peek() == Some(byte) {
+            self.offset += 1;
+            Ok(())
+        } else {
+            self.error(message)
+        }
+    }
+
+    fn literal(&mut self, literal: &str, value: Value) -> Result<Value, ParseError> {
+        if self.bytes[self.offset..].starts_with(literal.as_bytes()) {
+            self.offset += literal.len();
+            Ok(value)
+        } else {
+            self.error("unknown literal")
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, ParseError> {
+        match self.peek() {
+            Some(b'n') => self.literal("null", Value::Null),
+            Some(b't') => self.literal("true", Value::Bool(true)),
+            Some(b'f') => self.literal("false", Value::Bool(false)),
+            Some(b'"') => self.string().map(Value::String),
+            Some(b'[') => self.array(),
+            Some(b'{') => self.object(),
+            Some(b'-') | Some(b'0'..=b'9') => self.number(),
+            Some(_) => self.error("unexpected character"),
+            None => self.error("unexpected end of input")
+        }
+    }
+
+    fn number(&mut self) -> Result<Value, ParseError> {
+        let start = self.offset;
+        while let Some(b'-') | Some(b'+') | Some(b'.') | Some(b'e') | Some(b'E') | Some(b'0'..=b'9') = self.bytes.get(self.offset) {
+            self.offset += 1;
+        }
+        std::str::from_utf8(&self.bytes[start..self.offset])
+            .ok()
+            .and_then(|number| number.parse().ok())
+            .map(Value::Number)
+            .map_or_else(|| self.error("invalid number"), Ok)
+    }
+
+    fn string(&mut self) -> Result<String, ParseError> {
+        self.expect(b'"', "expected a string")?;
+        let mut string = Vec::new();
+        loop {
+            let byte = match self.bytes.get(self.offset) {
+                Some(byte) => *byte,
+                None => return self.error("unterminated string")
+            };
+            self.offset += 1;
+            match byte {
+                b'"' => break,
+                b'\\' => {
+                    let escaped = match self.bytes.get(self.offset) {
+                        Some(byte) => *byte,
+                        None => return self.error("unterminated string")
+                    };
+                    self.offset += 1;
+                    let c = match escaped {
+                        b'"' => '"',
+                        b'\\' => '\\',
+                        b'/' => '/',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'u' => {
+                            let code = self.bytes.get(self.offset..self.offset + 4)
+                                .and_then(|hex| std::str::from_utf8(hex).ok())
+                                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                                .and_then(std::char::from_u32);
+                            match code {
+                                Some(c) => {
+                                    self.offset += 4;
+                                    c
+                                },
+                                None => return self.error("invalid unicode escape")
+                            }
+                        },
+                        _ => return self.error("invalid escape")
+                    };
+                    let mut buffer = [0; 4];
+                    string.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+                },
+                byte => string.push(byte)
+            }
+        }
+        String::from_utf8(string).map_or_else(|_| self.error("invalid utf-8"), Ok)
+    }
+
+    fn array(&mut self) -> Result<Value, ParseError> {
+        self.expect(b'[', "expected an array")?;
+        let mut values = Vec::new();
+        if self.peek() == Some(b']') {
+            self.offset += 1;
+            return Ok(Value::Array(values));
+        }
+        loop {
+            values.push(self.value()?);
+            match self.peek() {
+                Some(b',') => self.offset += 1,
+                Some(b']') => {
+                    self.offset += 1;
+                    return Ok(Value::Array(values));
+                },
+                _ => return self.error("expected ',' or ']'")
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Value, ParseError> {
+        self.expect(b'{', "expected an object")?;
+        let mut fields = Vec::new();
+        if self.peek() == Some(b'}') {
+            self.offset += 1;
+            return Ok(Value::Object(fields));
+        }
+        loop {
+            self.skip_whitespace();
+            let name = self.string()?;
+            self.expect(b':', "expected ':'")?;
+            fields.push((name, self.value()?));
+            match self.peek() {
+                Some(b',') => self.offset += 1,
+                Some(b'}') => {
+                    self.offset += 1;
+                    return Ok(Value::Object(fields));
+                },
+                _ => return self.error("expected ',' or '}'")
+            }
+        }
+    }
+}
+
+/// Parses a whole JSON document
+pub fn parse(text: &str) -> Result<Value, ParseError> {
+    let mut parser = Parser { bytes: text.as_bytes(), offset: 0 };
+    let value = parser.value()?;
+    if parser.peek().is_some() {
+        return parser.error("trailing characters");
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let value = Value::object(vec![
+            ("name", Value::from("a \"quoted\"\nline")),
+            ("speed", Value::Array(vec![Value::from(0.1f32), Value::from(-2.5f32)])),
+            ("flag", Value::from(true)),
+            ("nothing", Value::Null),
+            ("nested", Value::object(vec![("id", Value::from(-1))])),
+        ]);
+
+        let parsed = parse(&value.to_string()).unwrap();
+        assert_eq!(parsed, value);
+        assert_eq!(parsed.get("speed").unwrap().as_array().unwrap()[0].as_f32(), Some(0.1));
+        assert_eq!(parsed.get("nested").and_then(|nested| nested.get("id")).and_then(Value::as_i64), Some(-1));
+    }
+
+    #[test]
+    fn whitespace_and_escapes() {
+        let parsed = parse(" { \"a\" : [ 1 , 2e1 ] , \"b\" : \"\\u0041\\/\" } ").unwrap();
+        assert_eq!(parsed.get("a"), Some(&Value::Array(vec![Value::Number(1.0), Value::Number(20.0)])));
+        assert_eq!(parsed.get("b").and_then(Value::as_str), Some("A/"));
+    }
+
+    #[test]
+    fn errors() {
+        assert!(parse("").is_err());
+        assert!(parse("[1, 2").is_err());
+        assert!(parse("{\"a\" 1}").is_err());
+        assert!(parse("\"unterminated").is_err());
+        assert!(parse("1 2").is_err());
+        assert_eq!(parse("[1,]").unwrap_err().offset, 3);
+    }
+}
@@ -0,0 +1,127 @@
+/// Values used by the stop energy to push a fighter that was hit on the ground out of the attacker (DamageKnockBack).
+///
+/// The push follows a cubic bezier curve over the (scaled) hitstop frames, where the curve's value is the fraction of the
+/// overlap that has been pushed out so far. Values below 0 are replaced with the game's params, which is the default
+#[derive(Debug, Copy, Clone)]
+#[repr(C)]
+pub struct KnockBackPushOutParams {
+    /// The first point of the curve, the game uses `damage_knock_back_speed_x_rate * 0.01`
+    pub start: f32,
+    pub control_1: f32,
+    pub control_2: f32,
+    pub end: f32,
+    /// The percentage of the hitstop frames that the push takes, the game uses `damage_knock_back_hitstop_frame_rate`
+    pub hitstop_frame_rate: f32,
+    /// The joints of the attacker that are checked on top of their jostle area, so that extended limbs are respected
+    pub joints: [u64; 2],
+}
+
+impl KnockBackPushOutParams {
+    pub const DEFAULT: Self = Self {
+        start: -1.0,
+        control_1: 0.6,
+        control_2: 0.79,
+        end: 1.0,
+        hitstop_frame_rate: -1.0,
+        joints: [0x14d5b6ea53, 0x142fb9d730],
+    };
+
+    /// Gets the curve to push the fighter out with
+    /// # Arguments
+    /// * `speed_x_rate` - The game's `damage_knock_back_speed_x_rate` param
+    pub fn curve(&self, speed_x_rate: f32) -> KnockBackCurve {
+        KnockBackCurve {
+            points: [
+                if self.start < 0.0 { speed_x_rate * 0.01 } else { self.start },
+                self.control_1,
+                self.control_2,
+                self.end
+            ]
+        }
+    }
+
+    /// Gets how many frames the push takes
+    /// # Arguments
+    /// * `hitstop_frame_rate` - The game's `damage_knock_back_hitstop_frame_rate` param
+    /// * `hitstop_frames` - The hitstop frames of the hit from the damage log
+    pub fn frames(&self, hitstop_frame_rate: f32, hitstop_frames: i32) -> f32 {
+        let rate = if self.hitstop_frame_rate < 0.0 {
+            hitstop_frame_rate
+        } else {
+            self.hitstop_frame_rate
+        };
+
+        (rate * 0.01 * hitstop_frames as f32).max(1.0)
+    }
+}
+
+/// A 1D cubic bezier curve
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct KnockBackCurve {
+    pub points: [f32; 4],
+}
+
+impl KnockBackCurve {
+    /// Evaluates the curve
+    /// # Arguments
+    /// * `progress` - How far along the curve to evaluate, from 0 to 1
+    pub fn evaluate(&self, progress: f32) -> f32 {
+        let [a, b, c, d] = self.points;
+        let remaining = 1.0 - progress;
+        // A(1-x)^3 + 3Bx(1-x)^2 + 3C(1-x)x^2 + Dx^3
+        a * remaining.powi(3)
+            + 3.0 * b * progress * remaining.powi(2)
+            + 3.0 * c * progress.powi(2) * remaining
+            + d * progress.powi(3)
+    }
+
+    /// Samples the curve at every frame of a push, this is what the energy's speed is derived from
+    /// # Arguments
+    /// * `frames` - The number of frames the push takes
+    /// # Returns
+    /// The progress and value of the curve for each frame
+    pub fn samples(&self, frames: f32) -> Vec<(f32, f32)> {
+        let mut samples = Vec::new();
+        let mut elapsed = 0.0;
+        while elapsed < frames {
+            let progress = elapsed / frames;
+            samples.push((progress, self.evaluate(progress)));
+            elapsed += 1.0;
+        }
+        samples
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn curve_matches_game() {
+        let curve = KnockBackPushOutParams::DEFAULT.curve(20.0);
+        assert_eq!(curve.points, [0.2, 0.6, 0.79, 1.0]);
+        assert!((curve.evaluate(0.0) - 0.2).abs() < 1.0e-6);
+        assert!((curve.evaluate(1.0) - 1.0).abs() < 1.0e-6);
+    }
+
+    #[test]
+    fn configured_values_override_game() {
+        let params = KnockBackPushOutParams {
+            start: 0.0,
+            hitstop_frame_rate: 50.0,
+            ..KnockBackPushOutParams::DEFAULT
+        };
+        assert_eq!(params.curve(20.0).points[0], 0.0);
+        assert_eq!(params.frames(100.0, 10), 5.0);
+        assert_eq!(params.frames(100.0, 0), 1.0);
+    }
+
+    #[test]
+    fn samples_cover_every_frame() {
+        let curve = KnockBackPushOutParams::DEFAULT.curve(20.0);
+        let samples = curve.samples(4.0);
+        assert_eq!(samples.len(), 4);
+        assert_eq!(samples[0].0, 0.0);
+        assert!(samples.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+    }
+}
@@ -0,0 +1,127 @@
+use super::{
+    energy::PaddedVec2,
+    fighter::FighterAccessor
+};
+
+/// What grounded root motion does when it would carry the fighter off a ledge
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u32)]
+pub enum LedgeBehavior {
+    /// The animation keeps moving the fighter, which is what the game does
+    Allow,
+    /// The fighter stops at the ledge
+    Stop,
+    /// The fighter slows down as they get close to the ledge, but can still walk off it
+    Slow,
+}
+
+/// Values used by the motion energy to keep grounded animations from moving fighters off ledges.
+///
+/// Status scripts choose the behavior per move by setting one of the flags, so that for example a dash attack can stop
+/// at the ledge while another one slides off it
+#[derive(Debug, Copy, Clone)]
+#[repr(C)]
+pub struct LedgeParams {
+    /// The work flag that makes the fighter stop at ledges, nothing stops when this is below 0
+    pub stop_flag: i32,
+    /// The work flag that makes the fighter slow down near ledges, nothing slows down when this is below 0
+    pub slow_flag: i32,
+    /// How far from the ledge the fighter stops, on top of the distance they would move this frame
+    pub stop_distance: f32,
+    /// How far from the ledge the fighter starts slowing down
+    pub slow_distance: f32,
+    /// The multiplier on the root motion while slowing down
+    pub slow_mul: f32,
+}
+
+impl LedgeParams {
+    pub const DEFAULT: Self = Self {
+        stop_flag: -1,
+        slow_flag: -1,
+        stop_distance: 0.0,
+        slow_distance: 10.0,
+        slow_mul: 0.5,
+    };
+
+    /// Gets the behavior that the fighter's status asked for, stopping wins over slowing down
+    pub fn behavior<A: FighterAccessor + ?Sized>(&self, boma: &mut A) -> LedgeBehavior {
+        if self.stop_flag >= 0 && boma.is_flag(self.stop_flag) {
+            LedgeBehavior::Stop
+        } else if self.slow_flag >= 0 && boma.is_flag(self.slow_flag) {
+            LedgeBehavior::Slow
+        } else {
+            LedgeBehavior::Allow
+        }
+    }
+
+    /// Adjusts the root motion for the ledge in front of the fighter
+    /// # Arguments
+    /// * `behavior` - What to do near the ledge
+    /// * `speed` - The speed taken from the animation
+    /// * `lr` - The direction the fighter is facing
+    /// * `is_near_ledge` - Checks if there is a ledge within the given distance in front of the fighter
+    pub fn adjust(&self, behavior: LedgeBehavior, speed: PaddedVec2, lr: f32, mut is_near_ledge: impl FnMut(f32) -> bool) -> PaddedVec2 {
+        // the ledge check only looks in front of the fighter, so moving backwards is left alone
+        if behavior == LedgeBehavior::Allow || speed.x * lr <= 0.0 {
+            return speed;
+        }
+
+        match behavior {
+            LedgeBehavior::Stop if is_near_ledge(self.stop_distance + speed.x.abs()) => PaddedVec2::zeros(),
+            LedgeBehavior::Slow if is_near_ledge(self.slow_distance) => speed * self.slow_mul,
+            _ => speed
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fighter::stub::*;
+
+    const STOP_FLAG: i32 = 0x200000e1;
+    const SLOW_FLAG: i32 = 0x200000e2;
+
+    fn params() -> LedgeParams {
+        LedgeParams {
+            stop_flag: STOP_FLAG,
+            slow_flag: SLOW_FLAG,
+            ..LedgeParams::DEFAULT
+        }
+    }
+
+    #[test]
+    fn behavior_from_flags() {
+        let mut fighter = StubFighter::new();
+        assert_eq!(LedgeParams::DEFAULT.behavior(&mut fighter), LedgeBehavior::Allow);
+        assert_eq!(params().behavior(&mut fighter), LedgeBehavior::Allow);
+
+        fighter.flags.insert(SLOW_FLAG, true);
+        assert_eq!(params().behavior(&mut fighter), LedgeBehavior::Slow);
+        fighter.flags.insert(STOP_FLAG, true);
+        assert_eq!(params().behavior(&mut fighter), LedgeBehavior::Stop);
+        assert_eq!(LedgeParams::DEFAULT.behavior(&mut fighter), LedgeBehavior::Allow);
+    }
+
+    #[test]
+    fn stop_uses_the_frames_movement() {
+        let speed = PaddedVec2::new(2.0, 0.0);
+        let ledge = 1.5;
+        let near = |distance: f32| ledge <= distance;
+
+        assert_eq!(params().adjust(LedgeBehavior::Stop, speed, 1.0, near), PaddedVec2::zeros());
+        assert_eq!(params().adjust(LedgeBehavior::Allow, speed, 1.0, near), speed);
+        assert_eq!(params().adjust(LedgeBehavior::Stop, PaddedVec2::new(1.0, 0.0), 1.0, near), PaddedVec2::new(1.0, 0.0));
+
+        // moving away from the ledge
+        assert_eq!(params().adjust(LedgeBehavior::Stop, -speed, 1.0, near), -speed);
+        assert_eq!(params().adjust(LedgeBehavior::Stop, -speed, -1.0, near), PaddedVec2::zeros());
+    }
+
+    #[test]
+    fn slow_near_ledge() {
+        let speed = PaddedVec2::new(2.0, 0.0);
+        assert_eq!(params().adjust(LedgeBehavior::Slow, speed, 1.0, |distance| distance >= 8.0), PaddedVec2::new(1.0, 0.0));
+        assert_eq!(params().adjust(LedgeBehavior::Slow, speed, 1.0, |distance| distance >= 12.0), speed);
+    }
+}
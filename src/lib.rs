@@ -2,10 +2,41 @@
 #![feature(repr_simd)]
 #![feature(simd_ffi)]
 #![feature(asm)]
+#[macro_use]
+mod log;
 mod control;
 mod stop;
 mod energy;
 mod motion;
+mod config;
+mod fighter;
+mod fighter_state;
+mod swim;
+mod glide;
+mod tether;
+mod grab;
+mod knockback;
+mod battle_object;
+mod modules;
+mod offsets;
+mod signature;
+mod handlers;
+mod blend;
+mod handoff;
+mod root_motion;
+mod slope;
+mod traction;
+mod ledge;
+mod json;
+mod snapshot;
+mod hash_log;
+mod determinism;
+#[cfg(test)]
+mod golden;
+#[cfg(test)]
+mod properties;
+#[cfg(test)]
+mod fuzz;
 
 use smash::{
     app::{
@@ -54,8 +85,12 @@ pub fn uninstall() {
 
 #[skyline::main(name = "energy_id")]
 pub fn main() {
+    offsets::resolve_all();
+    handlers::install();
+
     #[cfg(not(feature = "dev-plugin"))]
     {
+        fighter_state::install();
         control::install();
         stop::install();
         motion::install();
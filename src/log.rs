@@ -0,0 +1,267 @@
+//! Diagnostic output for the energies.
+//!
+//! Every message has a level and a context (the module it came from, and optionally the reset type and fighter it's
+//! about), which are filtered by `EnergyConfig::log` so that the output can be narrowed down at runtime. Each call site
+//! is also rate limited, since most of the messages come from functions that run every frame for every fighter. Errors
+//! are never rate limited, they are rare and some call sites (like resolving the offsets at startup) write one for each
+//! thing that failed in a burst
+
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicU32, AtomicU64, Ordering},
+        Once
+    },
+    time::Instant
+};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Level {
+    Off,
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u32)]
+pub enum Module {
+    Control,
+    Motion,
+    Stop,
+    Offsets,
+}
+
+impl Module {
+    pub const ALL: u32 = u32::MAX;
+
+    pub const fn bit(self) -> u32 {
+        1 << self as u32
+    }
+}
+
+/// What a message is about
+#[derive(Debug, Copy, Clone)]
+pub struct Context {
+    pub module: Module,
+    pub reset_type: Option<u32>,
+    pub entry_id: Option<i32>,
+}
+
+impl Context {
+    pub const fn new(module: Module) -> Self {
+        Self {
+            module,
+            reset_type: None,
+            entry_id: None
+        }
+    }
+
+    pub const fn with_reset_type(mut self, reset_type: u32) -> Self {
+        self.reset_type = Some(reset_type);
+        self
+    }
+
+    pub const fn with_entry_id(mut self, entry_id: i32) -> Self {
+        self.entry_id = Some(entry_id);
+        self
+    }
+}
+
+/// Which messages are written
+#[derive(Debug, Copy, Clone)]
+#[repr(C)]
+pub struct LogConfig {
+    /// The most verbose level that is written
+    pub level: Level,
+    /// A mask of `Module::bit`s to write messages for
+    pub modules: u32,
+    /// Only write messages about this reset type, or every reset type when below 0
+    pub reset_type: i32,
+    /// Only write messages about the fighter with this entry id, or every fighter when below 0
+    pub entry_id: i32,
+    /// How many messages each call site can write per second, 0 to never limit them. Errors are never limited
+    pub max_per_second: u32,
+}
+
+impl LogConfig {
+    pub const DEFAULT: Self = Self {
+        level: Level::Warn,
+        modules: Module::ALL,
+        reset_type: -1,
+        entry_id: -1,
+        max_per_second: 10,
+    };
+
+    /// Checks if a message passes the filters
+    pub fn allows(&self, level: Level, context: &Context) -> bool {
+        if level == Level::Off || level > self.level || self.modules & context.module.bit() == 0 {
+            return false;
+        }
+
+        // messages that aren't about a specific reset type or fighter are never filtered out by them
+        let reset_type_matches = self.reset_type < 0 || context.reset_type.map_or(true, |reset_type| reset_type as i32 == self.reset_type);
+        let entry_id_matches = self.entry_id < 0 || context.entry_id.map_or(true, |entry_id| entry_id == self.entry_id);
+        reset_type_matches && entry_id_matches
+    }
+
+    /// Gets how many messages of a level each call site can write per second, 0 means there is no limit
+    pub fn rate_limit(&self, level: Level) -> u32 {
+        if level == Level::Error {
+            0
+        } else {
+            self.max_per_second
+        }
+    }
+}
+
+/// Limits how often a single call site writes
+pub struct RateLimiter {
+    window_start: AtomicU64,
+    count: AtomicU32,
+}
+
+impl RateLimiter {
+    pub const fn new() -> Self {
+        Self {
+            window_start: AtomicU64::new(0),
+            count: AtomicU32::new(0)
+        }
+    }
+
+    /// Checks if another message can be written
+    /// # Arguments
+    /// * `now` - The current time, in milliseconds
+    /// * `max_per_second` - How many messages can be written every second, 0 means there is no limit
+    pub fn allow_at(&self, now: u64, max_per_second: u32) -> bool {
+        if max_per_second == 0 {
+            return true;
+        }
+
+        let window_start = self.window_start.load(Ordering::Relaxed);
+        if now < window_start || now - window_start >= 1000 {
+            self.window_start.store(now, Ordering::Relaxed);
+            self.count.store(1, Ordering::Relaxed);
+            return true;
+        }
+
+        self.count.fetch_add(1, Ordering::Relaxed) < max_per_second
+    }
+
+    pub fn allow(&self, max_per_second: u32) -> bool {
+        self.allow_at(now(), max_per_second)
+    }
+}
+
+static mut EPOCH: Option<Instant> = None;
+static EPOCH_INIT: Once = Once::new();
+
+/// Milliseconds since the first message was checked
+fn now() -> u64 {
+    unsafe {
+        EPOCH_INIT.call_once(|| EPOCH = Some(Instant::now()));
+        EPOCH.map_or(0, |epoch| epoch.elapsed().as_millis() as u64)
+    }
+}
+
+/// Checks the level on its own, so that building the context can be skipped for most messages
+pub fn level_enabled(level: Level) -> bool {
+    level != Level::Off && level <= crate::config::get().log.level
+}
+
+pub fn enabled(level: Level, context: &Context) -> bool {
+    crate::config::get().log.allows(level, context)
+}
+
+pub fn max_per_second(level: Level) -> u32 {
+    crate::config::get().log.rate_limit(level)
+}
+
+pub fn write(level: Level, context: &Context, args: fmt::Arguments) {
+    let mut prefix = format!("[energy_id] [{:?}] [{:?}", level, context.module);
+    if let Some(reset_type) = context.reset_type {
+        prefix += &format!(" reset_type={}", reset_type);
+    }
+    if let Some(entry_id) = context.entry_id {
+        prefix += &format!(" entry={}", entry_id);
+    }
+    println!("{}] {}", prefix, args);
+}
+
+/// Writes a message if it passes the filters in the config and the call site isn't being rate limited
+/// ```ignore
+/// energy_log!(Level::Debug, Context::new(Module::Control).with_reset_type(3), "accel: {}", accel);
+/// ```
+macro_rules! energy_log {
+    ($level:expr, $context:expr, $($arg:tt)+) => {{
+        static LIMITER: $crate::log::RateLimiter = $crate::log::RateLimiter::new();
+        if $crate::log::level_enabled($level) {
+            let context = $context;
+            if $crate::log::enabled($level, &context) && LIMITER.allow($crate::log::max_per_second($level)) {
+                $crate::log::write($level, &context, format_args!($($arg)+));
+            }
+        }
+    }};
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn level_and_module_filters() {
+        let config = LogConfig {
+            level: Level::Info,
+            modules: Module::Control.bit() | Module::Stop.bit(),
+            ..LogConfig::DEFAULT
+        };
+
+        assert!(config.allows(Level::Error, &Context::new(Module::Control)));
+        assert!(config.allows(Level::Info, &Context::new(Module::Stop)));
+        assert!(!config.allows(Level::Debug, &Context::new(Module::Control)));
+        assert!(!config.allows(Level::Error, &Context::new(Module::Motion)));
+        assert!(!config.allows(Level::Off, &Context::new(Module::Control)));
+    }
+
+    #[test]
+    fn reset_type_and_fighter_filters() {
+        let config = LogConfig {
+            level: Level::Trace,
+            reset_type: 3,
+            entry_id: 1,
+            ..LogConfig::DEFAULT
+        };
+
+        let context = Context::new(Module::Control);
+        assert!(config.allows(Level::Trace, &context.with_reset_type(3).with_entry_id(1)));
+        assert!(!config.allows(Level::Trace, &context.with_reset_type(2).with_entry_id(1)));
+        assert!(!config.allows(Level::Trace, &context.with_reset_type(3).with_entry_id(0)));
+        assert!(config.allows(Level::Trace, &context));
+    }
+
+    #[test]
+    fn rate_limit_resets_every_second() {
+        let limiter = RateLimiter::new();
+        assert!(limiter.allow_at(0, 2));
+        assert!(limiter.allow_at(10, 2));
+        assert!(!limiter.allow_at(20, 2));
+        assert!(!limiter.allow_at(999, 2));
+        assert!(limiter.allow_at(1000, 2));
+
+        let limiter = RateLimiter::new();
+        assert!((0..100).all(|now| limiter.allow_at(now, 0)));
+    }
+
+    #[test]
+    fn errors_are_not_rate_limited() {
+        let config = LogConfig::DEFAULT;
+        assert_eq!(config.rate_limit(Level::Error), 0);
+        assert_eq!(config.rate_limit(Level::Warn), config.max_per_second);
+
+        let limiter = RateLimiter::new();
+        assert!((0..100).all(|_| limiter.allow_at(0, config.rate_limit(Level::Error))));
+    }
+}
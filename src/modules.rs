@@ -0,0 +1,150 @@
+use smash::{
+    app::BattleObjectModuleAccessor,
+    phx::{Hash40, Vector4f}
+};
+
+use super::energy;
+
+/// Offsets of the modules inside of the BattleObjectModuleAccessor that the energies need to reach directly
+mod offsets {
+    pub const STATUS_MODULE: usize = 0x8;
+    pub const MODEL_MODULE: usize = 0x78;
+    pub const MOTION_MODULE: usize = 0x88;
+    pub const AREA_MODULE: usize = 0xC0;
+}
+
+/// Offsets into the vtables of the modules, for the functions that lua_bind doesn't expose
+mod vtable {
+    pub const MOTION_IS_MAIN_UPDATING_ENERGY: usize = 0x1e8;
+    pub const MOTION_IS_2ND_UPDATING_ENERGY: usize = 0x1f0;
+    pub const MOTION_UPDATE_TRANS_MOVE_SPEED_2ND: usize = 0x220;
+    pub const MOTION_CLIFF_TRANS_MOVE: usize = 0x230;
+    pub const AREA_GET_AREA: usize = 0x118;
+    pub const MODEL_HAS_JOINT: usize = 0x320;
+}
+
+/// A module of the BattleObjectModuleAccessor that is accessed through its vtable
+#[derive(Copy, Clone)]
+pub struct RawModule(u64);
+
+impl RawModule {
+    unsafe fn vtable_entry(self, offset: usize) -> *const () {
+        *(*(self.0 as *const *const u64)).add(offset / 0x8) as *const ()
+    }
+
+    unsafe fn field<T: Copy>(self, offset: usize) -> T {
+        *((self.0 as *const u8).add(offset) as *const T)
+    }
+}
+
+/// Named access to the parts of the module accessor that are reached through raw offsets and vtable calls
+pub trait ModuleAccessorExt {
+    fn raw_module(&self, offset: usize) -> RawModule;
+
+    fn status_module(&self) -> RawModule {
+        self.raw_module(offsets::STATUS_MODULE)
+    }
+
+    fn model_module(&self) -> RawModule {
+        self.raw_module(offsets::MODEL_MODULE)
+    }
+
+    fn motion_module(&self) -> RawModule {
+        self.raw_module(offsets::MOTION_MODULE)
+    }
+
+    fn area_module(&self) -> RawModule {
+        self.raw_module(offsets::AREA_MODULE)
+    }
+
+    /// Checks if the main motion is updating the kinetic energy
+    fn is_main_motion_updating_energy(&self) -> bool {
+        unsafe {
+            let module = self.motion_module();
+            let function: extern "C" fn(u64) -> bool = std::mem::transmute(module.vtable_entry(vtable::MOTION_IS_MAIN_UPDATING_ENERGY));
+            function(module.0)
+        }
+    }
+
+    /// Checks if the 2nd motion is updating the kinetic energy
+    fn is_motion_2nd_updating_energy(&self) -> bool {
+        unsafe {
+            let module = self.motion_module();
+            let function: extern "C" fn(u64) -> bool = std::mem::transmute(module.vtable_entry(vtable::MOTION_IS_2ND_UPDATING_ENERGY));
+            function(module.0)
+        }
+    }
+
+    /// Updates the trans move speed of the 2nd motion
+    fn update_trans_move_speed_2nd(&self) {
+        unsafe {
+            let module = self.motion_module();
+            let function: extern "C" fn(u64) = std::mem::transmute(module.vtable_entry(vtable::MOTION_UPDATE_TRANS_MOVE_SPEED_2ND));
+            function(module.0)
+        }
+    }
+
+    /// Gets the movement of the current cliff motion, this still has to be passed through the game's cliff handling
+    fn cliff_trans_move(&self) -> Vector4f {
+        unsafe {
+            let module = self.motion_module();
+            let function: extern "C" fn(u64) -> energy::Vec4 = std::mem::transmute(module.vtable_entry(vtable::MOTION_CLIFF_TRANS_MOVE));
+            let vec = function(module.0);
+            Vector4f {
+                x: vec.x,
+                y: vec.y,
+                z: vec.z,
+                w: vec.w
+            }
+        }
+    }
+
+    /// The float at MotionModule + 0x98, read by TurnRun to check if the turn is being held against.
+    ///
+    /// This hasn't been checked against the disassembly. It keeps what the original code read, which was
+    /// `*(module as *const f32).add(0x130 / 0x8)` on the module at 0x88. That module was named `ground_module`, but 0x88
+    /// is the MotionModule (see `offsets::MOTION_MODULE`). `add` on an `*const f32` counts floats, so the read was at
+    /// byte 0x98. The other offsets there are byte offsets divided by 8 for `*const u64`, so 0x130 may have been meant as
+    /// a byte offset, which would make this MotionModule + 0x130 instead
+    fn motion_unk_float(&self) -> f32 {
+        unsafe {
+            self.motion_module().field(0x98)
+        }
+    }
+
+    /// The flag at StatusModule + 0x12a, when this is set the common speed limits are not applied after processing
+    fn is_ignore_common_speed_limit(&self) -> bool {
+        unsafe {
+            self.status_module().field(0x12a)
+        }
+    }
+
+    /// Gets the left and right edges of an area instance
+    /// # Arguments
+    /// * `area_kind` - The kind of area, which should have been checked with `AreaModule::is_exist_area_instance`
+    fn area_x_range(&self, area_kind: i32) -> (f32, f32) {
+        unsafe {
+            let module = self.area_module();
+            let function: extern "C" fn(u64, i32) -> u64 = std::mem::transmute(module.vtable_entry(vtable::AREA_GET_AREA));
+            let area = RawModule(function(module.0, area_kind));
+            (area.field(0x40), area.field(0x50))
+        }
+    }
+
+    /// Checks if the model has a joint, `ModelModule::joint_global_position` can't be used on joints that don't exist
+    fn has_joint(&self, joint: Hash40) -> bool {
+        unsafe {
+            let module = self.model_module();
+            let function: extern "C" fn(u64, Hash40) -> bool = std::mem::transmute(module.vtable_entry(vtable::MODEL_HAS_JOINT));
+            function(module.0, joint)
+        }
+    }
+}
+
+impl ModuleAccessorExt for BattleObjectModuleAccessor {
+    fn raw_module(&self, offset: usize) -> RawModule {
+        unsafe {
+            RawModule(*((self as *const Self as *const u8).add(offset) as *const u64))
+        }
+    }
+}
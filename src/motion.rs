@@ -2,7 +2,14 @@ use std::ops::{Deref, DerefMut};
 
 use super::{
     *,
-    energy::*
+    energy::*,
+    fighter_state,
+    handlers,
+    handoff::{self, HandOffPolicy},
+    ledge::LedgeBehavior,
+    log::{Context, Level, Module},
+    fighter::FighterAccessor,
+    root_motion
 };
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -66,6 +73,88 @@ pub struct FighterKineticEnergyMotion {
     // ...
 }
 
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+    use crate::fighter::stub::*;
+
+    pub(crate) fn motion_energy(reset_type: EnergyMotionResetType) -> FighterKineticEnergyMotion {
+        let mut parent = stub_energy();
+        parent.energy_reset_type = reset_type as u32;
+        FighterKineticEnergyMotion {
+            parent,
+            lr: 1.0,
+            angle: 0.0,
+            angle_whole: 0.0,
+            angle_intp_end: 0.0,
+            angle_intp_frames_remaining: 0,
+            speed_mul: 1.0,
+            prev_speed: PaddedVec2::zeros(),
+            speed_mul_2nd: PaddedVec2::new(1.0, 1.0),
+            update_flag: false
+        }
+    }
+
+    #[test]
+    fn angle_interpolates_over_frames() {
+        let mut energy = motion_energy(EnergyMotionResetType::AirTrans);
+        energy.angle_intp_end = 1.0;
+        energy.angle_intp_frames_remaining = 4;
+        let mut fighter = StubFighter::new();
+        fighter.trans_move_speed = Some(Vector3f { x: 0.0, y: 0.0, z: 1.0 });
+        for _ in 0..4 {
+            unsafe {
+                update_motion(&mut energy, &mut fighter);
+            }
+        }
+        assert!((energy.angle_whole - 1.0).abs() < 1.0e-6);
+        assert_eq!(energy.angle_intp_frames_remaining, 0);
+        assert!((energy.speed.x - 1.0f32.cos()).abs() < 1.0e-5);
+        assert!((energy.speed.y - 1.0f32.sin()).abs() < 1.0e-5);
+    }
+
+    #[test]
+    fn decay_ignores_traction() {
+        let mut config = crate::config::EnergyConfig::DEFAULT;
+        config.hand_off.push(handoff::HandOffRule {
+            reset_type: EnergyMotionResetType::GroundTrans as i32,
+            status_kind: -1,
+            policy: HandOffPolicy::Decay(4)
+        });
+        config.traction.set(crate::traction::MaterialTraction { material: 1, brake_mul: 0.25, accel_mul: 1.0 });
+        crate::config::with(config, || {
+            let mut energy = motion_energy(EnergyMotionResetType::GroundTrans);
+            energy.speed = PaddedVec2::new(2.0, 0.0);
+            energy.speed_brake = PaddedVec2::new(0.1, 0.0);
+            energy.update_flag = true;
+            let mut fighter = StubFighter::new().with_param("common", "ground_speed_limit", 10.0);
+            fighter.ground_material = Some(1);
+
+            for _ in 0..3 {
+                unsafe {
+                    update_motion(&mut energy, &mut fighter);
+                }
+            }
+            assert!((energy.speed.x - 0.5).abs() < 1.0e-5);
+            unsafe {
+                update_motion(&mut energy, &mut fighter);
+            }
+            assert_eq!(energy.speed.x, 0.0);
+
+            // the decay has its own state, the energy's is left alone
+            assert_eq!(energy.speed_brake.x, 0.1);
+            assert!(energy.update_flag);
+
+            // the animation driving the energy again ends the decay
+            fighter.trans_move_speed = Some(Vector3f { x: 0.0, y: 0.0, z: 1.0 });
+            unsafe {
+                update_motion(&mut energy, &mut fighter);
+            }
+            assert!(fighter_state::get(&fighter).decay.is_none());
+        });
+    }
+}
+
 impl Deref for FighterKineticEnergyMotion {
     type Target = super::energy::KineticEnergy;
 
@@ -81,36 +170,6 @@ impl DerefMut for FighterKineticEnergyMotion {
 }
 
 impl FighterKineticEnergyMotion {
-    /// Calls a MotionModule vtable function to update the trans move speed (2nd)
-    pub fn update_trans_move_speed_2nd(boma: &mut BattleObjectModuleAccessor) {
-        unsafe {
-            let motion_module = *(boma as *const BattleObjectModuleAccessor as *const u64).add(0x88 / 0x8);
-            let motion_module_vtable = *(motion_module as *const *const u64);
-            let function: extern "C" fn(u64) = std::mem::transmute(*motion_module_vtable.add(0x220 / 0x8));
-            function(motion_module);
-        }
-    }
-
-    /// Checks if the motion (2nd) is updating the kinetic energy
-    pub fn is_motion_2nd_updating_energy(boma: &mut BattleObjectModuleAccessor) -> bool {
-        unsafe {
-            let motion_module = *(boma as *const BattleObjectModuleAccessor as *const u64).add(0x88 / 0x8);
-            let motion_module_vtable = *(motion_module as *const *const u64);
-            let function: extern "C" fn(u64) -> bool = std::mem::transmute(*motion_module_vtable.add(0x1f0 / 0x8));
-            function(motion_module)
-        }
-    }
-
-    /// Checks if the motion is updating the kinetic energy
-    pub fn is_main_motion_updating_energy(boma: &mut BattleObjectModuleAccessor) -> bool {
-        unsafe {
-            let motion_module = *(boma as *const BattleObjectModuleAccessor as *const u64).add(0x88 / 0x8);
-            let motion_module_vtable = *(motion_module as *const *const u64);
-            let function: extern "C" fn(u64) -> bool = std::mem::transmute(*motion_module_vtable.add(0x1e8 / 0x8));
-            function(motion_module)
-        }
-    }
-
     pub fn trans_move_speed_correct(boma: &mut BattleObjectModuleAccessor) -> Vector3f {
         unsafe {
             let func: extern "C" fn(&mut BattleObjectModuleAccessor) -> energy::Vec3 = std::mem::transmute(MotionModule::trans_move_speed as *const ());
@@ -140,29 +199,37 @@ impl FighterKineticEnergyMotion {
     /// * `accel` - The acceleration of the energy
     /// * `max_speed` - The maximum speed of the energy
     /// * `speed` - The speed that we are attempting to accelerate to
-    pub fn set_values_and_process(&mut self, accel: PaddedVec2, max_speed: PaddedVec2, speed: PaddedVec2, boma: &mut BattleObjectModuleAccessor) {
+    pub fn set_values_and_process<A: FighterAccessor + ?Sized>(&mut self, accel: PaddedVec2, max_speed: PaddedVec2, speed: PaddedVec2, boma: &mut A) {
         self.accel = accel;
         self.speed_max = max_speed;
-        self.process(boma);
+        boma.process_energy(self);
         self.active_flag = true;
         self.prev_speed = speed;
     }
 
-    /// Gets the translation based on the specified energy reset type
+    /// Gets the translation based on the specified energy reset type, or the blend of both motions' translations
+    /// when `EnergyConfig::motion_blend` applies to the fighter
     /// # Arguments
     /// * `boma` - The BattleObjectModuleAccessor
     /// * `reset_type` - The reset type of the current energy
     /// # Returns
     /// The translation as a Vec2
-    pub fn get_translation_by_reset_type(boma: &mut BattleObjectModuleAccessor, reset_type: EnergyMotionResetType) -> PaddedVec2 {
-        let translation = unsafe {
-            if reset_type.is_2nd() {
-                Self::update_trans_move_speed_2nd(boma);
-                Self::trans_move_speed_2nd_correct(boma)
-            } else {
-                MotionModule::update_trans_move_speed(boma);
-                Self::trans_move_speed_correct(boma)
-            }
+    pub fn get_translation_by_reset_type<A: FighterAccessor + ?Sized>(boma: &mut A, reset_type: EnergyMotionResetType) -> PaddedVec2 {
+        let blend = crate::config::get().motion_blend;
+        if let Some(weight) = blend.weight(boma) {
+            let main = boma.trans_move_speed();
+            let second = boma.trans_move_speed_2nd();
+            return blend.blend(
+                PaddedVec2::new(main.z, main.y),
+                PaddedVec2::new(second.z, second.y),
+                weight
+            );
+        }
+
+        let translation = if reset_type.is_2nd() {
+            boma.trans_move_speed_2nd()
+        } else {
+            boma.trans_move_speed()
         };
 
         PaddedVec2::new(translation.z, translation.y)
@@ -172,67 +239,132 @@ impl FighterKineticEnergyMotion {
     /// # Arguments
     /// * `boma` - The BattleObjectModuleAccessor
     /// * `reset_type` - The reset type of the current energy
-    pub fn is_motion_updating_energy(boma: &mut BattleObjectModuleAccessor, reset_type: EnergyMotionResetType) -> bool {
-        if reset_type.is_2nd() {
-            Self::is_motion_2nd_updating_energy(boma)
+    pub fn is_motion_updating_energy<A: FighterAccessor + ?Sized>(boma: &mut A, reset_type: EnergyMotionResetType) -> bool {
+        // when blending, either motion can keep the energy going
+        if crate::config::get().motion_blend.weight(boma).is_some() {
+            boma.is_main_motion_updating_energy() || boma.is_motion_2nd_updating_energy()
+        } else if reset_type.is_2nd() {
+            boma.is_motion_2nd_updating_energy()
         } else {
-            Self::is_main_motion_updating_energy(boma)
+            boma.is_main_motion_updating_energy()
         }
     }
 }
 
 // This function references BattleObjectWorld, which is defo for the ledge positions
-#[skyline::from_offset(0x6941c0)]
-extern "C" fn handle_cliff(boma: &mut BattleObjectModuleAccessor, vec: &Vector4f) -> energy::Vec4;
+pub(crate) unsafe fn handle_cliff(boma: &mut BattleObjectModuleAccessor, vec: &Vector4f) -> energy::Vec4 {
+    let function: extern "C" fn(&mut BattleObjectModuleAccessor, &Vector4f) -> energy::Vec4 = std::mem::transmute(crate::offsets::required(crate::offsets::Function::HandleCliff));
+    function(boma, vec)
+}
 
-#[cfg(not(feature = "dev-plugin"))]
-#[skyline::hook(offset = 0x6d5c90)]
-unsafe fn update_hook(energy: &mut FighterKineticEnergyMotion, boma: &mut BattleObjectModuleAccessor) {
+/// Runs once the animation is no longer updating the energy, handling the speed that it left behind according to
+/// `EnergyConfig::hand_off`
+fn hand_off<A: FighterAccessor + ?Sized>(energy: &mut FighterKineticEnergyMotion, reset_type: EnergyMotionResetType, boma: &mut A) {
     use EnergyMotionResetType::*;
-    let reset_type = std::mem::transmute(energy.energy_reset_type);
-
-    energy.active_flag = true;
-    if !FighterKineticEnergyMotion::is_motion_updating_energy(boma, reset_type) {
-        if reset_type == LadderMove {
-            // If we are on a ladder, we need to **immediately** stop moving if the MotionModule is no longer updating our position
-            // By setting the acceleration to negative of our speed, we are immediately stopping our movement. This should not be applied
-            // to any other state as you will experience absolutely 0 momentum in odd situations.
-            //
-            // A bug that I encountered when reimplementing was setting this for the grounded states as well, which caused things like jumpsquat
-            // and landing to immediately stop all momentum
-            energy.set_values_and_process(
-                PaddedVec2::new(-energy.speed.x, -energy.speed.y),
-                PaddedVec2::zeros(),
-                PaddedVec2::zeros(),
-                boma
-            );
-            return;
-        }
 
-        // Set our grounded speed limit if we are on the ground
-        // This is applied in situations like landing (which includes wavetech in HDR)
-        if reset_type.is_ground() {
-            energy.speed_limit = PaddedVec2::new(
-                WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("ground_speed_limit")),
-                0.0
-            );
-        }
+    let policy = crate::config::get().hand_off.policy(reset_type as u32, boma.status_kind());
+    let stop = match policy {
+        HandOffPolicy::Vanilla => reset_type == LadderMove,
+        HandOffPolicy::Stop => true,
+        _ => false
+    };
 
-        // Basically we are setting our maximum speed to 0.0, which means that we are going to start slowing down to that speed with the use of only
-        // our brake value
-        // For ground, this is `ground_brake`, for example. That's the only thing applied here.
-        // If you wanted to, say apply double traction in this situation you could double the energy.brake temporarily and restore it afterwards
-        // as done in the control kinetic energy for some situations
+    if stop {
+        // If we are on a ladder, we need to **immediately** stop moving if the MotionModule is no longer updating our position
+        // By setting the acceleration to negative of our speed, we are immediately stopping our movement. This should not be applied
+        // to any other state (by default) as you will experience absolutely 0 momentum in odd situations.
+        //
+        // A bug that I encountered when reimplementing was setting this for the grounded states as well, which caused things like jumpsquat
+        // and landing to immediately stop all momentum
         energy.set_values_and_process(
-            PaddedVec2::zeros(),
+            PaddedVec2::new(-energy.speed.x, -energy.speed.y),
             PaddedVec2::zeros(),
             PaddedVec2::zeros(),
             boma
         );
+        return;
+    }
+
+    match policy {
+        HandOffPolicy::TransferToControl => {
+            // Only the horizontal speed is handed over, since the vertical speed is the gravity energy's job in the air
+            if boma.add_control_speed(PaddedVec2::new(energy.speed.x, 0.0)) {
+                energy.speed.x = 0.0;
+            }
+        },
+        _ => {}
+    }
+
+    // Without a decay brake this is the first frame after the animation stopped driving the energy
+    let decay_brake = match policy {
+        HandOffPolicy::Decay(frames) => Some(fighter_state::get(boma).decay.unwrap_or_else(|| {
+            let brake = handoff::decay_brake(energy.speed, frames);
+            fighter_state::update(boma, |fighter| fighter.decay = Some(brake));
+            brake
+        })),
+        _ => None
+    };
+
+    // Set our grounded speed limit if we are on the ground
+    // This is applied in situations like landing (which includes wavetech in HDR)
+    if reset_type.is_ground() {
+        energy.speed_limit = PaddedVec2::new(
+            boma.get_param_float(smash::hash40("common"), smash::hash40("ground_speed_limit")),
+            0.0
+        );
+    }
+
+    // Basically we are setting our maximum speed to 0.0, which means that we are going to start slowing down to that speed with the use of only
+    // our brake value
+    // For ground, this is `ground_brake`, for example. That's the only thing applied here.
+    // If you wanted to, say apply double traction in this situation you could double the energy.brake temporarily and restore it afterwards
+    // as done in the control kinetic energy for some situations, which is how the ground's material is applied (see EnergyConfig::traction)
+    // A decay has to stop in its number of frames, so it replaces the brake and isn't scaled by the traction
+    let brake = energy.speed_brake;
+    let traction = &crate::config::get().traction;
+    if let Some(decay_brake) = decay_brake {
+        energy.speed_brake = decay_brake;
+    } else if reset_type.is_ground() && !traction.is_empty() {
+        energy.speed_brake.x *= traction.get(boma.ground_material()).brake_mul;
+    }
+
+    energy.set_values_and_process(
+        PaddedVec2::zeros(),
+        PaddedVec2::zeros(),
+        PaddedVec2::zeros(),
+        boma
+    );
+    energy.speed_brake = brake;
+}
 
+#[cfg(not(feature = "dev-plugin"))]
+unsafe extern "C" fn update_hook(energy: &mut FighterKineticEnergyMotion, boma: &mut BattleObjectModuleAccessor) {
+    update_motion(energy, boma)
+}
+
+#[cfg(not(feature = "dev-plugin"))]
+unsafe extern "C" fn initialize_hook(energy: &mut FighterKineticEnergyMotion, boma: &mut BattleObjectModuleAccessor) {
+    initialize_motion(energy, boma)
+}
+
+#[cfg(not(feature = "dev-plugin"))]
+unsafe extern "C" fn setup_hook(energy: &mut FighterKineticEnergyMotion, reset_type: EnergyMotionResetType, initial_speed: &Vector3f, unk: u64, boma: &mut BattleObjectModuleAccessor) {
+    setup_motion(energy, reset_type, initial_speed, unk, boma)
+}
+
+pub unsafe fn update_motion<A: FighterAccessor>(energy: &mut FighterKineticEnergyMotion, boma: &mut A) {
+    use EnergyMotionResetType::*;
+    let reset_type = std::mem::transmute(energy.energy_reset_type);
+
+    energy.active_flag = true;
+    if !FighterKineticEnergyMotion::is_motion_updating_energy(boma, reset_type) {
+        hand_off(energy, reset_type, boma);
         return;
     }
 
+    // The next hand off starts a new decay
+    fighter_state::update(boma, |fighter| fighter.decay = None);
+
     // begin block for calculating move speed based on animation
 
     let mut move_speed = FighterKineticEnergyMotion::get_translation_by_reset_type(boma, reset_type);
@@ -246,15 +378,18 @@ unsafe fn update_hook(energy: &mut FighterKineticEnergyMotion, boma: &mut Battle
 
     // `angle_whole` is the angle to rotate the energy by, regardless of whether or not its reset type is AirTransAngle
     if energy.angle_whole != 0.0 {
-        println!("Rotating on angle whole: {}", energy.angle_whole);
-        move_speed.x = move_speed.x * energy.angle_whole.cos() - move_speed.y * energy.angle_whole.sin();
-        move_speed.y = move_speed.y * energy.angle_whole.cos() + move_speed.x * energy.angle_whole.sin();
+        energy_log!(
+            Level::Trace,
+            Context::new(Module::Motion).with_reset_type(reset_type as u32).with_entry_id(boma.entry_id()),
+            "Rotating on angle whole: {}", energy.angle_whole
+        );
+        move_speed = move_speed.rotate(energy.angle_whole);
     }
 
     // PostureModule::lr is used when the animation is looping or when it depends on the fighter's angle, probably because other
     // reset types are allowed to change the LR of the fighter
     let lr = if matches!(reset_type, GroundTransLoop | GroundTransLoopGekikara | AirTransAngle | AirTransAngleSuperJumpPunch) {
-        PostureModule::lr(boma)
+        boma.lr()
     } else {
         energy.lr
     };
@@ -268,140 +403,146 @@ unsafe fn update_hook(energy: &mut FighterKineticEnergyMotion, boma: &mut Battle
     // the following flag is set for the same reset types mentioned in the above LR check, except for AirTransAngleSuperJumpPunch
     energy.active_flag = false;
 
-    let speed = match reset_type {
-        // It appears that when grounded and your animation is controlling your kinetic energy,
-        // there is no grounded speed limit.
-        //
-        // This is the same for all of the grounded reset types
-        GroundTransLoop => {
-            energy.active_flag = true;
-            energy.speed_limit = PaddedVec2::new(-1.0, 0.0);
-            move_speed
-        },
-        
-        GroundTransIgnoreNorm => {
-            energy.speed_limit = PaddedVec2::new(-1.0, 0.0);
-            move_speed
-        },
-
-        // I'm still not quit sure what this "active_flag" is, but it's unset for these moves and reset for other moves?
-        // Enabling it for this and the previous reset_types doesn't appear to have any different behavior off a few quick tests
-        GroundTrans => {
-            energy.speed_limit = PaddedVec2::new(-1.0, 0.0);
-            energy::KineticEnergy::adjust_speed_for_ground_normal(&move_speed, boma)
-        },
-
-        // Haven't quite figured out where this gets used yet, and the work const has a few hits so I'm just not quite sure
-        GroundTransLoopGekikara => {
-            energy.active_flag = true;
-            energy.speed_limit = PaddedVec2::new(-1.0, 0.0);
-            let some_rate = WorkModule::get_float(boma, 0x1000009);
-            let motion_rate = MotionModule::rate(boma);
-            if some_rate != 0.0 && motion_rate / some_rate != 0.0 {
-                PaddedVec2::new(
-                    move_speed.x * some_rate / motion_rate,
-                    move_speed.y * some_rate / motion_rate
-                )
-            } else {
-                PaddedVec2::zeros()
-            }
-        },
+    let handler = handlers::find(boma.fighter_kind(), boma.status_kind());
+    let handled_speed = handler.and_then(|handler| handler.motion_speed(energy, reset_type, move_speed, boma));
 
-        // When you are in the air your speed doesn't have any special calculations
-        AirTrans | AirTrans2nd => move_speed,
-
-        // This multiplies by the angle set with (afaik) app::sv_kinetic_energy::set_angle
-        // Set angle whole is used regardless of the energy reset type
-        AirTransAngle => {
-            println!("Rotating on angle: {}", energy.angle);
-            energy.active_flag = true;
-            PaddedVec2::new(
-                move_speed.x * energy.angle.cos() - move_speed.y * energy.angle.sin(),
-                move_speed.y * energy.angle.cos() + move_speed.x * energy.angle.sin()
-            )    
-        },
-
-        // Here we zero out the X speed and literally only use the Y speed. Epic!
-        AirTransY => PaddedVec2::new(0.0, move_speed.y),
-
-        // This is the most complex (aside from cliff stuff) and it appears to adjust your momentum depending on where you are holding on the stick
-        // It looks like it can change variadically throughout the animation, which is most likely unique for these kinds of moves
-        // This is only for Mario and likely Dr. Mario
-        AirTransAngleSuperJumpPunch => {
-            let stick_x = ControlModule::get_stick_x(boma);
-            let dir = WorkModule::get_float(boma, *FIGHTER_STATUS_SUPER_JUMP_PUNCH_WORK_FLOAT_CONST_DIR_STICK_X);
-            let angle = if stick_x.abs() <= dir {
-                -energy.angle
-            } else {
-                let interp = (stick_x.abs() - dir) / (1.0 - dir);
-                let interp = interp * WorkModule::get_float(boma, *FIGHTER_STATUS_SUPER_JUMP_PUNCH_WORK_FLOAT_CONST_DIR_MUL);
-                let new_angle = -interp.to_radians();
-                if energy.angle.abs() < new_angle.abs() {
-                    energy.angle = new_angle;
+    let speed = if let Some(speed) = handled_speed {
+        speed
+    } else {
+        match reset_type {
+            // It appears that when grounded and your animation is controlling your kinetic energy,
+            // there is no grounded speed limit.
+            //
+            // This is the same for all of the grounded reset types
+            GroundTransLoop => {
+                energy.active_flag = true;
+                energy.speed_limit = PaddedVec2::new(-1.0, 0.0);
+                move_speed
+            },
+        
+            GroundTransIgnoreNorm => {
+                energy.speed_limit = PaddedVec2::new(-1.0, 0.0);
+                move_speed
+            },
+
+            // I'm still not quit sure what this "active_flag" is, but it's unset for these moves and reset for other moves?
+            // Enabling it for this and the previous reset_types doesn't appear to have any different behavior off a few quick tests
+            GroundTrans => {
+                energy.speed_limit = PaddedVec2::new(-1.0, 0.0);
+                boma.adjust_speed_for_ground_normal(&move_speed)
+            },
+
+            // Haven't quite figured out where this gets used yet, and the work const has a few hits so I'm just not quite sure
+            GroundTransLoopGekikara => {
+                energy.active_flag = true;
+                energy.speed_limit = PaddedVec2::new(-1.0, 0.0);
+                let some_rate = boma.get_float(0x1000009);
+                let motion_rate = boma.motion_rate();
+                let speed = if some_rate != 0.0 && motion_rate / some_rate != 0.0 {
+                    PaddedVec2::new(
+                        move_speed.x * some_rate / motion_rate,
+                        move_speed.y * some_rate / motion_rate
+                    )
+                } else {
+                    PaddedVec2::zeros()
+                };
+                // a motion rate close enough to 0 overflows instead, which is treated like a motion rate of 0
+                if speed.is_finite() {
+                    speed
+                } else {
+                    PaddedVec2::zeros()
                 }
-                energy.angle
-            };
-            PaddedVec2::new(
-                move_speed.x * angle.cos() + move_speed.y * angle.sin(),
-                move_speed.y * angle.cos() - move_speed.x * angle.sin()
-            )
-        },
-
-        // Cliff functions require using a dedicated function, probably to figure out where the fighter needs to move
-        // to in order to complete the cliff catch
-        // These likely happen in a very brief, perhaps only 1 frame, window
-        CliffTransIntp | CliffTrans | CliffTransGround => {
-
-            let motion_module = *(boma as *const BattleObjectModuleAccessor as *const u64).add(0x88 / 0x8);
-            let motion_vtable = *(motion_module as *const *const u64);
-            let some_func: extern "C" fn(u64) -> energy::Vec4 = std::mem::transmute(*motion_vtable.add(0x230 / 0x8));
-            let vec = some_func(motion_module);
-            let vec = Vector4f {
-                x: vec.x,
-                y: vec.y,
-                z: vec.z,
-                w: vec.w
-            };
-            let vec = handle_cliff(boma, &vec);
-            if reset_type == CliffTransIntp {
-                let frame = WorkModule::get_int(boma, 0x11000005);
-                let interpolated = 1.0 / (frame + 1) as f32;
-                PaddedVec2::new(vec.x * interpolated, vec.y * interpolated)
-            } else {
-                PaddedVec2::new(vec.x, vec.y)
-            }
-        },
-
-        // LadderMove appears to be for when you are actually moving up/down the later
-        LadderMove => {
-            let stick_y = ControlModule::get_stick_y(boma);
-            let speed_y = if 0.5 <= stick_y.abs() {
-                if stick_y <= 0.0 {
-                    -WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("ladder_speed_d_max")) * MotionModule::rate(boma)
+            },
+
+            // When you are in the air your speed doesn't have any special calculations.
+            // AirTransAngleSuperJumpPunch is steered with the stick by the fighters that use it, see handlers::mario
+            AirTrans | AirTrans2nd | AirTransAngleSuperJumpPunch => move_speed,
+
+            // This multiplies by the angle set with (afaik) app::sv_kinetic_energy::set_angle
+            // Set angle whole is used regardless of the energy reset type
+            AirTransAngle => {
+                energy_log!(
+                    Level::Trace,
+                    Context::new(Module::Motion).with_reset_type(reset_type as u32).with_entry_id(boma.entry_id()),
+                    "Rotating on angle: {}", energy.angle
+                );
+                energy.active_flag = true;
+                move_speed.rotate(energy.angle)    
+            },
+
+            // Here we zero out the X speed and literally only use the Y speed. Epic!
+            AirTransY => PaddedVec2::new(0.0, move_speed.y),
+
+            // Cliff functions require using a dedicated function, probably to figure out where the fighter needs to move
+            // to in order to complete the cliff catch
+            // These likely happen in a very brief, perhaps only 1 frame, window
+            CliffTransIntp | CliffTrans | CliffTransGround => {
+                let speed = boma.cliff_trans_speed();
+                if reset_type == CliffTransIntp {
+                    let frame = boma.get_int(0x11000005);
+                    // the frame can be -1 (or anything) once the interpolation is over, which would divide by 0 or overflow
+                    let interpolated = 1.0 / (frame.max(0) as f32 + 1.0);
+                    speed * interpolated
                 } else {
-                    WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("ladder_speed_u_max")) * MotionModule::rate(boma)
+                    speed
                 }
-            } else {
-                0.0
-            };
+            },
+
+            // LadderMove appears to be for when you are actually moving up/down the later
+            LadderMove => {
+                let stick_y = boma.get_stick_y();
+                let speed_y = if 0.5 <= stick_y.abs() {
+                    if stick_y <= 0.0 {
+                        -boma.get_param_float(smash::hash40("common"), smash::hash40("ladder_speed_d_max")) * boma.motion_rate()
+                    } else {
+                        boma.get_param_float(smash::hash40("common"), smash::hash40("ladder_speed_u_max")) * boma.motion_rate()
+                    }
+                } else {
+                    0.0
+                };
+
+                PaddedVec2::new(0.0, speed_y)
+            },
+
+            // As opposed to LadderMove, LadderTrans is likely for when you are getting on/off the ladder
+            // 
+            // The reason I say this, is due to a bug in reimplementation, when you would get off the ladder you
+            // would meet god in the top blastzone
+            LadderTrans => {
+                let ladder_end_y = boma.get_float(*FIGHTER_STATUS_LADDER_WORK_FLOAT_LADDER_END_Y);
+                let ladder_end_start_y = boma.get_float(*FIGHTER_STATUS_LADDER_WORK_FLOAT_LADDER_END_START_Y);
+                let vec = boma.trans_tra();
+                let speed_y = (ladder_end_y + vec.y) - ladder_end_start_y;
+                boma.add_float(speed_y, *FIGHTER_STATUS_LADDER_WORK_FLOAT_LADDER_END_START_Y);
+                PaddedVec2::new(0.0, speed_y)
+            }
+            // _ => {}
+        }
+    };
 
-            PaddedVec2::new(0.0, speed_y)
-        },
+    // Status scripts can reshape the root motion at runtime, see root_motion
+    let speed = match root_motion::get(boma) {
+        Some(modifier) => modifier.apply(speed, boma.lr()),
+        None => speed
+    };
 
-        // As opposed to LadderMove, LadderTrans is likely for when you are getting on/off the ladder
-        // 
-        // The reason I say this, is due to a bug in reimplementation, when you would get off the ladder you
-        // would meet god in the top blastzone
-        LadderTrans => {
-            let ladder_end_y = WorkModule::get_float(boma, *FIGHTER_STATUS_LADDER_WORK_FLOAT_LADDER_END_Y);
-            let ladder_end_start_y = WorkModule::get_float(boma, *FIGHTER_STATUS_LADDER_WORK_FLOAT_LADDER_END_START_Y);
-            let mut vec = Vector3f { x: 0.0, y: 0.0, z: 0.0 };
-            MotionModule::trans_tra(boma, &mut vec, true, true);
-            let speed_y = (ladder_end_y + vec.y) - ladder_end_start_y;
-            WorkModule::add_float(boma, speed_y, *FIGHTER_STATUS_LADDER_WORK_FLOAT_LADDER_END_START_Y);
-            PaddedVec2::new(0.0, speed_y)
+    // Walking up a slope shortens the root motion and walking down it lengthens it, see EnergyConfig::slope
+    let slope = &crate::config::get().slope;
+    let speed = if slope.enabled && reset_type.is_ground() {
+        speed * slope.modifiers(boma.ground_normal(), speed.x).speed_max_mul
+    } else {
+        speed
+    };
+
+    // Status scripts can keep grounded animations from carrying the fighter off ledges, see EnergyConfig::ledge
+    let ledge = &crate::config::get().ledge;
+    let speed = if reset_type.is_ground() {
+        match ledge.behavior(boma) {
+            LedgeBehavior::Allow => speed,
+            behavior => ledge.adjust(behavior, speed, boma.lr(), |distance| boma.is_near_ledge(distance))
         }
-        // _ => {}
+    } else {
+        speed
     };
 
     // It is unclear to me why this specific case is handled so explicitly, but it is
@@ -432,28 +573,24 @@ unsafe fn update_hook(energy: &mut FighterKineticEnergyMotion, boma: &mut Battle
     // Since acceleration is just the difference in speed between two frames, just subtract where we want to be 
     // and where we were/are
     energy.set_values_and_process(
-        PaddedVec2::new(speed.x - speed_to_change_from.x, speed.y - speed_to_change_from.y),
+        speed - speed_to_change_from,
         PaddedVec2::new(-1.0, -1.0),
         speed,
         boma
     );
 }
 
-#[cfg(not(feature = "dev-plugin"))]
-#[skyline::hook(offset = 0x6d6400)]
-unsafe fn initialize_hook(energy: &mut FighterKineticEnergyMotion, boma: &mut BattleObjectModuleAccessor) {
+pub unsafe fn initialize_motion<A: FighterAccessor>(energy: &mut FighterKineticEnergyMotion, boma: &mut A) {
     let reset_type: EnergyMotionResetType = std::mem::transmute(energy.energy_reset_type);
     if reset_type.is_ground() {
         energy.speed_brake = PaddedVec2::new(
-            WorkModule::get_param_float(boma, smash::hash40("ground_brake"), 0),
+            boma.get_param_float(smash::hash40("ground_brake"), 0),
             0.0
         );
     }
 }
 
-#[cfg(not(feature = "dev-plugin"))]
-#[skyline::hook(offset = 0x6d6460)]
-unsafe fn setup_hook(energy: &mut FighterKineticEnergyMotion, reset_type: EnergyMotionResetType, initial_speed: &Vector3f, unk: u64, boma: &mut BattleObjectModuleAccessor) {
+pub unsafe fn setup_motion<A: FighterAccessor>(energy: &mut FighterKineticEnergyMotion, reset_type: EnergyMotionResetType, initial_speed: &Vector3f, _unk: u64, boma: &mut A) {
     energy.clear_energy();
     energy.speed_mul = 1.0;
     energy.lr = 1.0;
@@ -466,31 +603,30 @@ unsafe fn setup_hook(energy: &mut FighterKineticEnergyMotion, reset_type: Energy
     energy.speed_mul_2nd = PaddedVec2::new(1.0, 1.0);
     energy.energy_reset_type = reset_type as u32;
     energy.speed = PaddedVec2::new(initial_speed.x, initial_speed.y);
-    energy.lr = PostureModule::lr(boma);
+    energy.lr = boma.lr();
+
+    fighter_state::update(boma, |fighter| fighter.decay = None);
 
     use EnergyMotionResetType::*;
-    match reset_type {
-        GroundTransLoop | GroundTransLoopGekikara | GroundTrans => {
-            let new_speed = super::energy::KineticEnergy::adjust_speed_for_ground_normal(&energy.speed, boma);
-            energy.speed = new_speed;
-        },
-        AirTrans => {
-            let reserve = WorkModule::get_float(boma, *FIGHTER_STATUS_WORK_ID_FLOAT_RESERVE_KINETIC_MOTION_SPEED_MUL);
-            if reserve != 0.0 {
-                energy.speed_mul = reserve;
-            }
-        },
-        AirTransAngleSuperJumpPunch => {
-            energy.speed_mul = if WorkModule::is_flag(boma, *FIGHTER_STATUS_SUPER_JUMP_PUNCH_FLAG_AIR_MOTION) {
-                WorkModule::get_float(boma, *FIGHTER_STATUS_SUPER_JUMP_PUNCH_WORK_FLOAT_CONST_AIR_PASS_MUL)
-            } else {
-                WorkModule::get_float(boma, *FIGHTER_STATUS_SUPER_JUMP_PUNCH_WORK_FLOAT_CONST_PASS_MUL)
-            };
-        },
-        _ => {}
+    let handler = handlers::find(boma.fighter_kind(), boma.status_kind());
+    if !handler.map_or(false, |handler| handler.setup_motion(energy, reset_type, boma)) {
+        match reset_type {
+            GroundTransLoop | GroundTransLoopGekikara | GroundTrans => {
+                let new_speed = boma.adjust_speed_for_ground_normal(&energy.speed);
+                energy.speed = new_speed;
+            },
+            AirTrans => {
+                let reserve = boma.get_float(*FIGHTER_STATUS_WORK_ID_FLOAT_RESERVE_KINETIC_MOTION_SPEED_MUL);
+                if reserve != 0.0 {
+                    energy.speed_mul = reserve;
+                }
+            },
+            _ => {}
+        }
     }
 
-    energy.initialize(boma);
+    // the game calls this through the vtable, which ends up in initialize_hook anyway
+    initialize_motion(energy, boma);
     energy.prev_speed = PaddedVec2::new(initial_speed.x, initial_speed.y);
     energy.update_flag = false;
 }
@@ -498,10 +634,17 @@ unsafe fn setup_hook(energy: &mut FighterKineticEnergyMotion, reset_type: Energy
 pub fn install() {
     #[cfg(not(feature = "dev-plugin"))]
     {
-        skyline::install_hooks!(
-            update_hook,
-            initialize_hook,
-            setup_hook
-        );
+        use crate::offsets::{self, Function::*};
+
+        if !offsets::all_resolved(&[UpdateMotion, InitializeMotion, SetupMotion, ProcessEnergy, HandleCliff, AdjustSpeedForGroundNormal]) {
+            energy_log!(Level::Error, Context::new(Module::Motion), "Not installing the motion energy hooks");
+            return;
+        }
+
+        unsafe {
+            offsets::install_hook(UpdateMotion, update_hook as *const ());
+            offsets::install_hook(InitializeMotion, initialize_hook as *const ());
+            offsets::install_hook(SetupMotion, setup_hook as *const ());
+        }
     }
 }
\ No newline at end of file
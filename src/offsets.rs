@@ -0,0 +1,248 @@
+//! Finds the game functions that energy_id hooks and calls.
+//!
+//! Each function is first searched for by its byte signature in the game's text section, and if that fails
+//! (or it has no signature yet) the offset for the running game version is taken from a fallback table.
+//! A fallback offset is checked against the signature, or without one, against the shape of a function's first
+//! instructions, so a hook is never installed over the middle of some other function.
+//! Anything that can't be resolved is reported at startup, and the hooks that need it are not installed.
+//!
+//! The signatures are made from a dump of the game's text section with tools/make_signatures, which fills them in here
+//! from the fallback offsets. None of them have been made yet, since it needs a dump of 13.0.1.
+
+use super::{
+    log::{Context, Level, Module},
+    signature::{self, Signature}
+};
+
+/// The game functions that energy_id hooks or calls
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(usize)]
+pub enum Function {
+    UpdateControl,
+    InitializeControl,
+    SetupControl,
+    UpdateMotion,
+    InitializeMotion,
+    SetupMotion,
+    UpdateStop,
+    InitializeStop,
+    SetupStop,
+    ProcessEnergy,
+    HandleCliff,
+    GetBattleObjectFromId,
+    AdjustSpeedForGroundNormal,
+}
+
+/// Where a function can be found
+pub struct FunctionInfo {
+    pub function: Function,
+    pub name: &'static str,
+    /// The byte signature of the start of the function (see tools/make_signatures), if one has been made for it
+    pub signature: Option<&'static str>,
+    /// The offset of the function from the start of the text section, for each game version
+    pub fallbacks: &'static [(&'static str, usize)],
+}
+
+const V13_0_1: &str = "13.0.1";
+
+pub const FUNCTIONS: &[FunctionInfo] = &[
+    FunctionInfo { function: Function::UpdateControl, name: "FighterKineticEnergyControl::update", signature: None, fallbacks: &[(V13_0_1, 0x6d3610)] },
+    FunctionInfo { function: Function::InitializeControl, name: "FighterKineticEnergyControl::initialize", signature: None, fallbacks: &[(V13_0_1, 0x6d4040)] },
+    FunctionInfo { function: Function::SetupControl, name: "FighterKineticEnergyControl::setup", signature: None, fallbacks: &[(V13_0_1, 0x6d4ba0)] },
+    FunctionInfo { function: Function::UpdateMotion, name: "FighterKineticEnergyMotion::update", signature: None, fallbacks: &[(V13_0_1, 0x6d5c90)] },
+    FunctionInfo { function: Function::InitializeMotion, name: "FighterKineticEnergyMotion::initialize", signature: None, fallbacks: &[(V13_0_1, 0x6d6400)] },
+    FunctionInfo { function: Function::SetupMotion, name: "FighterKineticEnergyMotion::setup", signature: None, fallbacks: &[(V13_0_1, 0x6d6460)] },
+    FunctionInfo { function: Function::UpdateStop, name: "FighterKineticEnergyStop::update", signature: None, fallbacks: &[(V13_0_1, 0x6d6630)] },
+    FunctionInfo { function: Function::InitializeStop, name: "FighterKineticEnergyStop::initialize", signature: None, fallbacks: &[(V13_0_1, 0x6d80e0)] },
+    FunctionInfo { function: Function::SetupStop, name: "FighterKineticEnergyStop::setup", signature: None, fallbacks: &[(V13_0_1, 0x6d8540)] },
+    FunctionInfo { function: Function::ProcessEnergy, name: "KineticEnergy::process", signature: None, fallbacks: &[(V13_0_1, 0x47bf70)] },
+    FunctionInfo { function: Function::HandleCliff, name: "handle_cliff", signature: None, fallbacks: &[(V13_0_1, 0x6941c0)] },
+    FunctionInfo { function: Function::GetBattleObjectFromId, name: "get_battle_object_from_id", signature: None, fallbacks: &[(V13_0_1, 0x3ac540)] },
+    FunctionInfo { function: Function::AdjustSpeedForGroundNormal, name: "KineticEnergy::adjust_speed_for_ground_normal", signature: None, fallbacks: &[(V13_0_1, 0x47b4d0)] },
+];
+
+/// How a function was found
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Resolution {
+    Signature(usize),
+    Fallback(usize),
+    Unresolved,
+}
+
+impl Resolution {
+    pub fn offset(self) -> Option<usize> {
+        match self {
+            Resolution::Signature(offset) | Resolution::Fallback(offset) => Some(offset),
+            Resolution::Unresolved => None
+        }
+    }
+}
+
+/// Finds a function in the text section. A fallback offset is only used if the bytes there are the function's: they
+/// have to match its signature, or if it has none, look like the start of a function
+/// # Arguments
+/// * `info` - The function to find
+/// * `text` - The game's text section
+/// * `version` - The display version of the running game
+pub fn resolve(info: &FunctionInfo, text: &[u8], version: &str) -> Resolution {
+    let is_valid = |offset: usize| offset % 4 == 0 && offset < text.len();
+
+    let signature = info.signature.and_then(Signature::parse);
+    if info.signature.is_some() {
+        match signature.as_ref().map(|signature| signature.find_unique(text)) {
+            Some(Ok(offset)) if is_valid(offset) => return Resolution::Signature(offset),
+            Some(Ok(offset)) => energy_log!(Level::Warn, Context::new(Module::Offsets), "{}: signature matched misaligned offset {:#x}", info.name, offset),
+            Some(Err(error)) => energy_log!(Level::Warn, Context::new(Module::Offsets), "{}: {}", info.name, error),
+            None => energy_log!(Level::Warn, Context::new(Module::Offsets), "{}: signature is malformed", info.name)
+        }
+    }
+
+    let offset = match info.fallbacks.iter().find(|(fallback_version, _)| *fallback_version == version) {
+        Some(&(_, offset)) => offset,
+        None => return Resolution::Unresolved
+    };
+    if !is_valid(offset) {
+        energy_log!(Level::Warn, Context::new(Module::Offsets), "{}: fallback offset {:#x} is outside of the text section", info.name, offset);
+        return Resolution::Unresolved;
+    }
+
+    let is_verified = match &signature {
+        Some(signature) => signature.matches_at(text, offset),
+        None => signature::is_function_start(text, offset)
+    };
+    if is_verified {
+        Resolution::Fallback(offset)
+    } else {
+        energy_log!(Level::Warn, Context::new(Module::Offsets), "{}: the bytes at fallback offset {:#x} aren't the function's", info.name, offset);
+        Resolution::Unresolved
+    }
+}
+
+static mut RESOLVED: [Option<usize>; FUNCTIONS.len()] = [None; FUNCTIONS.len()];
+
+/// Resolves every function, this has to be called before any hooks are installed
+pub fn resolve_all() {
+    unsafe {
+        use skyline::hooks::{getRegionAddress, Region};
+
+        let text_start = getRegionAddress(Region::Text) as *const u8;
+        let text_len = getRegionAddress(Region::Rodata) as usize - text_start as usize;
+        let text = std::slice::from_raw_parts(text_start, text_len);
+
+        let mut display_version = skyline::nn::oe::DisplayVersion { name: [0; 16] };
+        skyline::nn::oe::GetDisplayVersion(&mut display_version);
+        let version = std::ffi::CStr::from_ptr(display_version.name.as_ptr() as _).to_string_lossy();
+
+        for info in FUNCTIONS {
+            let resolution = resolve(info, text, &version);
+            match resolution {
+                Resolution::Unresolved => energy_log!(Level::Error, Context::new(Module::Offsets), "Failed to resolve {} on version {}", info.name, version),
+                Resolution::Fallback(offset) => energy_log!(Level::Info, Context::new(Module::Offsets), "{} has no signature match, using {:#x} from the {} table", info.name, offset, version),
+                Resolution::Signature(_) => {}
+            }
+            RESOLVED[info.function as usize] = resolution.offset().map(|offset| text_start as usize + offset);
+        }
+    }
+}
+
+/// Gets the address of a function, if it was resolved
+pub fn address(function: Function) -> Option<usize> {
+    unsafe {
+        RESOLVED[function as usize]
+    }
+}
+
+/// Checks that every function is resolved, printing the ones that aren't
+pub fn all_resolved(functions: &[Function]) -> bool {
+    let mut all_resolved = true;
+    for &function in functions {
+        if address(function).is_none() {
+            energy_log!(Level::Error, Context::new(Module::Offsets), "{:?} is unresolved", function);
+            all_resolved = false;
+        }
+    }
+    all_resolved
+}
+
+/// Gets the address of a function that is only called from hooks, which are never installed unless it resolved
+pub fn required(function: Function) -> usize {
+    address(function).unwrap_or_else(|| panic!("{:?} was called but never resolved", function))
+}
+
+/// Hooks a function, replacing it entirely
+/// # Arguments
+/// * `function` - The function to hook, which must be resolved
+/// * `replacement` - The function to call instead
+pub unsafe fn install_hook(function: Function, replacement: *const ()) {
+    skyline::hooks::A64HookFunction(
+        required(function) as *const skyline::libc::c_void,
+        replacement as *const skyline::libc::c_void,
+        std::ptr::null_mut()
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEXT: &[u8] = &[
+        0xFF, 0x43, 0x01, 0xD1, 0xFD, 0x7B, 0x03, 0xA9,
+        0xF4, 0x4F, 0x04, 0xA9, 0xFD, 0xC3, 0x00, 0x91,
+        0xFF, 0x43, 0x02, 0xD1, 0xFD, 0x7B, 0x03, 0xA9,
+        0x00, 0x00, 0x80, 0x52, 0xC0, 0x03, 0x5F, 0xD6,
+    ];
+
+    fn info(signature: Option<&'static str>, fallbacks: &'static [(&'static str, usize)]) -> FunctionInfo {
+        FunctionInfo { function: Function::ProcessEnergy, name: "test", signature, fallbacks }
+    }
+
+    #[test]
+    fn signature_is_preferred() {
+        let info = info(Some("00 00 80 52"), &[("13.0.1", 0x10)]);
+        assert_eq!(resolve(&info, TEXT, "13.0.1"), Resolution::Signature(0x18));
+    }
+
+    #[test]
+    fn fallback_is_version_specific() {
+        let info = info(None, &[("13.0.1", 0x10)]);
+        assert_eq!(resolve(&info, TEXT, "13.0.1"), Resolution::Fallback(0x10));
+        assert_eq!(resolve(&info, TEXT, "13.0.0"), Resolution::Unresolved);
+    }
+
+    #[test]
+    fn fallback_is_checked_against_signature() {
+        // matches at 0x0 and 0x10, so the signature alone can't tell which one it is
+        let info = info(Some("FF 43 ?? D1"), &[("13.0.1", 0x10)]);
+        assert_eq!(resolve(&info, TEXT, "13.0.1"), Resolution::Fallback(0x10));
+
+        let info = info(Some("FF 43 ?? D1"), &[("13.0.1", 0x8)]);
+        assert_eq!(resolve(&info, TEXT, "13.0.1"), Resolution::Unresolved);
+    }
+
+    #[test]
+    fn fallback_without_signature_must_be_function_start() {
+        let info = info(None, &[("13.0.1", 0x10)]);
+        assert_eq!(resolve(&info, TEXT, "13.0.1"), Resolution::Fallback(0x10));
+
+        // the middle of the function at 0x10
+        let info = info(None, &[("13.0.1", 0x18)]);
+        assert_eq!(resolve(&info, TEXT, "13.0.1"), Resolution::Unresolved);
+    }
+
+    #[test]
+    fn invalid_results_are_rejected() {
+        // matches, but not on an instruction boundary
+        let info = info(Some("D1 FD 7B 03 A9 F4"), &[]);
+        assert_eq!(resolve(&info, TEXT, "13.0.1"), Resolution::Unresolved);
+
+        let info = info(None, &[("13.0.1", 0x1000)]);
+        assert_eq!(resolve(&info, TEXT, "13.0.1"), Resolution::Unresolved);
+    }
+
+    #[test]
+    fn table_covers_every_function() {
+        for (index, info) in FUNCTIONS.iter().enumerate() {
+            assert_eq!(info.function as usize, index);
+        }
+    }
+}
@@ -0,0 +1,203 @@
+//! Property tests for the control, motion and stop energies.
+//!
+//! Every case is built from its own seed: a random reset type, a stubbed fighter with random params (see
+//! `StubFighter::random_params`), facing, situation and animation speed, a random starting speed and a random stick on
+//! each frame. The energy is then set up and updated for a few frames, checking the property after every update. A
+//! failure reports its seed, which is enough to replay it with `run_case`
+//!
+//! Like the golden vectors these run against the stub's approximation of the game's processing, so they check the
+//! energy logic and not the game's processing
+
+use smash::lib::lua_const::*;
+use smash::phx::Vector3f;
+
+use super::{
+    control::FighterKineticEnergyControl,
+    energy::PaddedVec2,
+    fighter::stub::{Rng, StubFighter},
+    golden::GoldenEnergy,
+    motion::FighterKineticEnergyMotion,
+    stop::{EnergyStopResetType::{self, *}, FighterKineticEnergyStop}
+};
+
+const CASES: u64 = 2000;
+const FRAMES: usize = 8;
+
+fn random_trans(rng: &mut Rng) -> Option<Vector3f> {
+    if rng.bool() {
+        let speed = rng.vec2(-3.0, 3.0);
+        Some(Vector3f { x: 0.0, y: speed.y, z: speed.x })
+    } else {
+        None
+    }
+}
+
+fn random_fighter(rng: &mut Rng) -> StubFighter {
+    let mut fighter = StubFighter::new();
+    fighter.random_params = Some(rng.next_u64());
+    fighter.stick = rng.vec2(-1.0, 1.0);
+    fighter.lr = rng.pick(&[1.0, -1.0]);
+    fighter.situation_kind = rng.pick(&[*SITUATION_KIND_GROUND, *SITUATION_KIND_AIR]);
+    fighter.ignore_common_speed_limit = rng.bool();
+    fighter.is_turn = rng.bool();
+    fighter.reverse_x_frame = rng.int(0, 2);
+    fighter.motion_frame = rng.range(0.0, 30.0);
+    fighter.trans_move_speed = random_trans(rng);
+    fighter.trans_move_speed_2nd = random_trans(rng);
+    fighter.cliff_trans_speed = rng.vec2(-3.0, 3.0);
+    if rng.bool() {
+        let angle = rng.range(-0.5, 0.5);
+        fighter.ground_normal = Some(PaddedVec2::new(-angle.sin(), angle.cos()));
+    }
+    fighter
+}
+
+/// Runs the case of a seed, calling `check` with the speed before every update and the energy after it
+fn run_case<E: GoldenEnergy>(seed: u64, skip: &[E::ResetType], check: impl Fn(PaddedVec2, &E) -> Option<String>) {
+    let mut rng = Rng::new(seed);
+    let reset_type = E::reset_type(rng.int(0, E::RESET_TYPES as i32) as u32);
+    if skip.contains(&reset_type) {
+        return;
+    }
+
+    let mut fighter = random_fighter(&mut rng);
+    let mut energy = E::new(reset_type);
+    unsafe {
+        energy.setup(reset_type, rng.vec2(-5.0, 5.0), &mut fighter);
+    }
+
+    for frame in 0..FRAMES {
+        fighter.stick = rng.vec2(-1.0, 1.0);
+        // the animation stopping partway through is what hands the motion energy off
+        if rng.int(0, 4) == 0 {
+            fighter.trans_move_speed = None;
+        }
+
+        let before = energy.speed;
+        unsafe {
+            energy.update(&mut fighter);
+        }
+        if let Some(error) = check(before, &energy) {
+            panic!("seed {} ({:?}), frame {}: {}", seed, reset_type, frame + 1, error);
+        }
+    }
+}
+
+fn check_brake<E: GoldenEnergy>(skip: &[E::ResetType]) {
+    // Without any accel the only thing that changes the speed is the brake, which should stop at 0
+    let flipped = |before: f32, after: f32, accel: f32| accel == 0.0 && before * after < 0.0;
+    for seed in 0..CASES {
+        run_case::<E>(seed, skip, |before, energy| {
+            if flipped(before.x, energy.speed.x, energy.accel.x) || flipped(before.y, energy.speed.y, energy.accel.y) {
+                Some(format!("braking turned [{}, {}] into [{}, {}]", before.x, before.y, energy.speed.x, energy.speed.y))
+            } else {
+                None
+            }
+        });
+    }
+}
+
+fn check_speed_limit<E: GoldenEnergy>() {
+    let exceeds = |speed: f32, limit: f32| 0.0 < limit && limit * (1.0 + 1.0e-6) < speed.abs();
+    for seed in 0..CASES {
+        run_case::<E>(seed, &[], |_, energy| {
+            let (speed, limit) = (energy.speed, energy.speed_limit);
+            if energy.enable && (exceeds(speed.x, limit.x) || exceeds(speed.y, limit.y)) {
+                Some(format!("[{}, {}] is over the limit of [{}, {}]", speed.x, speed.y, limit.x, limit.y))
+            } else {
+                None
+            }
+        });
+    }
+}
+
+#[test]
+fn brake_never_flips_speed() {
+    check_brake::<FighterKineticEnergyControl>(&[]);
+    check_brake::<FighterKineticEnergyMotion>(&[]);
+    // These set the speed directly instead of braking
+    check_brake::<FighterKineticEnergyStop>(&[Capture, CaptureBeetle, GlidStart, AirLassoHang, AirLassoRewind, DamageKnockBack]);
+}
+
+#[test]
+fn speed_never_exceeds_limit() {
+    check_speed_limit::<FighterKineticEnergyControl>();
+    check_speed_limit::<FighterKineticEnergyMotion>();
+    check_speed_limit::<FighterKineticEnergyStop>();
+}
+
+/// The game samples the push-out curve at the start of each frame, so the pushes add up to the curve at `(n - 1) / n`
+/// of the overlap and never reach its end. This is the game's behavior, so it's what is checked here
+#[test]
+fn knock_back_pushes_out_curve_of_overlap() {
+    for seed in 0..CASES {
+        let mut rng = Rng::new(seed);
+        let overlap = rng.range(-5.0, 5.0);
+        let speed_rate = rng.range(0.0, 100.0);
+        let mut fighter = random_fighter(&mut rng)
+            .with_param("common", "damage_knock_back_hitstop_frame_rate", rng.range(0.0, 100.0))
+            .with_param("common", "damage_knock_back_speed_x_rate", speed_rate);
+        fighter.situation_kind = *SITUATION_KIND_GROUND;
+        // the common limit cutting the push short is the game's doing and not the curve's
+        fighter.ignore_common_speed_limit = true;
+        fighter.knock_back_push_out = Some((overlap, rng.int(0, 30)));
+
+        let mut energy = FighterKineticEnergyStop::new(DamageKnockBack);
+        unsafe {
+            energy.setup(DamageKnockBack, PaddedVec2::zeros(), &mut fighter);
+        }
+        let hitstop_frames = energy.hitstop_frames;
+
+        let mut pushed = 0.0;
+        let mut frames = 0;
+        while energy.hitstop_frames > 0.0 && frames < 64 {
+            unsafe {
+                energy.update(&mut fighter);
+            }
+            pushed += energy.speed.x;
+            frames += 1;
+        }
+
+        assert_eq!(energy.hitstop_frames, 0.0, "seed {}: the push never ended", seed);
+        // the last update only ends the push, every one before it samples the curve
+        let expected = if hitstop_frames > 0.0 {
+            let last_sample = (hitstop_frames.ceil() - 1.0) / hitstop_frames;
+            crate::config::get().knock_back.curve(speed_rate).evaluate(last_sample) * overlap
+        } else {
+            0.0
+        };
+        assert!((pushed - expected).abs() <= 1.0e-4 * overlap.abs().max(1.0), "seed {}: pushed {} instead of {}", seed, pushed, expected);
+    }
+}
+
+#[test]
+fn interpolation_reaches_target_speed() {
+    for seed in 0..CASES {
+        let mut rng = Rng::new(seed);
+        let reset_type: EnergyStopResetType = rng.pick(&[DamageGround, DamageGroundOrbit, DamageAir, DamageAirOrbit]);
+        // In the air with no brake the interpolation is the only thing moving the speed
+        let mut fighter = random_fighter(&mut rng).with_param("common", "damage_air_brake", 0.0);
+        fighter.situation_kind = *SITUATION_KIND_AIR;
+        fighter.ignore_common_speed_limit = true;
+
+        let mut energy = FighterKineticEnergyStop::new(reset_type);
+        let initial_speed = PaddedVec2::new(rng.range(0.5, 5.0), rng.range(-5.0, 5.0));
+        unsafe {
+            energy.setup(reset_type, initial_speed, &mut fighter);
+        }
+
+        let frames = rng.int(1, 10);
+        let target = rng.vec2(-5.0, 5.0);
+        energy.damage_target_speed = target;
+        energy.interpolation_frames_remaining = frames as u8;
+        for _ in 0..frames {
+            unsafe {
+                energy.update(&mut fighter);
+            }
+        }
+
+        assert_eq!(energy.interpolation_frames_remaining, 0, "seed {}", seed);
+        assert!(energy.speed.x == target.x && energy.speed.y == target.y,
+            "seed {} ({:?}): ended on [{}, {}] instead of [{}, {}]", seed, reset_type, energy.speed.x, energy.speed.y, target.x, target.y);
+    }
+}
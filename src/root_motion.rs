@@ -0,0 +1,192 @@
+//! Lets status and ACMD scripts reshape the speed that the motion energy takes from the animation, without editing
+//! the animation itself.
+//!
+//! A modifier is kept in the fighter's state (see fighter_state) and applies from the next frame of the motion energy
+//! until it is cleared, or until the fighter changes to another status. Changing the kinetic type doesn't clear it, so a
+//! status can set its modifier before or after changing the kinetic type
+
+use smash::app::BattleObjectModuleAccessor;
+
+use super::{
+    energy::PaddedVec2,
+    fighter::FighterAccessor,
+    fighter_state
+};
+
+/// How one axis of the root motion is changed, in the order `mul`, `offset` and then the clamp
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[repr(C)]
+pub struct AxisModifier {
+    pub mul: f32,
+    pub offset: f32,
+    pub min: f32,
+    pub max: f32,
+}
+
+impl AxisModifier {
+    pub const IDENTITY: Self = Self {
+        mul: 1.0,
+        offset: 0.0,
+        min: f32::NEG_INFINITY,
+        max: f32::INFINITY,
+    };
+
+    pub fn apply(&self, speed: f32) -> f32 {
+        (speed * self.mul + self.offset).max(self.min).min(self.max)
+    }
+}
+
+/// How the root motion is changed. The x axis is relative to the direction the fighter is facing, so that a positive
+/// offset always moves the fighter forward
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[repr(C)]
+pub struct RootMotionModifier {
+    pub x: AxisModifier,
+    pub y: AxisModifier,
+}
+
+impl RootMotionModifier {
+    pub const IDENTITY: Self = Self {
+        x: AxisModifier::IDENTITY,
+        y: AxisModifier::IDENTITY,
+    };
+
+    /// Applies the modifier to the speed of the motion energy
+    /// # Arguments
+    /// * `speed` - The speed taken from the animation
+    /// * `lr` - The direction the fighter is facing
+    pub fn apply(&self, speed: PaddedVec2, lr: f32) -> PaddedVec2 {
+        let lr = if lr < 0.0 { -1.0 } else { 1.0 };
+        PaddedVec2::new(
+            self.x.apply(speed.x * lr) * lr,
+            self.y.apply(speed.y)
+        )
+    }
+}
+
+/// Gets the modifier of a fighter, if it has one that was set in their current status
+pub fn get<A: FighterAccessor + ?Sized>(boma: &mut A) -> Option<RootMotionModifier> {
+    let status_kind = boma.status_kind();
+    fighter_state::update(boma, |fighter| match fighter.root_motion {
+        Some((status, modifier)) if status == status_kind => Some(modifier),
+        _ => {
+            fighter.root_motion = None;
+            None
+        }
+    })
+}
+
+/// Changes the modifier of a fighter, starting from `RootMotionModifier::IDENTITY` if they don't have one for their
+/// current status
+fn modify<A: FighterAccessor + ?Sized>(boma: &mut A, f: impl FnOnce(&mut RootMotionModifier)) {
+    let status_kind = boma.status_kind();
+    fighter_state::update(boma, |fighter| {
+        let (status, modifier) = fighter.root_motion.get_or_insert((status_kind, RootMotionModifier::IDENTITY));
+        if *status != status_kind {
+            *status = status_kind;
+            *modifier = RootMotionModifier::IDENTITY;
+        }
+        f(modifier);
+    });
+}
+
+/// Replaces the whole modifier of a fighter
+#[no_mangle]
+pub extern "Rust" fn energy_id_set_root_motion(boma: &mut BattleObjectModuleAccessor, modifier: RootMotionModifier) {
+    modify(boma, |current| *current = modifier);
+}
+
+/// Sets the multipliers of the root motion, keeping the rest of the modifier
+#[no_mangle]
+pub extern "Rust" fn energy_id_set_root_motion_mul(boma: &mut BattleObjectModuleAccessor, x: f32, y: f32) {
+    modify(boma, |modifier| {
+        modifier.x.mul = x;
+        modifier.y.mul = y;
+    });
+}
+
+/// Sets the offsets added to the root motion, keeping the rest of the modifier
+#[no_mangle]
+pub extern "Rust" fn energy_id_set_root_motion_offset(boma: &mut BattleObjectModuleAccessor, x: f32, y: f32) {
+    modify(boma, |modifier| {
+        modifier.x.offset = x;
+        modifier.y.offset = y;
+    });
+}
+
+/// Sets the range the root motion is clamped to, keeping the rest of the modifier
+#[no_mangle]
+pub extern "Rust" fn energy_id_set_root_motion_clamp(boma: &mut BattleObjectModuleAccessor, min: PaddedVec2, max: PaddedVec2) {
+    modify(boma, |modifier| {
+        modifier.x.min = min.x;
+        modifier.x.max = max.x;
+        modifier.y.min = min.y;
+        modifier.y.max = max.y;
+    });
+}
+
+/// Removes the modifier of a fighter
+pub fn clear<A: ?Sized>(boma: &A) {
+    fighter_state::update(boma, |fighter| fighter.root_motion = None);
+}
+
+/// Removes the modifier of a fighter, so that its root motion is used as is
+#[no_mangle]
+pub extern "Rust" fn energy_id_clear_root_motion(boma: &mut BattleObjectModuleAccessor) {
+    clear(boma);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fighter::stub::StubFighter;
+
+    #[test]
+    fn axis_order() {
+        let axis = AxisModifier {
+            mul: 2.0,
+            offset: 1.0,
+            min: 0.0,
+            max: 4.0
+        };
+        assert_eq!(axis.apply(1.0), 3.0);
+        assert_eq!(axis.apply(2.0), 4.0);
+        assert_eq!(axis.apply(-1.0), 0.0);
+        assert_eq!(AxisModifier::IDENTITY.apply(-123.0), -123.0);
+    }
+
+    #[test]
+    fn x_is_relative_to_facing() {
+        let modifier = RootMotionModifier {
+            x: AxisModifier { offset: 0.5, max: 1.0, ..AxisModifier::IDENTITY },
+            ..RootMotionModifier::IDENTITY
+        };
+        assert_eq!(modifier.apply(PaddedVec2::new(0.25, 1.0), 1.0), PaddedVec2::new(0.75, 1.0));
+        assert_eq!(modifier.apply(PaddedVec2::new(-0.75, 1.0), -1.0), PaddedVec2::new(-1.0, 1.0));
+        assert_eq!(RootMotionModifier::IDENTITY.apply(PaddedVec2::new(-0.75, 1.0), -1.0), PaddedVec2::new(-0.75, 1.0));
+    }
+
+    #[test]
+    fn modifier_lasts_until_status_changes() {
+        let mut fighter = StubFighter::new();
+        fighter.status_kind = 1;
+        modify(&mut fighter, |modifier| modifier.x.mul = 2.0);
+        modify(&mut fighter, |modifier| modifier.y.mul = 0.5);
+        let modifier = get(&mut fighter).unwrap();
+        assert_eq!((modifier.x.mul, modifier.y.mul), (2.0, 0.5));
+
+        fighter.status_kind = 2;
+        assert!(get(&mut fighter).is_none());
+        fighter.status_kind = 1;
+        assert!(get(&mut fighter).is_none());
+
+        // a modifier set in the new status starts over
+        modify(&mut fighter, |modifier| modifier.y.mul = 0.5);
+        fighter.status_kind = 3;
+        modify(&mut fighter, |modifier| modifier.x.mul = 2.0);
+        let modifier = get(&mut fighter).unwrap();
+        assert_eq!((modifier.x.mul, modifier.y.mul), (2.0, 1.0));
+        clear(&fighter);
+        assert!(get(&mut fighter).is_none());
+    }
+}
@@ -0,0 +1,306 @@
+//! Byte signatures for finding functions in the game's text section, and making them from a known offset.
+//!
+//! This file only uses std, since the host tool in tools/make_signatures builds it on its own
+
+use std::fmt;
+
+/// A byte pattern to search for, where `None` matches any byte
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signature {
+    bytes: Vec<Option<u8>>,
+}
+
+impl Signature {
+    /// Parses a signature from space separated hex bytes, with `??` (or `?`) as a wildcard, e.g. `"FF 43 ?? D1"`
+    pub fn parse(pattern: &str) -> Option<Self> {
+        let bytes = pattern
+            .split_whitespace()
+            .map(|byte| match byte {
+                "?" | "??" => Some(None),
+                byte if byte.len() == 2 => u8::from_str_radix(byte, 16).ok().map(Some),
+                _ => None
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        if bytes.is_empty() || bytes.iter().all(Option::is_none) {
+            None
+        } else {
+            Some(Self { bytes })
+        }
+    }
+
+    /// Makes the shortest signature that only matches the function at `offset`, ignoring the parts of instructions that
+    /// change when the game is rebuilt (branch targets and PC relative addresses)
+    /// # Arguments
+    /// * `text` - The game's text section
+    /// * `offset` - The offset of the function from the start of the text section
+    /// * `max_len` - The longest that the signature can be, in bytes
+    pub fn generate(text: &[u8], offset: usize, max_len: usize) -> Option<Self> {
+        // instructions are always 4 byte aligned
+        if offset & 0b11 != 0 {
+            return None;
+        }
+
+        let mut bytes = Vec::new();
+        for instruction in text.get(offset..)?.chunks_exact(4).take(max_len / 4) {
+            let instruction = u32::from_le_bytes([instruction[0], instruction[1], instruction[2], instruction[3]]);
+            bytes.extend_from_slice(&instruction_pattern(instruction));
+
+            let signature = Self { bytes: bytes.clone() };
+            if signature.bytes.iter().any(Option::is_some) && signature.find_unique(text) == Ok(offset) {
+                return Some(signature);
+            }
+        }
+
+        None
+    }
+
+    /// Checks whether the signature matches at `start` in `haystack`
+    pub fn matches_at(&self, haystack: &[u8], start: usize) -> bool {
+        if start > haystack.len() || haystack.len() - start < self.bytes.len() {
+            return false;
+        }
+
+        self.bytes.iter().zip(&haystack[start..]).all(|(expected, actual)| match expected {
+            Some(expected) => expected == actual,
+            None => true
+        })
+    }
+
+    /// Finds every offset in `haystack` where the signature matches
+    pub fn find_all(&self, haystack: &[u8]) -> Vec<usize> {
+        if haystack.len() < self.bytes.len() {
+            return Vec::new();
+        }
+
+        (0..=haystack.len() - self.bytes.len())
+            .filter(|&start| self.matches_at(haystack, start))
+            .collect()
+    }
+
+    /// Finds the offset in `haystack` where the signature matches, which must be the only match
+    pub fn find_unique(&self, haystack: &[u8]) -> Result<usize, ScanError> {
+        match self.find_all(haystack).as_slice() {
+            [] => Err(ScanError::NotFound),
+            [offset] => Ok(*offset),
+            matches => Err(ScanError::Ambiguous(matches.len()))
+        }
+    }
+}
+
+impl fmt::Display for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (index, byte) in self.bytes.iter().enumerate() {
+            if index != 0 {
+                write!(f, " ")?;
+            }
+            match byte {
+                Some(byte) => write!(f, "{:02X}", byte)?,
+                None => write!(f, "??")?
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Gets the bytes of an instruction that can be matched on, with the immediates of PC relative instructions replaced by
+/// wildcards
+fn instruction_pattern(instruction: u32) -> [Option<u8>; 4] {
+    let [b0, b1, b2, b3] = instruction.to_le_bytes();
+    let is_branch = instruction & 0x7c00_0000 == 0x1400_0000;
+    let is_address = instruction & 0x1f00_0000 == 0x1000_0000;
+    let is_conditional_branch = instruction & 0xff00_0010 == 0x5400_0000;
+    let is_compare_branch = instruction & 0x7c00_0000 == 0x3400_0000;
+    let is_literal_load = instruction & 0x3b00_0000 == 0x1800_0000;
+
+    if is_branch || is_address {
+        // b, bl, adr and adrp have their immediate spread over every byte
+        [None; 4]
+    } else if is_conditional_branch || is_compare_branch || is_literal_load {
+        // b.cond, cbz, cbnz, tbz, tbnz and ldr (literal) only keep the opcode in the top byte
+        [None, None, None, Some(b3)]
+    } else {
+        [Some(b0), Some(b1), Some(b2), Some(b3)]
+    }
+}
+
+fn instruction_at(text: &[u8], offset: usize) -> Option<u32> {
+    let bytes = text.get(offset..offset.checked_add(4)?)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// Checks whether `offset` looks like the start of a function, for offsets that can't be checked against a signature.
+/// Either the first instruction makes room on the stack (`stp`/`str` with sp pre-index, `sub sp, sp, #imm` or
+/// `pacibsp`), or the instruction before can't fall through into it, so it's the end of the previous function or
+/// the padding after it
+pub fn is_function_start(text: &[u8], offset: usize) -> bool {
+    if offset & 0b11 != 0 {
+        return false;
+    }
+
+    let instruction = match instruction_at(text, offset) {
+        Some(instruction) => instruction,
+        None => return false
+    };
+    let is_pair_push = instruction & 0x3bc0_03e0 == 0x2980_03e0;
+    let is_push = instruction & 0x3be0_0fe0 == 0x3800_0fe0;
+    let is_stack_alloc = instruction & 0xff80_03ff == 0xd100_03ff;
+    let is_pacibsp = instruction == 0xd503_237f;
+    if is_pair_push || is_push || is_stack_alloc || is_pacibsp {
+        return true;
+    }
+
+    let previous = match offset.checked_sub(4).and_then(|previous| instruction_at(text, previous)) {
+        Some(previous) => previous,
+        // the start of the text section
+        None => return true
+    };
+    let is_return = previous & 0xffff_fc1f == 0xd65f_0000;
+    let is_branch = previous & 0xfc00_0000 == 0x1400_0000;
+    let is_register_branch = previous & 0xffff_fc1f == 0xd61f_0000;
+    let is_trap = previous & 0xffe0_001f == 0xd420_0000 || previous & 0xffff_0000 == 0;
+    let is_nop = previous == 0xd503_201f;
+    is_return || is_branch || is_register_branch || is_trap || is_nop
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ScanError {
+    NotFound,
+    Ambiguous(usize),
+}
+
+impl fmt::Display for ScanError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ScanError::NotFound => write!(f, "signature not found"),
+            ScanError::Ambiguous(count) => write!(f, "signature matched {} times", count)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEXT: &[u8] = &[
+        0xFF, 0x43, 0x01, 0xD1, 0xFD, 0x7B, 0x03, 0xA9,
+        0xF4, 0x4F, 0x04, 0xA9, 0xFD, 0xC3, 0x00, 0x91,
+        0xFF, 0x43, 0x02, 0xD1, 0xFD, 0x7B, 0x03, 0xA9,
+        0x00, 0x00, 0x80, 0x52, 0xC0, 0x03, 0x5F, 0xD6,
+    ];
+
+    // bl, ret, bl, ret, mov w0, #0, mov w0, #1
+    const CALLS: &[u8] = &[
+        0x10, 0x00, 0x00, 0x94, 0xC0, 0x03, 0x5F, 0xD6,
+        0x20, 0x00, 0x00, 0x94, 0xC0, 0x03, 0x5F, 0xD6,
+        0x00, 0x00, 0x80, 0x52, 0x20, 0x00, 0x80, 0x52,
+    ];
+
+    #[test]
+    fn parse_signature() {
+        assert!(Signature::parse("FF 43 ?? D1").is_some());
+        assert!(Signature::parse("ff 43 ? d1").is_some());
+        assert!(Signature::parse("").is_none());
+        assert!(Signature::parse("?? ??").is_none());
+        assert!(Signature::parse("FF 4").is_none());
+        assert!(Signature::parse("FF GG").is_none());
+    }
+
+    #[test]
+    fn find_with_wildcards() {
+        let signature = Signature::parse("FF 43 ?? D1 FD 7B").unwrap();
+        assert_eq!(signature.find_all(TEXT), vec![0x0, 0x10]);
+        assert_eq!(signature.find_unique(TEXT), Err(ScanError::Ambiguous(2)));
+
+        let signature = Signature::parse("FF 43 02 D1").unwrap();
+        assert_eq!(signature.find_unique(TEXT), Ok(0x10));
+
+        let signature = Signature::parse("C0 03 5F D6 00").unwrap();
+        assert_eq!(signature.find_unique(TEXT), Err(ScanError::NotFound));
+    }
+
+    #[test]
+    fn signature_round_trips() {
+        let signature = Signature::parse("FF 43 ?? D1").unwrap();
+        assert_eq!(signature.to_string(), "FF 43 ?? D1");
+        assert_eq!(Signature::parse(&signature.to_string()), Some(signature));
+    }
+
+    #[test]
+    fn pc_relative_immediates_are_wildcards() {
+        // bl
+        assert_eq!(instruction_pattern(0x9400_0010), [None; 4]);
+        // adrp x8, ...
+        assert_eq!(instruction_pattern(0xb000_0008), [None; 4]);
+        // b.eq
+        assert_eq!(instruction_pattern(0x5400_0040), [None, None, None, Some(0x54)]);
+        // cbz w0
+        assert_eq!(instruction_pattern(0x3400_0040), [None, None, None, Some(0x34)]);
+        // ldr x0, (literal)
+        assert_eq!(instruction_pattern(0x5800_0040), [None, None, None, Some(0x58)]);
+        // ret
+        assert_eq!(instruction_pattern(0xd65f_03c0), [Some(0xC0), Some(0x03), Some(0x5F), Some(0xD6)]);
+    }
+
+    #[test]
+    fn generated_signatures_are_unique() {
+        assert_eq!(Signature::generate(TEXT, 0x10, 64).unwrap().to_string(), "FF 43 02 D1");
+        assert_eq!(
+            Signature::generate(CALLS, 0x0, 64).unwrap().to_string(),
+            "?? ?? ?? ?? C0 03 5F D6 ?? ?? ?? ?? C0 03 5F D6"
+        );
+        assert_eq!(
+            Signature::generate(CALLS, 0x8, 64).unwrap().to_string(),
+            "?? ?? ?? ?? C0 03 5F D6 00 00 80 52"
+        );
+
+        for offset in (0..TEXT.len()).step_by(4) {
+            if let Some(signature) = Signature::generate(TEXT, offset, 64) {
+                assert_eq!(signature.find_unique(TEXT), Ok(offset));
+            }
+        }
+    }
+
+    #[test]
+    fn matches_at_checks_bounds() {
+        let signature = Signature::parse("C0 03 5F D6").unwrap();
+        assert!(signature.matches_at(TEXT, 0x1c));
+        assert!(!signature.matches_at(TEXT, 0x18));
+        assert!(!signature.matches_at(TEXT, 0x1e));
+        assert!(!signature.matches_at(TEXT, TEXT.len() + 4));
+    }
+
+    #[test]
+    fn function_starts() {
+        // sub sp, sp, #0x50
+        assert!(is_function_start(TEXT, 0x0));
+        assert!(is_function_start(TEXT, 0x10));
+        // stp x29, x30, [sp, #0x30] is inside of the function
+        assert!(!is_function_start(TEXT, 0x4));
+        assert!(!is_function_start(TEXT, 0x18));
+        // after a ret
+        assert!(is_function_start(CALLS, 0x8));
+        assert!(is_function_start(CALLS, 0x10));
+        // after a bl, which returns
+        assert!(!is_function_start(CALLS, 0x4));
+
+        // stp x29, x30, [sp, #-0x10]!, str x19, [sp, #-0x20]! and stp d9, d8, [sp, #-0x30]!
+        for &instruction in &[0xa9bf_7bfdu32, 0xf81e_0ff3, 0x6dbd_23e9] {
+            // after a bl, so only the instruction itself says it's a function start
+            let text = [0x9400_0000u32.to_le_bytes(), instruction.to_le_bytes()].concat();
+            assert!(is_function_start(&text, 0x4));
+        }
+
+        assert!(!is_function_start(TEXT, 0x2));
+        assert!(!is_function_start(TEXT, TEXT.len()));
+    }
+
+    #[test]
+    fn generate_gives_up() {
+        // too short to tell the two calls apart
+        assert!(Signature::generate(CALLS, 0x0, 8).is_none());
+        // not on an instruction boundary
+        assert!(Signature::generate(TEXT, 0x2, 64).is_none());
+        assert!(Signature::generate(TEXT, TEXT.len() + 4, 64).is_none());
+    }
+}
@@ -0,0 +1,148 @@
+use super::energy::PaddedVec2;
+
+/// Values used by the grounded energies to let slopes change how fighters move. The game only projects the speed onto
+/// the ground, so every value here defaults to having no effect.
+///
+/// The steepness of a slope is the sine of its angle, so that 1.0 would be a vertical wall
+#[derive(Debug, Copy, Clone)]
+#[repr(C)]
+pub struct SlopeParams {
+    pub enabled: bool,
+    /// Slopes with a smaller steepness than this are treated as flat ground
+    pub min_steepness: f32,
+    /// How much of the maximum speed is lost per unit of steepness while moving uphill
+    pub uphill_speed_loss: f32,
+    /// How much maximum speed is gained per unit of steepness while moving downhill
+    pub downhill_speed_gain: f32,
+    /// The acceleration per unit of steepness that pulls fighters down the slope while they are moving
+    pub downhill_accel: f32,
+    /// How much the brake grows per unit of steepness while moving uphill
+    pub uphill_brake_gain: f32,
+    /// How much of the brake is lost per unit of steepness while moving downhill
+    pub downhill_brake_loss: f32,
+}
+
+/// What a slope does to an energy for one frame
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SlopeModifiers {
+    pub speed_max_mul: f32,
+    /// Added to the horizontal acceleration, this points down the slope
+    pub accel_add: f32,
+    pub brake_mul: f32,
+}
+
+impl SlopeModifiers {
+    pub const NONE: Self = Self {
+        speed_max_mul: 1.0,
+        accel_add: 0.0,
+        brake_mul: 1.0,
+    };
+}
+
+impl SlopeParams {
+    pub const DEFAULT: Self = Self {
+        enabled: false,
+        min_steepness: 0.05,
+        uphill_speed_loss: 0.5,
+        downhill_speed_gain: 0.3,
+        downhill_accel: 0.01,
+        uphill_brake_gain: 0.5,
+        downhill_brake_loss: 0.5,
+    };
+
+    /// Gets the signed steepness of the ground, positive when it rises to the right
+    pub fn steepness(normal: PaddedVec2) -> f32 {
+        let normal = normal.normalize();
+        if normal.y <= 0.0 {
+            0.0
+        } else {
+            -normal.x
+        }
+    }
+
+    /// Gets how the slope changes an energy
+    /// # Arguments
+    /// * `normal` - The normal of the ground, or `None` if the fighter isn't touching any
+    /// * `direction` - The horizontal direction the fighter is moving in
+    pub fn modifiers(&self, normal: Option<PaddedVec2>, direction: f32) -> SlopeModifiers {
+        let steepness = match normal {
+            Some(normal) if self.enabled => Self::steepness(normal),
+            _ => return SlopeModifiers::NONE
+        };
+
+        if steepness.abs() < self.min_steepness || direction == 0.0 {
+            return SlopeModifiers::NONE;
+        }
+
+        // positive going uphill, negative going downhill
+        let uphill = steepness * direction.signum();
+        let downhill_direction = -steepness.signum();
+        if 0.0 < uphill {
+            SlopeModifiers {
+                speed_max_mul: (1.0 - self.uphill_speed_loss * uphill).max(0.0),
+                accel_add: self.downhill_accel * uphill * downhill_direction,
+                brake_mul: 1.0 + self.uphill_brake_gain * uphill,
+            }
+        } else {
+            let downhill = -uphill;
+            SlopeModifiers {
+                speed_max_mul: 1.0 + self.downhill_speed_gain * downhill,
+                accel_add: self.downhill_accel * downhill * downhill_direction,
+                brake_mul: (1.0 - self.downhill_brake_loss * downhill).max(0.0),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // rises to the right at 30 degrees
+    fn slope() -> PaddedVec2 {
+        PaddedVec2::new(-0.5, 0.75f32.sqrt())
+    }
+
+    fn enabled() -> SlopeParams {
+        SlopeParams {
+            enabled: true,
+            ..SlopeParams::DEFAULT
+        }
+    }
+
+    #[test]
+    fn disabled_or_flat() {
+        assert_eq!(SlopeParams::DEFAULT.modifiers(Some(slope()), 1.0), SlopeModifiers::NONE);
+        assert_eq!(enabled().modifiers(None, 1.0), SlopeModifiers::NONE);
+        assert_eq!(enabled().modifiers(Some(PaddedVec2::new(0.0, 1.0)), 1.0), SlopeModifiers::NONE);
+        assert_eq!(enabled().modifiers(Some(PaddedVec2::new(-0.01, 1.0)), 1.0), SlopeModifiers::NONE);
+    }
+
+    #[test]
+    fn uphill_and_downhill() {
+        let params = enabled();
+        assert!((SlopeParams::steepness(slope()) - 0.5).abs() < 1.0e-5);
+
+        let uphill = params.modifiers(Some(slope()), 1.0);
+        assert!(uphill.speed_max_mul < 1.0);
+        assert!(uphill.brake_mul > 1.0);
+        assert!(uphill.accel_add < 0.0);
+
+        let downhill = params.modifiers(Some(slope()), -1.0);
+        assert!(downhill.speed_max_mul > 1.0);
+        assert!(downhill.brake_mul < 1.0);
+        assert!(downhill.accel_add < 0.0);
+        assert_eq!(uphill.accel_add, downhill.accel_add);
+    }
+
+    #[test]
+    fn steep_slopes_are_clamped() {
+        let params = SlopeParams {
+            uphill_speed_loss: 4.0,
+            downhill_brake_loss: 4.0,
+            ..enabled()
+        };
+        assert_eq!(params.modifiers(Some(slope()), 1.0).speed_max_mul, 0.0);
+        assert_eq!(params.modifiers(Some(slope()), -1.0).brake_mul, 0.0);
+    }
+}
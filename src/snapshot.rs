@@ -0,0 +1,661 @@
+//! Captures and restores the kinetic energies of a fighter, so that save states and replay tools can reproduce
+//! exact momentum.
+//!
+//! Every energy is stored as the raw bytes of its struct after the vtable, which keeps the fields we haven't named yet
+//! and the padding between them. Only the parts of the structs that are declared here are captured, anything past
+//! the last declared field is left as it is on restore. The JSON format also names the base fields of each energy,
+//! which are applied over the bytes when it is read so that they can be edited by hand.
+//!
+//! The state that energy_id keeps for the fighter outside of the energies (see fighter_state) is captured with them,
+//! since the glide, tether, decay and root motion would otherwise carry on from whatever the fighter was doing before
+//! the restore
+
+use std::{convert::TryFrom, fmt};
+
+use smash::app::{lua_bind::*, BattleObjectModuleAccessor};
+use smash::lib::lua_const::*;
+
+use super::{
+    control::FighterKineticEnergyControl,
+    energy::{KineticEnergy, PaddedVec2},
+    fighter_state::{self, FighterState},
+    glide::GlideState,
+    json::{self, Value},
+    motion::FighterKineticEnergyMotion,
+    root_motion::{AxisModifier, RootMotionModifier},
+    stop::FighterKineticEnergyStop
+};
+
+/// The first bytes of the binary format
+pub const MAGIC: [u8; 4] = *b"EIDS";
+
+/// Bumped whenever the binary or JSON format, or the layout of one of the energies, changes
+pub const VERSION: u16 = 2;
+
+/// Which struct an energy's bytes belong to
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u8)]
+pub enum EnergyKind {
+    Base,
+    Control,
+    Motion,
+    Stop,
+}
+
+impl EnergyKind {
+    const ALL: [EnergyKind; 4] = [EnergyKind::Base, EnergyKind::Control, EnergyKind::Motion, EnergyKind::Stop];
+
+    fn from_u8(kind: u8) -> Option<Self> {
+        Self::ALL.iter().copied().find(|other| *other as u8 == kind)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            EnergyKind::Base => "base",
+            EnergyKind::Control => "control",
+            EnergyKind::Motion => "motion",
+            EnergyKind::Stop => "stop"
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|kind| kind.name() == name)
+    }
+
+    /// The number of bytes captured for this kind of energy
+    pub fn data_len(self) -> usize {
+        let size = match self {
+            EnergyKind::Base => std::mem::size_of::<KineticEnergy>(),
+            EnergyKind::Control => std::mem::size_of::<FighterKineticEnergyControl>(),
+            EnergyKind::Motion => std::mem::size_of::<FighterKineticEnergyMotion>(),
+            EnergyKind::Stop => std::mem::size_of::<FighterKineticEnergyStop>()
+        };
+        size - VTABLE_SIZE
+    }
+}
+
+const VTABLE_SIZE: usize = std::mem::size_of::<usize>();
+
+/// The structs that can be captured
+pub trait SnapshotEnergy {
+    const KIND: EnergyKind;
+}
+
+impl SnapshotEnergy for KineticEnergy {
+    const KIND: EnergyKind = EnergyKind::Base;
+}
+
+impl SnapshotEnergy for FighterKineticEnergyControl {
+    const KIND: EnergyKind = EnergyKind::Control;
+}
+
+impl SnapshotEnergy for FighterKineticEnergyMotion {
+    const KIND: EnergyKind = EnergyKind::Motion;
+}
+
+impl SnapshotEnergy for FighterKineticEnergyStop {
+    const KIND: EnergyKind = EnergyKind::Stop;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnapshotError {
+    /// The data doesn't start with `MAGIC`
+    BadMagic,
+    /// The data was written by another version of the format
+    Version(u16),
+    /// The data ended in the middle of an energy, or kept going after the last one
+    Truncated,
+    UnknownKind(u8),
+    /// An energy's data doesn't match the size of its struct
+    Length { kind: EnergyKind, expected: usize, found: usize },
+    /// The fighter doesn't have the energy, or it is a different kind of energy than the one that was captured
+    Mismatch { id: i32 },
+    Json(String),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SnapshotError::BadMagic => write!(f, "not an energy snapshot"),
+            SnapshotError::Version(version) => write!(f, "snapshot version {} is not supported (expected {})", version, VERSION),
+            SnapshotError::Truncated => write!(f, "snapshot is truncated"),
+            SnapshotError::UnknownKind(kind) => write!(f, "unknown energy kind {}", kind),
+            SnapshotError::Length { kind, expected, found } => write!(f, "{} energy has {} bytes, expected {}", kind.name(), found, expected),
+            SnapshotError::Mismatch { id } => write!(f, "energy {} can't be restored on this fighter", id),
+            SnapshotError::Json(message) => write!(f, "invalid snapshot json: {}", message)
+        }
+    }
+}
+
+/// The captured bytes of one energy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnergySnapshot {
+    /// The `FIGHTER_KINETIC_ENERGY_ID_*` the energy was captured from
+    pub id: i32,
+    pub kind: EnergyKind,
+    pub data: Vec<u8>,
+}
+
+impl EnergySnapshot {
+    pub fn capture<T: SnapshotEnergy>(id: i32, energy: &T) -> Self {
+        let data = unsafe {
+            let start = (energy as *const T as *const u8).add(VTABLE_SIZE);
+            std::slice::from_raw_parts(start, T::KIND.data_len()).to_vec()
+        };
+        Self {
+            id,
+            kind: T::KIND,
+            data
+        }
+    }
+
+    /// Writes the captured bytes back, the vtable is left alone
+    pub fn restore<T: SnapshotEnergy>(&self, energy: &mut T) -> Result<(), SnapshotError> {
+        if self.kind != T::KIND {
+            return Err(SnapshotError::Mismatch { id: self.id });
+        }
+        self.check_len()?;
+        unsafe {
+            let start = (energy as *mut T as *mut u8).add(VTABLE_SIZE);
+            std::ptr::copy_nonoverlapping(self.data.as_ptr(), start, self.data.len());
+        }
+        Ok(())
+    }
+
+    fn check_len(&self) -> Result<(), SnapshotError> {
+        let expected = self.kind.data_len();
+        if self.data.len() != expected {
+            return Err(SnapshotError::Length { kind: self.kind, expected, found: self.data.len() });
+        }
+        Ok(())
+    }
+
+    /// Reads a float of the energy
+    /// # Arguments
+    /// * `offset` - The offset of the float in the energy's struct, including the vtable
+    fn field(&self, offset: usize) -> Option<f32> {
+        let offset = offset - VTABLE_SIZE;
+        let bytes = self.data.get(offset..offset + 4)?;
+        Some(f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// Writes a float of the energy
+    /// # Arguments
+    /// * `offset` - The offset of the float in the energy's struct, including the vtable
+    /// * `value` - The value to write
+    fn set_field(&mut self, offset: usize, value: f32) {
+        let offset = offset - VTABLE_SIZE;
+        if let Some(bytes) = self.data.get_mut(offset..offset + 4) {
+            bytes.copy_from_slice(&value.to_le_bytes());
+        }
+    }
+}
+
+/// The base fields of every energy that are named in JSON, with their offsets in `KineticEnergy`
+const BASE_FIELDS: [(&str, usize); 5] = [
+    ("speed", 0x10),
+    ("accel", 0x40),
+    ("speed_max", 0x50),
+    ("speed_brake", 0x60),
+    ("speed_limit", 0x70),
+];
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], SnapshotError> {
+        let taken = self.bytes.get(self.offset..self.offset + len).ok_or(SnapshotError::Truncated)?;
+        self.offset += len;
+        Ok(taken)
+    }
+}
+
+/// The number of bytes the fighter state is written in. Every part of it is always written, with a flag byte in front
+/// that says which of them are in use
+const STATE_LEN: usize = 1 + 8 + 8 + 8 + 4 + 32;
+
+const GLIDE: u8 = 1 << 0;
+const TETHER_CAUGHT_SPEED: u8 = 1 << 1;
+const DECAY: u8 = 1 << 2;
+const ROOT_MOTION: u8 = 1 << 3;
+
+fn state_to_bytes(state: &FighterState) -> Vec<u8> {
+    let mut flags = 0;
+    for &(is_some, flag) in [
+        (state.glide.is_some(), GLIDE),
+        (state.tether_caught_speed.is_some(), TETHER_CAUGHT_SPEED),
+        (state.decay.is_some(), DECAY),
+        (state.root_motion.is_some(), ROOT_MOTION),
+    ].iter() {
+        if is_some {
+            flags |= flag;
+        }
+    }
+
+    let glide = state.glide.map_or([0.0; 2], |glide| [glide.angle, glide.speed]);
+    let tether_caught_speed = state.tether_caught_speed.map_or([0.0; 2], |speed| [speed.x, speed.y]);
+    let decay = state.decay.map_or([0.0; 2], |decay| [decay.x, decay.y]);
+    let (status_kind, modifier) = state.root_motion.unwrap_or((0, RootMotionModifier::IDENTITY));
+    let modifier = [modifier.x, modifier.y].iter().flat_map(|axis| vec![axis.mul, axis.offset, axis.min, axis.max]).collect::<Vec<_>>();
+
+    let mut bytes = vec![flags];
+    for value in glide.iter().chain(tether_caught_speed.iter()).chain(decay.iter()) {
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    bytes.extend_from_slice(&status_kind.to_le_bytes());
+    for value in modifier.iter() {
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    bytes
+}
+
+fn state_from_bytes(bytes: &[u8]) -> Result<FighterState, SnapshotError> {
+    if bytes.len() != STATE_LEN {
+        return Err(SnapshotError::Truncated);
+    }
+
+    let flags = bytes[0];
+    let word = |index: usize| {
+        let start = 1 + index * 4;
+        [bytes[start], bytes[start + 1], bytes[start + 2], bytes[start + 3]]
+    };
+    let float = |index: usize| f32::from_le_bytes(word(index));
+    let axis = |index: usize| AxisModifier {
+        mul: float(index),
+        offset: float(index + 1),
+        min: float(index + 2),
+        max: float(index + 3),
+    };
+    let is_set = |flag: u8| flags & flag != 0;
+
+    Ok(FighterState {
+        glide: Some(GlideState { angle: float(0), speed: float(1) }).filter(|_| is_set(GLIDE)),
+        tether_caught_speed: Some(PaddedVec2::new(float(2), float(3))).filter(|_| is_set(TETHER_CAUGHT_SPEED)),
+        decay: Some(PaddedVec2::new(float(4), float(5))).filter(|_| is_set(DECAY)),
+        root_motion: Some((i32::from_le_bytes(word(6)), RootMotionModifier { x: axis(7), y: axis(11) })).filter(|_| is_set(ROOT_MOTION)),
+    })
+}
+
+/// The captured energies of one fighter, along with the state that is kept for it outside of them
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Snapshot {
+    pub energies: Vec<EnergySnapshot>,
+    pub state: FighterState,
+}
+
+impl Snapshot {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&MAGIC);
+        bytes.extend_from_slice(&VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(self.energies.len() as u16).to_le_bytes());
+        for energy in self.energies.iter() {
+            bytes.extend_from_slice(&energy.id.to_le_bytes());
+            bytes.push(energy.kind as u8);
+            bytes.extend_from_slice(&(energy.data.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(&energy.data);
+        }
+        bytes.extend_from_slice(&state_to_bytes(&self.state));
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SnapshotError> {
+        let mut reader = Reader { bytes, offset: 0 };
+
+        if reader.take(4)? != MAGIC {
+            return Err(SnapshotError::BadMagic);
+        }
+        let version = reader.take(2)?;
+        let version = u16::from_le_bytes([version[0], version[1]]);
+        if version != VERSION {
+            return Err(SnapshotError::Version(version));
+        }
+        let count = reader.take(2)?;
+        let count = u16::from_le_bytes([count[0], count[1]]);
+
+        let mut energies = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let id = reader.take(4)?;
+            let id = i32::from_le_bytes([id[0], id[1], id[2], id[3]]);
+            let kind = reader.take(1)?[0];
+            let kind = EnergyKind::from_u8(kind).ok_or(SnapshotError::UnknownKind(kind))?;
+            let len = reader.take(4)?;
+            let len = u32::from_le_bytes([len[0], len[1], len[2], len[3]]) as usize;
+            let energy = EnergySnapshot { id, kind, data: reader.take(len)?.to_vec() };
+            energy.check_len()?;
+            energies.push(energy);
+        }
+        let state = state_from_bytes(reader.take(STATE_LEN)?)?;
+
+        if reader.offset != bytes.len() {
+            return Err(SnapshotError::Truncated);
+        }
+        Ok(Self { energies, state })
+    }
+
+    /// Writes the snapshot as JSON. `data` holds the bytes in hex, and the base fields of the energy are named next to
+    /// it. When the JSON is read, the named fields are written over `data`. The fighter state is written in hex as well,
+    /// since its unused bounds are infinite, which JSON can't hold
+    pub fn to_json(&self) -> String {
+        let energies = self.energies.iter().map(|energy| {
+            let mut fields = vec![
+                ("id", Value::from(energy.id)),
+                ("kind", Value::from(energy.kind.name())),
+                ("data", Value::String(to_hex(&energy.data))),
+            ];
+            for &(name, offset) in BASE_FIELDS.iter() {
+                if let (Some(x), Some(y)) = (energy.field(offset), energy.field(offset + 4)) {
+                    fields.push((name, Value::Array(vec![Value::from(x), Value::from(y)])));
+                }
+            }
+            Value::object(fields)
+        }).collect();
+
+        Value::object(vec![
+            ("version", Value::from(VERSION as u32)),
+            ("energies", Value::Array(energies)),
+            ("state", Value::String(to_hex(&state_to_bytes(&self.state)))),
+        ]).to_string()
+    }
+
+    /// Reads a snapshot from JSON, a named field that is missing keeps the value in `data`, and so does a value of null
+    /// (which is how NaN and infinities are written)
+    pub fn from_json(text: &str) -> Result<Self, SnapshotError> {
+        let invalid = |message: &str| SnapshotError::Json(message.to_owned());
+
+        let document = json::parse(text).map_err(|error| SnapshotError::Json(error.to_string()))?;
+        let version = document.get("version").and_then(Value::as_i64).ok_or_else(|| invalid("missing version"))?;
+        let version = u16::try_from(version).map_err(|_| invalid("version is out of range"))?;
+        if version != VERSION {
+            return Err(SnapshotError::Version(version));
+        }
+
+        let energies = document.get("energies").and_then(Value::as_array).ok_or_else(|| invalid("missing energies"))?;
+        let energies = energies.iter().map(|energy| {
+            let id = energy.get("id").and_then(Value::as_i64).ok_or_else(|| invalid("missing energy id"))?;
+            let kind = energy.get("kind").and_then(Value::as_str).ok_or_else(|| invalid("missing energy kind"))?;
+            let kind = EnergyKind::from_name(kind).ok_or_else(|| invalid("unknown energy kind"))?;
+            let data = energy.get("data").and_then(Value::as_str).ok_or_else(|| invalid("missing energy data"))?;
+            let data = from_hex(data).ok_or_else(|| invalid("energy data isn't hex"))?;
+
+            let mut snapshot = EnergySnapshot { id: id as i32, kind, data };
+            snapshot.check_len()?;
+
+            for &(name, offset) in BASE_FIELDS.iter() {
+                let values = match energy.get(name) {
+                    Some(value) => value.as_array().filter(|values| values.len() == 2).ok_or_else(|| invalid("energy field isn't a pair"))?,
+                    None => continue
+                };
+                for (index, value) in values.iter().enumerate() {
+                    if *value != Value::Null {
+                        let value = value.as_f32().ok_or_else(|| invalid("energy field isn't a number"))?;
+                        snapshot.set_field(offset + index * 4, value);
+                    }
+                }
+            }
+            Ok(snapshot)
+        }).collect::<Result<Vec<_>, _>>()?;
+
+        let state = document.get("state").and_then(Value::as_str).ok_or_else(|| invalid("missing fighter state"))?;
+        let state = from_hex(state).ok_or_else(|| invalid("fighter state isn't hex"))?;
+        let state = state_from_bytes(&state).map_err(|_| invalid("fighter state has the wrong length"))?;
+
+        Ok(Self { energies, state })
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn from_hex(text: &str) -> Option<Vec<u8>> {
+    if text.len() % 2 != 0 {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|index| text.get(index..index + 2).and_then(|byte| u8::from_str_radix(byte, 16).ok()))
+        .collect()
+}
+
+/// The energies that are captured, with the struct the game uses for each of them
+fn fighter_energies() -> [(i32, EnergyKind); 6] {
+    [
+        (*FIGHTER_KINETIC_ENERGY_ID_CONTROL, EnergyKind::Control),
+        (*FIGHTER_KINETIC_ENERGY_ID_MOTION, EnergyKind::Motion),
+        (*FIGHTER_KINETIC_ENERGY_ID_STOP, EnergyKind::Stop),
+        (*FIGHTER_KINETIC_ENERGY_ID_GRAVITY, EnergyKind::Base),
+        (*FIGHTER_KINETIC_ENERGY_ID_DAMAGE, EnergyKind::Base),
+        (*FIGHTER_KINETIC_ENERGY_ID_JOSTLE, EnergyKind::Base),
+    ]
+}
+
+unsafe fn energy_ptr(boma: &mut BattleObjectModuleAccessor, id: i32) -> *mut KineticEnergy {
+    KineticModule::get_energy(boma, id) as *mut KineticEnergy
+}
+
+/// Captures every energy of a fighter
+pub unsafe fn capture(boma: &mut BattleObjectModuleAccessor) -> Snapshot {
+    let energies = fighter_energies().iter().filter_map(|(id, kind)| {
+        let energy = energy_ptr(boma, *id);
+        if energy.is_null() {
+            return None;
+        }
+        Some(match kind {
+            EnergyKind::Base => EnergySnapshot::capture(*id, &*energy),
+            EnergyKind::Control => EnergySnapshot::capture(*id, &*(energy as *const FighterKineticEnergyControl)),
+            EnergyKind::Motion => EnergySnapshot::capture(*id, &*(energy as *const FighterKineticEnergyMotion)),
+            EnergyKind::Stop => EnergySnapshot::capture(*id, &*(energy as *const FighterKineticEnergyStop))
+        })
+    }).collect();
+
+    Snapshot { energies, state: fighter_state::get(boma) }
+}
+
+/// Restores every energy in the snapshot and the fighter's state, nothing is written unless all of the energies can be
+/// restored
+pub unsafe fn restore(boma: &mut BattleObjectModuleAccessor, snapshot: &Snapshot) -> Result<(), SnapshotError> {
+    let energies = fighter_energies();
+    for energy in snapshot.energies.iter() {
+        let matches = energies.iter().any(|(id, kind)| *id == energy.id && *kind == energy.kind);
+        if !matches || energy_ptr(boma, energy.id).is_null() {
+            return Err(SnapshotError::Mismatch { id: energy.id });
+        }
+        energy.check_len()?;
+    }
+
+    for energy in snapshot.energies.iter() {
+        let ptr = energy_ptr(boma, energy.id);
+        match energy.kind {
+            EnergyKind::Base => energy.restore(&mut *ptr)?,
+            EnergyKind::Control => energy.restore(&mut *(ptr as *mut FighterKineticEnergyControl))?,
+            EnergyKind::Motion => energy.restore(&mut *(ptr as *mut FighterKineticEnergyMotion))?,
+            EnergyKind::Stop => energy.restore(&mut *(ptr as *mut FighterKineticEnergyStop))?
+        }
+    }
+    fighter_state::set(boma, snapshot.state);
+    Ok(())
+}
+
+/// Captures every energy of a fighter in the binary format
+#[no_mangle]
+pub extern "Rust" fn energy_id_snapshot(boma: &mut BattleObjectModuleAccessor) -> Vec<u8> {
+    unsafe {
+        capture(boma).to_bytes()
+    }
+}
+
+/// Restores the energies of a fighter from the binary format
+#[no_mangle]
+pub extern "Rust" fn energy_id_restore(boma: &mut BattleObjectModuleAccessor, bytes: &[u8]) -> Result<(), SnapshotError> {
+    unsafe {
+        restore(boma, &Snapshot::from_bytes(bytes)?)
+    }
+}
+
+/// Captures every energy of a fighter as JSON
+#[no_mangle]
+pub extern "Rust" fn energy_id_snapshot_json(boma: &mut BattleObjectModuleAccessor) -> String {
+    unsafe {
+        capture(boma).to_json()
+    }
+}
+
+/// Restores the energies of a fighter from JSON
+#[no_mangle]
+pub extern "Rust" fn energy_id_restore_json(boma: &mut BattleObjectModuleAccessor, text: &str) -> Result<(), SnapshotError> {
+    unsafe {
+        restore(boma, &Snapshot::from_json(text)?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        control::{test::control_energy, EnergyControllerResetType},
+        fighter::stub::*
+    };
+
+    const CONTROL: i32 = 1;
+    const GRAVITY: i32 = 2;
+
+    fn snapshot() -> Snapshot {
+        let mut control = control_energy(EnergyControllerResetType::Dash);
+        control.speed = PaddedVec2::new(1.5, -0.25);
+        control.accel_mul_x = 0.1;
+        control._x9c = 3.0;
+        control.unk = [1, 2, 3, 4];
+
+        let mut gravity = stub_energy();
+        gravity.speed = PaddedVec2::new(0.0, -2.0);
+
+        Snapshot {
+            energies: vec![
+                EnergySnapshot::capture(CONTROL, &control),
+                EnergySnapshot::capture(GRAVITY, &gravity),
+            ],
+            state: FighterState {
+                glide: Some(GlideState { angle: 0.5, speed: 1.25 }),
+                decay: Some(PaddedVec2::new(0.02, 0.0)),
+                root_motion: Some((3, RootMotionModifier {
+                    x: AxisModifier { mul: 2.0, ..AxisModifier::IDENTITY },
+                    ..RootMotionModifier::IDENTITY
+                })),
+                ..FighterState::default()
+            }
+        }
+    }
+
+    #[test]
+    fn capture_and_restore() {
+        let snapshot = snapshot();
+        let mut control = control_energy(EnergyControllerResetType::FallAdjust);
+        snapshot.energies[0].restore(&mut control).unwrap();
+
+        assert_eq!(control.speed, PaddedVec2::new(1.5, -0.25));
+        assert_eq!(control.energy_reset_type, EnergyControllerResetType::Dash as u32);
+        assert_eq!(control.accel_mul_x, 0.1);
+        assert_eq!(control._x9c, 3.0);
+        assert_eq!(control.unk, [1, 2, 3, 4]);
+
+        // the vtable has to stay the one of the energy that was restored into
+        assert!(std::ptr::eq(control.vtable, stub_energy().vtable));
+
+        let mut gravity = stub_energy();
+        assert_eq!(snapshot.energies[0].restore(&mut gravity), Err(SnapshotError::Mismatch { id: CONTROL }));
+    }
+
+    #[test]
+    fn binary_round_trip() {
+        let snapshot = snapshot();
+        let bytes = snapshot.to_bytes();
+        assert_eq!(Snapshot::from_bytes(&bytes), Ok(snapshot));
+
+        assert_eq!(Snapshot::from_bytes(&bytes[..bytes.len() - 1]), Err(SnapshotError::Truncated));
+        assert_eq!(Snapshot::from_bytes(b"NOPE"), Err(SnapshotError::BadMagic));
+
+        let mut future = bytes.clone();
+        future[4] = 3;
+        assert_eq!(Snapshot::from_bytes(&future), Err(SnapshotError::Version(3)));
+    }
+
+    #[test]
+    fn fighter_state_round_trips() {
+        let empty = FighterState::default();
+        assert_eq!(state_from_bytes(&state_to_bytes(&empty)), Ok(empty));
+
+        let state = FighterState {
+            tether_caught_speed: Some(PaddedVec2::new(-1.0, 0.5)),
+            ..snapshot().state
+        };
+        let bytes = state_to_bytes(&state);
+        assert_eq!(bytes.len(), STATE_LEN);
+        assert_eq!(state_from_bytes(&bytes), Ok(state));
+        assert_eq!(state_from_bytes(&bytes[1..]), Err(SnapshotError::Truncated));
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let snapshot = snapshot();
+        let text = snapshot.to_json();
+        assert_eq!(Snapshot::from_json(&text), Ok(snapshot));
+
+        let document = json::parse(&text).unwrap();
+        let control = &document.get("energies").unwrap().as_array().unwrap()[0];
+        assert_eq!(control.get("kind").and_then(Value::as_str), Some("control"));
+        assert_eq!(control.get("speed").unwrap().as_array().unwrap()[0].as_f32(), Some(1.5));
+    }
+
+    #[test]
+    fn json_versions_are_checked() {
+        let text = snapshot().to_json();
+        let with_version = |version: &str| text.replacen(&format!("\"version\":{}", VERSION), &format!("\"version\":{}", version), 1);
+        assert_ne!(with_version("3"), text);
+        assert_eq!(Snapshot::from_json(&with_version("3")), Err(SnapshotError::Version(3)));
+        // would be read as the current version if it was truncated to 16 bits
+        let wrapped = (VERSION as u32 + 0x10000).to_string();
+        assert!(matches!(Snapshot::from_json(&with_version(&wrapped)), Err(SnapshotError::Json(_))));
+        assert!(matches!(Snapshot::from_json(&with_version("-1")), Err(SnapshotError::Json(_))));
+    }
+
+    #[test]
+    fn json_named_fields_are_restored() {
+        let snapshot = snapshot();
+        let mut document = json::parse(&snapshot.to_json()).unwrap();
+        let set = |document: &mut Value, name: &str, value: Value| {
+            if let Value::Object(fields) = document {
+                if let Some((_, Value::Array(energies))) = fields.iter_mut().find(|(field, _)| field == "energies") {
+                    if let Value::Object(fields) = &mut energies[0] {
+                        fields.iter_mut().find(|(field, _)| field == name).unwrap().1 = value;
+                    }
+                }
+            }
+        };
+
+        // an edited field wins over the bytes, a null keeps them
+        set(&mut document, "speed", Value::Array(vec![Value::from(3.0f32), Value::Null]));
+        let restored = Snapshot::from_json(&document.to_string()).unwrap();
+        let mut control = control_energy(EnergyControllerResetType::FallAdjust);
+        restored.energies[0].restore(&mut control).unwrap();
+        assert_eq!(control.speed, PaddedVec2::new(3.0, -0.25));
+        assert_eq!(control.accel_mul_x, 0.1);
+
+        set(&mut document, "speed", Value::from(3.0f32));
+        assert!(matches!(Snapshot::from_json(&document.to_string()), Err(SnapshotError::Json(_))));
+        set(&mut document, "speed", Value::Array(vec![Value::from(true), Value::Null]));
+        assert!(matches!(Snapshot::from_json(&document.to_string()), Err(SnapshotError::Json(_))));
+    }
+
+    #[test]
+    fn wrong_lengths_are_rejected() {
+        let mut snapshot = snapshot();
+        snapshot.energies[1].data.pop();
+        let expected = Err(SnapshotError::Length { kind: EnergyKind::Base, expected: EnergyKind::Base.data_len(), found: EnergyKind::Base.data_len() - 1 });
+        assert_eq!(Snapshot::from_bytes(&snapshot.to_bytes()), expected);
+        assert_eq!(Snapshot::from_json(&snapshot.to_json()), expected);
+    }
+}
@@ -2,32 +2,269 @@ use std::ops::{DerefMut, Deref};
 
 use super::{
     *,
-    energy::*
+    battle_object::{BattleObjectRef, DamageLog},
+    energy::*,
+    fighter::FighterAccessor,
+    log::{Context, Level, Module},
+    modules::ModuleAccessorExt,
+    fighter_state
 };
 
 #[repr(C)]
 pub struct FighterKineticEnergyStop {
     parent: super::energy::KineticEnergy,
-    padding: u64,
-    damage_target_speed: PaddedVec2,
-    reset_type: EnergyStopResetType,
-    elapsed_hitstop_frames: f32,
-    hitstop_frames: f32,
-    _xAC: f32,
-    _xB0: f32,
-    should_sync_damage_speed: bool,
-    needs_to_sync_damage_speed: bool,
-    should_start_interpolation: bool,
-    interpolation_frames_remaining: u8,
-    _xB8: u8,
-    is_target_pos: bool,
-    _xBA: bool,
-    _xBB: bool,
-    _xBC: u32,
-    _xC0: PaddedVec2
+    pub(crate) padding: u64,
+    pub(crate) damage_target_speed: PaddedVec2,
+    pub(crate) reset_type: EnergyStopResetType,
+    pub(crate) elapsed_hitstop_frames: f32,
+    pub(crate) hitstop_frames: f32,
+    pub(crate) _xAC: f32,
+    pub(crate) _xB0: f32,
+    pub(crate) should_sync_damage_speed: bool,
+    pub(crate) needs_to_sync_damage_speed: bool,
+    pub(crate) should_start_interpolation: bool,
+    pub(crate) interpolation_frames_remaining: u8,
+    pub(crate) _xB8: u8,
+    pub(crate) is_target_pos: bool,
+    pub(crate) _xBA: bool,
+    pub(crate) _xBB: bool,
+    pub(crate) _xBC: u32,
+    pub(crate) _xC0: PaddedVec2
     // ...
 }
 
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+    use crate::fighter::stub::*;
+
+    pub(crate) fn stop_energy(reset_type: EnergyStopResetType) -> FighterKineticEnergyStop {
+        FighterKineticEnergyStop {
+            parent: stub_energy(),
+            padding: 0,
+            damage_target_speed: PaddedVec2::zeros(),
+            reset_type,
+            elapsed_hitstop_frames: 0.0,
+            hitstop_frames: 0.0,
+            _xAC: 0.0,
+            _xB0: 0.0,
+            should_sync_damage_speed: false,
+            needs_to_sync_damage_speed: false,
+            should_start_interpolation: false,
+            interpolation_frames_remaining: 0,
+            _xB8: 0,
+            is_target_pos: false,
+            _xBA: false,
+            _xBB: false,
+            _xBC: 0,
+            _xC0: PaddedVec2::zeros()
+        }
+    }
+
+    #[test]
+    fn capture_holds_in_place_by_default() {
+        let mut energy = stop_energy(EnergyStopResetType::Capture);
+        let mut fighter = StubFighter::new();
+        fighter.capture_offset = Some(PaddedVec2::new(10.0, 0.0));
+        energy.speed = PaddedVec2::new(1.0, 1.0);
+        unsafe {
+            update_stop_energy(&mut energy, &mut fighter);
+        }
+        assert_eq!(energy.speed, PaddedVec2::zeros());
+    }
+
+    #[test]
+    fn capture_pulls_towards_hold_position() {
+        let mut config = crate::config::EnergyConfig::DEFAULT;
+        config.grab.pull_in_rate = 0.2;
+        let pull_in_speed_max = config.grab.pull_in_speed_max;
+        crate::config::with(config, || {
+            let mut energy = stop_energy(EnergyStopResetType::Capture);
+            let mut fighter = StubFighter::new();
+            fighter.capture_offset = Some(PaddedVec2::new(10.0, 0.0));
+            unsafe {
+                update_stop_energy(&mut energy, &mut fighter);
+            }
+            assert!(energy.speed.x > 0.0);
+            assert!(energy.speed.x <= pull_in_speed_max);
+            assert_eq!(energy.speed.y, 0.0);
+
+            fighter.capture_offset = None;
+            unsafe {
+                update_stop_energy(&mut energy, &mut fighter);
+            }
+            assert_eq!(energy.speed.x, 0.0);
+        });
+    }
+
+    #[test]
+    fn catch_cut_keeps_speed_by_default() {
+        let mut energy = stop_energy(EnergyStopResetType::CatchCut);
+        let mut fighter = StubFighter::new();
+        fighter.lr = -1.0;
+        unsafe {
+            setup_stop_energy(&mut energy, EnergyStopResetType::CatchCut, &PaddedVec2::new(0.5, 0.0), 0, &mut fighter);
+        }
+        assert_eq!(energy.speed.x, 0.5);
+        assert!(fighter.was_requested("common", "capture_cut_brake_mul"));
+    }
+
+    #[test]
+    fn catch_cut_pushes_backwards() {
+        let mut config = crate::config::EnergyConfig::DEFAULT;
+        config.grab.release_speed = 0.6;
+        crate::config::with(config, || {
+            let mut energy = stop_energy(EnergyStopResetType::CatchCut);
+            let mut fighter = StubFighter::new();
+            fighter.lr = -1.0;
+            unsafe {
+                setup_stop_energy(&mut energy, EnergyStopResetType::CatchCut, &PaddedVec2::zeros(), 0, &mut fighter);
+            }
+            assert_eq!(energy.speed.x, 0.6);
+        });
+    }
+
+    #[test]
+    fn catch_dash_uses_game_brake_without_distance() {
+        let mut energy = stop_energy(EnergyStopResetType::CatchDash);
+        let mut fighter = StubFighter::new()
+            .with_param("ground_brake", "", 0.1)
+            .with_param("common", "catch_dash_brake_mul", 0.5);
+        unsafe {
+            setup_stop_energy(&mut energy, EnergyStopResetType::CatchDash, &PaddedVec2::new(2.0, 0.0), 0, &mut fighter);
+        }
+        assert_eq!(energy.speed_brake.x, 0.05);
+
+        let mut frames = 0;
+        while energy.speed.x > 0.0 && frames < 100 {
+            unsafe {
+                update_stop_energy(&mut energy, &mut fighter);
+            }
+            frames += 1;
+        }
+        assert_eq!(energy.speed.x, 0.0);
+    }
+
+    #[test]
+    fn glide_is_processed_by_default() {
+        let mut fighter = StubFighter::new()
+            .with_param("ground_brake", "", 0.25);
+        fighter.stick = PaddedVec2::new(0.0, -1.0);
+
+        let mut energy = stop_energy(EnergyStopResetType::GlidStart);
+        unsafe {
+            setup_stop_energy(&mut energy, EnergyStopResetType::GlidStart, &PaddedVec2::new(1.0, 0.0), 0, &mut fighter);
+        }
+        assert_eq!(energy.speed, PaddedVec2::new(1.0, 0.0));
+        assert_eq!(energy.speed_max, PaddedVec2::zeros());
+        unsafe {
+            update_stop_energy(&mut energy, &mut fighter);
+        }
+        assert!(fighter_state::get(&fighter).glide.is_none());
+
+        let mut energy = stop_energy(EnergyStopResetType::GlidLanding);
+        unsafe {
+            setup_stop_energy(&mut energy, EnergyStopResetType::GlidLanding, &PaddedVec2::new(1.0, 0.0), 0, &mut fighter);
+        }
+        assert_eq!(energy.speed.x, 1.0);
+        assert_eq!(energy.speed_brake.x, 0.25);
+    }
+
+    #[test]
+    fn glide_pitches_when_enabled() {
+        let mut config = crate::config::EnergyConfig::DEFAULT;
+        config.glide.enabled = true;
+        crate::config::with(config, || {
+            let mut fighter = StubFighter::new();
+            fighter.situation_kind = *SITUATION_KIND_AIR;
+            fighter.stick = PaddedVec2::new(0.0, -1.0);
+
+            let mut energy = stop_energy(EnergyStopResetType::GlidStart);
+            unsafe {
+                setup_stop_energy(&mut energy, EnergyStopResetType::GlidStart, &PaddedVec2::new(1.0, 0.0), 0, &mut fighter);
+            }
+            let start = fighter_state::get(&fighter).glide.unwrap();
+            unsafe {
+                update_stop_energy(&mut energy, &mut fighter);
+            }
+            let glide = fighter_state::get(&fighter).glide.unwrap();
+            assert!(glide.angle < start.angle);
+            assert!(energy.speed.y < 0.0);
+
+            // the glide ends with the reset type
+            unsafe {
+                setup_stop_energy(&mut energy, EnergyStopResetType::Air, &PaddedVec2::zeros(), 0, &mut fighter);
+            }
+            assert!(fighter_state::get(&fighter).glide.is_none());
+        });
+    }
+
+    #[test]
+    fn tether_brakes_by_default() {
+        let mut energy = stop_energy(EnergyStopResetType::AirLassoHang);
+        let mut fighter = StubFighter::new()
+            .with_param("air_brake_x", "", 0.1)
+            .with_param("air_brake_y", "", 0.2);
+        fighter.situation_kind = *SITUATION_KIND_AIR;
+        unsafe {
+            setup_stop_energy(&mut energy, EnergyStopResetType::AirLassoHang, &PaddedVec2::new(0.5, 0.0), 0, &mut fighter);
+        }
+        assert_eq!(energy.damage_target_speed, PaddedVec2::new(0.5, 0.0));
+        assert_eq!(energy.speed_brake, PaddedVec2::new(0.1, 0.2));
+        assert!(fighter_state::get(&fighter).tether_caught_speed.is_none());
+    }
+
+    #[test]
+    fn tether_swings_when_enabled() {
+        let mut config = crate::config::EnergyConfig::DEFAULT;
+        config.tether.enabled = true;
+        crate::config::with(config, || {
+            let mut energy = stop_energy(EnergyStopResetType::AirLassoHang);
+            let mut fighter = StubFighter::new();
+            fighter.situation_kind = *SITUATION_KIND_AIR;
+            fighter.tether_anchor = Some(PaddedVec2::new(0.0, 10.0));
+            unsafe {
+                setup_stop_energy(&mut energy, EnergyStopResetType::AirLassoHang, &PaddedVec2::new(0.5, 0.0), 0, &mut fighter);
+            }
+            assert_eq!(fighter_state::get(&fighter).tether_caught_speed, Some(PaddedVec2::new(0.5, 0.0)));
+            assert_eq!(energy.speed_brake, PaddedVec2::zeros());
+
+            // hanging below the anchor, the tether stops the fall
+            unsafe {
+                update_stop_energy(&mut energy, &mut fighter);
+            }
+            assert!(energy.speed.y.abs() < 1.0e-5);
+            assert!(energy.speed.x < 0.5);
+
+            // reeling in keeps the hang's state and pulls towards the anchor
+            unsafe {
+                setup_stop_energy(&mut energy, EnergyStopResetType::AirLassoRewind, &PaddedVec2::zeros(), 0, &mut fighter);
+                update_stop_energy(&mut energy, &mut fighter);
+            }
+            assert!(fighter_state::get(&fighter).tether_caught_speed.is_some());
+            assert!(energy.speed.y > 0.0);
+
+            unsafe {
+                setup_stop_energy(&mut energy, EnergyStopResetType::Air, &PaddedVec2::zeros(), 0, &mut fighter);
+            }
+            assert!(fighter_state::get(&fighter).tether_caught_speed.is_none());
+        });
+    }
+
+    #[test]
+    fn capture_beetle_follows_link() {
+        let mut energy = stop_energy(EnergyStopResetType::CaptureBeetle);
+        let mut fighter = StubFighter::new();
+        fighter.link_speed = Some(PaddedVec2::new(0.5, 1.5));
+        unsafe {
+            update_stop_energy(&mut energy, &mut fighter);
+        }
+        let follow_mul = crate::config::get().grab.beetle_follow_mul;
+        assert_eq!(energy.speed.x, 0.5 * follow_mul);
+        assert_eq!(energy.speed.y, 1.5 * follow_mul);
+    }
+}
+
 impl Deref for FighterKineticEnergyStop {
     type Target = super::energy::KineticEnergy;
 
@@ -87,18 +324,122 @@ pub enum EnergyStopResetType {
     DamageAirOrbit,
 }
 
-#[skyline::from_offset(0x3ac540)]
-unsafe extern "C" fn get_battle_object_from_id(id: u32) -> *mut BattleObject;
+/// Finds how far the fighter needs to be pushed out of the attacker's jostle area after taking a hit on the ground,
+/// along with the hitstop frames of that hit
+pub unsafe fn get_damage_knock_back_push_out(boma: &mut BattleObjectModuleAccessor) -> Option<(f32, i32)> {
+    let damage_log = DamageLog::get(boma)?;
+    let hitstop_frames = damage_log.hitstop_frames;
+    let attacker = match damage_log.attacker() {
+        Some(attacker) => attacker,
+        None => {
+            energy_log!(Level::Warn, Context::new(Module::Stop), "DamageKnockBack: object is null!");
+            return None;
+        }
+    };
+
+    if !attacker.is_active() {
+        return None;
+    }
+
+    let area_kind = JostleModule::area_kind(boma);
+    if !AreaModule::is_exist_area_instance(boma, area_kind as i32) {
+        return None;
+    }
+
+    let area_kind = JostleModule::area_kind(attacker.module_accessor());
+    if !AreaModule::is_exist_area_instance(attacker.module_accessor(), area_kind as i32) {
+        return None;
+    }
+
+    let (area_left, area_right) = (*attacker.module_accessor()).area_x_range(area_kind as i32);
+    let our_pos = PostureModule::pos(boma);
+    let their_pos = PostureModule::pos(attacker.module_accessor());
+
+    // extended limbs of the attacker also count towards their jostle area
+    let joint_x_positions = crate::config::get().knock_back.joints.iter().filter_map(|&joint| {
+        if (*attacker.module_accessor()).has_joint(Hash40::new_raw(joint)) {
+            let mut pos = Vector3f { x: 0.0, y: 0.0, z: 0.0 };
+            ModelModule::joint_global_position(attacker.module_accessor(), Hash40::new_raw(joint), &mut pos, true);
+            Some(pos.x)
+        } else {
+            None
+        }
+    });
+
+    let (is_overlapping, other_pos) /* maybe */ = if (*our_pos).x >= (*their_pos).x {
+        let x_pos = joint_x_positions.fold(area_right, f32::max);
+        ((*our_pos).x < x_pos, x_pos)
+    } else {
+        let x_pos = joint_x_positions.fold(area_left, f32::min);
+        (x_pos < (*our_pos).x, x_pos)
+    };
+
+    let overlap = if !is_overlapping {
+        0.0
+    } else {
+        other_pos - (*our_pos).x
+    };
+
+    Some((overlap, hitstop_frames))
+}
+
+/// Finds where the fighter that is grabbing us holds us, relative to our own position
+pub unsafe fn get_capture_offset(boma: &mut BattleObjectModuleAccessor) -> Option<PaddedVec2> {
+    if !LinkModule::is_link(boma, *LINK_NO_CAPTURE) {
+        return None;
+    }
+
+    let grabber = BattleObjectRef::from_id(LinkModule::get_parent_object_id(boma, *LINK_NO_CAPTURE) as u32)?;
+
+    let our_pos = PostureModule::pos(boma);
+    let joint = Hash40::new_raw(crate::config::get().grab.hold_joint);
+    let hold_pos = if (*grabber.module_accessor()).has_joint(joint) {
+        let mut pos = Vector3f { x: 0.0, y: 0.0, z: 0.0 };
+        ModelModule::joint_global_position(grabber.module_accessor(), joint, &mut pos, true);
+        pos
+    } else {
+        *PostureModule::pos(grabber.module_accessor())
+    };
+    Some(PaddedVec2::from(hold_pos) - PaddedVec2::from(*our_pos))
+}
+
+/// Finds where the ledge that the fighter's tether is attached to is, relative to the fighter's own position
+pub unsafe fn get_tether_anchor(boma: &mut BattleObjectModuleAccessor) -> Option<PaddedVec2> {
+    let status_kind = StatusModule::status_kind(boma);
+    if status_kind != *FIGHTER_STATUS_KIND_AIR_LASSO_HANG && status_kind != *FIGHTER_STATUS_KIND_AIR_LASSO_REWIND {
+        return None;
+    }
+
+    let our_pos = PostureModule::pos(boma);
+    let anchor = GroundModule::hang_cliff_pos_3f(boma);
+    Some(PaddedVec2::from(anchor) - PaddedVec2::from(*our_pos))
+}
+
+#[cfg(feature = "dev-plugin")]
+#[no_mangle]
+pub unsafe extern "Rust" fn update_stop(energy: &mut FighterKineticEnergyStop, boma: &mut BattleObjectModuleAccessor) {
+    update_stop_energy(energy, boma)
+}
+
+#[cfg(feature = "dev-plugin")]
+#[no_mangle]
+pub unsafe extern "Rust" fn initialize_stop(energy: &mut FighterKineticEnergyStop, boma: &mut BattleObjectModuleAccessor) {
+    initialize_stop_energy(energy, boma)
+}
 
 #[cfg(feature = "dev-plugin")]
 #[no_mangle]
-pub unsafe extern "Rust" fn update_stop(energy: &mut FighterKineticEnergyStop, boma: &mut BattleObjectModuleAccessor) -> bool {
+pub unsafe extern "Rust" fn setup_stop(energy: &mut FighterKineticEnergyStop, reset_type: EnergyStopResetType, initial_speed: &PaddedVec2, unk: u64, boma: &mut BattleObjectModuleAccessor) {
+    setup_stop_energy(energy, reset_type, initial_speed, unk, boma)
+}
+
+#[cfg(any(feature = "dev-plugin", test))]
+pub unsafe fn update_stop_energy<A: FighterAccessor + ?Sized>(energy: &mut FighterKineticEnergyStop, boma: &mut A) {
     use EnergyStopResetType::*;
 
     let damage_common = |energy: &mut FighterKineticEnergyStop| {
         let speed = *energy.get_speed();
-        let magnitude = (speed.x.powi(2) + speed.y.powi(2)).sqrt();
-        if magnitude <= 1.0e-05 {
+        if speed.mag() <= 1.0e-05 {
             energy.accel = PaddedVec2::zeros();
             true
         } else {
@@ -125,8 +466,9 @@ pub unsafe extern "Rust" fn update_stop(energy: &mut FighterKineticEnergyStop, b
         ].contains(&status)
     };
 
-    let handle_processing_damage = |energy: &mut FighterKineticEnergyStop, boma: &mut BattleObjectModuleAccessor, speed: f32, brake: f32| {
-        if 0.0 <= speed - brake {
+    let handle_processing_damage = |energy: &mut FighterKineticEnergyStop, speed: f32, brake: f32| {
+        // at rest there's no direction to brake along, and the multiplier would be 0 / 0
+        if 0.0 < speed && 0.0 <= speed - brake {
             let multiplier = (speed - brake) / speed - 1.0;
             energy.accel = PaddedVec2::new(
                 energy.get_speed().x * multiplier,
@@ -156,13 +498,15 @@ pub unsafe extern "Rust" fn update_stop(energy: &mut FighterKineticEnergyStop, b
         }
     };
 
+    let mut backup_brake = None;
+
     match energy.reset_type {
-        DamageGround | DamageAir | DamageAirOrbit => loop {
+        DamageGround | DamageGroundOrbit | DamageAir | DamageAirOrbit => loop {
             if damage_common(energy) { break; }
             
 
             if energy.needs_to_sync_damage_speed {
-                let speed_mul = WorkModule::get_param_float(boma, smash::hash40("battle_object"), smash::hash40("damage_sync_speed_mul"));
+                let speed_mul = boma.get_param_float(smash::hash40("battle_object"), smash::hash40("damage_sync_speed_mul"));
                 let speed = energy.get_speed();
                 speed.x *= speed_mul;
                 speed.y *= speed_mul;
@@ -174,7 +518,11 @@ pub unsafe extern "Rust" fn update_stop(energy: &mut FighterKineticEnergyStop, b
                 energy.needs_to_sync_damage_speed = true;
             }
 
-            if energy.interpolation_frames_remaining > 0 {
+            // The last frame is set outright, since adding the remaining difference can round to just off the target
+            if energy.interpolation_frames_remaining == 1 {
+                energy.speed = energy.damage_target_speed;
+                energy.interpolation_frames_remaining = 0;
+            } else if energy.interpolation_frames_remaining > 0 {
                 energy.speed = PaddedVec2::new(
                     energy.speed.x + (energy.damage_target_speed.x - energy.speed.x) / energy.interpolation_frames_remaining as f32,
                     energy.speed.y + (energy.damage_target_speed.y - energy.speed.y) / energy.interpolation_frames_remaining as f32
@@ -183,29 +531,29 @@ pub unsafe extern "Rust" fn update_stop(energy: &mut FighterKineticEnergyStop, b
             }
 
             if energy.should_start_interpolation {
-                energy.interpolation_frames_remaining = WorkModule::get_param_int(boma, smash::hash40("common"), smash::hash40("damage_pull_speed_intp_frame")) as u8;
+                energy.interpolation_frames_remaining = boma.get_param_int(smash::hash40("common"), smash::hash40("damage_pull_speed_intp_frame")) as u8;
                 energy.should_start_interpolation = false;
             }
 
-            let should_speed_up = if !WorkModule::is_flag(boma, *FIGHTER_INSTANCE_WORK_ID_FLAG_UP_SPEED_DAMAGE) {
-                let status = StatusModule::status_kind(boma);
-                if (is_damage_status(status) && WorkModule::is_flag(boma, *FIGHTER_STATUS_DAMAGE_FLAG_ELEC)) || is_saving_knockback_status(status) {
-                    WorkModule::get_int(boma, *FIGHTER_STATUS_DAMAGE_WORK_INT_HIT_STOP_FRAME) != 0
+            let should_speed_up = if !boma.is_flag(*FIGHTER_INSTANCE_WORK_ID_FLAG_UP_SPEED_DAMAGE) {
+                let status = boma.status_kind();
+                if (is_damage_status(status) && boma.is_flag(*FIGHTER_STATUS_DAMAGE_FLAG_ELEC)) || is_saving_knockback_status(status) {
+                    boma.get_int(*FIGHTER_STATUS_DAMAGE_WORK_INT_HIT_STOP_FRAME) != 0
                 } else {
-                    StopModule::is_damage(boma)
+                    boma.is_damage()
                 }
             } else {
                 true
             };
 
-            let damage_air_brake = WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("damage_air_brake"));
+            let damage_air_brake = boma.get_param_float(smash::hash40("common"), smash::hash40("damage_air_brake"));
 
-            let is_grounded = if !should_speed_up && energy.reset_type != DamageAirOrbit && StatusModule::situation_kind(boma) == *SITUATION_KIND_GROUND {
-                let brake = WorkModule::get_param_float(boma, smash::hash40("ground_brake"), 0) * WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("damage_ground_mul"));
+            let is_grounded = if !should_speed_up && energy.reset_type != DamageAirOrbit && boma.situation_kind() == *SITUATION_KIND_GROUND {
+                let brake = boma.get_param_float(smash::hash40("ground_brake"), 0) * boma.get_param_float(smash::hash40("common"), smash::hash40("damage_ground_mul"));
                 energy.speed_brake = PaddedVec2::new(brake, 0.0);
                 energy.accel = PaddedVec2::zeros();
-                let speed_limit = WorkModule::get_param_float(boma, smash::hash40("battle_object"), smash::hash40("damage_speed_limit"));
-                if [*FIGHTER_STATUS_KIND_DAMAGE_FLY, *FIGHTER_STATUS_KIND_DAMAGE_FLY_ROLL, *FIGHTER_STATUS_KIND_DAMAGE_FLY_METEOR, *FIGHTER_STATUS_KIND_SAVING_DAMAGE_FLY].contains(&StatusModule::status_kind(boma)) {
+                let speed_limit = boma.get_param_float(smash::hash40("battle_object"), smash::hash40("damage_speed_limit"));
+                if [*FIGHTER_STATUS_KIND_DAMAGE_FLY, *FIGHTER_STATUS_KIND_DAMAGE_FLY_ROLL, *FIGHTER_STATUS_KIND_DAMAGE_FLY_METEOR, *FIGHTER_STATUS_KIND_SAVING_DAMAGE_FLY].contains(&boma.status_kind()) {
                     energy.speed_limit = PaddedVec2::new(speed_limit, speed_limit);
                 } else {
                     energy.speed_limit = PaddedVec2::new(speed_limit, 0.0);
@@ -217,7 +565,7 @@ pub unsafe extern "Rust" fn update_stop(energy: &mut FighterKineticEnergyStop, b
                     *GROUND_TOUCH_FLAG_RIGHT
                 };
 
-                if GroundModule::get_touch_pos(boma, pos as u32) & 1 != 0 {
+                if boma.is_touch_pos(pos as u32) {
                     energy.speed = PaddedVec2::zeros();
                 }
                 true
@@ -233,13 +581,13 @@ pub unsafe extern "Rust" fn update_stop(energy: &mut FighterKineticEnergyStop, b
                     if is_grounded {
                         break;
                     }
-                    handle_processing_damage(energy, boma, 0.0, damage_air_brake);
+                    handle_processing_damage(energy, 0.0, damage_air_brake);
                     break;
                 }
 
-                let speed_limit = WorkModule::get_param_float(boma, smash::hash40("battle_object"), smash::hash40("damage_target_pos_speed_limit"));
+                let speed_limit = boma.get_param_float(smash::hash40("battle_object"), smash::hash40("damage_target_pos_speed_limit"));
                 let speed = *energy.get_speed();
-                let magnitude = (speed.x.powi(2) + speed.y.powi(2)).sqrt();
+                let magnitude = speed.mag();
                 if speed_limit < magnitude {
                     let new_speed = if magnitude != 0.0 {
                         PaddedVec2::new(
@@ -262,46 +610,104 @@ pub unsafe extern "Rust" fn update_stop(energy: &mut FighterKineticEnergyStop, b
             }
 
             let mag = energy.get_speed().mag();
-            handle_processing_damage(energy, boma, mag, damage_air_brake);
+            handle_processing_damage(energy, mag, damage_air_brake);
 
             break;
         },
         DamageAirIce => {
-            if StatusModule::situation_kind(boma) == *SITUATION_KIND_GROUND {
-                let brake = WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("damage_ground_mul"))
-                                    * WorkModule::get_param_float(boma, smash::hash40("ground_brake"), 0);
+            if boma.situation_kind() == *SITUATION_KIND_GROUND {
+                let brake = boma.get_param_float(smash::hash40("common"), smash::hash40("damage_ground_mul"))
+                                    * boma.get_param_float(smash::hash40("ground_brake"), 0);
                 
                 energy.speed_brake = PaddedVec2::new(brake, 0.0);
                 energy.accel = PaddedVec2::zeros();
-                energy.speed_limit = PaddedVec2::new(WorkModule::get_param_float(boma, smash::hash40("battle_object"), smash::hash40("damage_speed_limit")), 0.0);
+                energy.speed_limit = PaddedVec2::new(boma.get_param_float(smash::hash40("battle_object"), smash::hash40("damage_speed_limit")), 0.0);
             }
         },
-        ShieldRebound => return true, // case 0x16
-        AirBrake | AirBrakeAlways => return true, // default
+
+        // When enabled the swing and the reel in both drive the speed directly, the brake is left at 0 for them. Otherwise
+        // they're processed like the ones below
+        AirLassoHang if crate::config::get().tether.enabled => {
+            let speed = *energy.get_speed();
+            let anchor = boma.tether_anchor();
+            let caught_speed = fighter_state::get(boma).tether_caught_speed.unwrap_or_else(PaddedVec2::zeros);
+            energy.speed = crate::config::get().tether.hang_speed(speed, caught_speed, anchor);
+            energy.accel = PaddedVec2::zeros();
+        },
+        AirLassoRewind if crate::config::get().tether.enabled => {
+            let speed = *energy.get_speed();
+            let anchor = boma.tether_anchor();
+            energy.speed = crate::config::get().tether.rewind_speed(speed, anchor, boma.lr());
+            energy.accel = PaddedVec2::zeros();
+        },
+
+        // The glide's angle and forwards speed are kept in the fighter's state, a glide that was set up before we were
+        // loaded starts over from the current speed. When glides aren't enabled it's processed like the ones below
+        GlidStart if crate::config::get().glide.enabled => {
+            let params = &crate::config::get().glide;
+            let state = fighter_state::get(boma).glide.unwrap_or_else(|| params.start(energy.speed));
+            let state = params.step(state, boma.get_stick_y());
+            fighter_state::update(boma, |fighter| fighter.glide = Some(state));
+            energy.speed = params.speed(state, boma.lr());
+            energy.accel = PaddedVec2::zeros();
+        },
+
+        // The brake and limits for all of these are set up in initialize_stop (or by the status), all that's left is processing them
+        Air | AirEscape | Run | GuardDamage | CatchCut | CatchDash | ShieldRebound | SwimBrake | Free
+        | DamageOther | EscapeAirSlide | GlidStart | GlidLanding | AirLassoHang | AirLassoRewind => {},
+
+        // Same as above, except that slopes can scale the brake (see EnergyConfig::slope). It is restored after processing
+        Ground | RunBrake => {
+            let slope = &crate::config::get().slope;
+            if slope.enabled {
+                let brake = energy.speed_brake;
+                backup_brake = Some(brake);
+                let speed_x = energy.get_speed().x;
+                energy.speed_brake.x = brake.x * slope.modifiers(boma.ground_normal(), speed_x).brake_mul;
+            }
+        },
+
+        // AirBrake brakes each axis on its own
+        AirBrake => {
+            energy.accel = PaddedVec2::zeros();
+        },
+
+        // AirBrakeAlways brakes along the direction that we are moving instead, so that the angle of the speed is kept.
+        // The brake is moved into the accel here and restored after processing
+        AirBrakeAlways => {
+            let brake = energy.speed_brake;
+            backup_brake = Some(brake);
+            let mag = energy.get_speed().mag();
+            handle_processing_damage(energy, mag, brake.x);
+            energy.speed_brake = PaddedVec2::zeros();
+        },
+
+        // The captured fighter is held in place. When enabled they're pulled in towards whoever grabbed them instead, if we
+        // can't find them then we just stay put
+        Capture => {
+            let grab = &crate::config::get().grab;
+            energy.speed = match boma.capture_offset() {
+                Some(offset) if grab.is_pull_in_enabled() => grab.pull_in_speed(offset),
+                _ => PaddedVec2::zeros()
+            };
+            energy.accel = PaddedVec2::zeros();
+            return;
+        },
         DamageKnockBack => loop {
             if 0.0 >= energy.hitstop_frames {
                 break;
             }
 
-            if StatusModule::situation_kind(boma) != *SITUATION_KIND_GROUND || energy.hitstop_frames <= energy.elapsed_hitstop_frames {
+            if boma.situation_kind() != *SITUATION_KIND_GROUND || energy.hitstop_frames <= energy.elapsed_hitstop_frames {
                 energy.speed.x = 0.0;
                 energy.elapsed_hitstop_frames = 0.0;
                 energy.hitstop_frames = 0.0;
                 energy._xAC = 0.0;
                 energy._xB0 = 0.0
             } else {
-                let rate = WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("damage_knock_back_speed_x_rate"));
+                let rate = boma.get_param_float(smash::hash40("common"), smash::hash40("damage_knock_back_speed_x_rate"));
                 let progress = energy.elapsed_hitstop_frames / energy.hitstop_frames;
-                let remaining_progress = 1.0 - progress;
-                // A(1-x)^3 + Bx(1-x)^2 + C(1-x)x^2 + Dx^3 
-                // A = damage_knock_back_speed_x_rate * 0.01
-                // B = 3.0 * 0.6 = 1.8
-                // C = 3.0 * 0.79 = ~2.4
-                // D = 1.0
-                let speed_x = rate * 0.01 * remaining_progress.powi(3);
-                let speed_x = speed_x + 3.0 * 0.6 * progress * remaining_progress.powi(2);
-                let speed_x = speed_x + 3.0 * 0.79 * progress.powi(2) * remaining_progress;
-                let speed_x = speed_x + progress.powi(3);
+                let speed_x = crate::config::get().knock_back.curve(rate).evaluate(progress);
                 let speed_x = speed_x * energy._xB0;
 
                 // not sure about this one chief
@@ -320,18 +726,16 @@ pub unsafe extern "Rust" fn update_stop(energy: &mut FighterKineticEnergyStop, b
         },
         AirXNormalMax => {
             let speed = energy.get_speed();
-            let brake = if speed.x.abs() <= WorkModule::get_param_float(boma, smash::hash40("air_speed_x_stable"), 0) {
-                WorkModule::get_param_float(boma, smash::hash40("air_brake_x"), 0)
+            let brake = if speed.x.abs() <= boma.get_param_float(smash::hash40("air_speed_x_stable"), 0) {
+                boma.get_param_float(smash::hash40("air_brake_x"), 0)
             } else {
-                WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("fall_brake_x"))
+                boma.get_param_float(smash::hash40("common"), smash::hash40("fall_brake_x"))
             };
             energy.speed_brake = PaddedVec2::new(brake, 0.0);
         },
         ItemSwingDash | ItemDashThrow => {
-            if crate::motion::FighterKineticEnergyMotion::is_main_motion_updating_energy(boma) {
-                MotionModule::update_trans_move_speed(boma);
-                let speed = crate::motion::FighterKineticEnergyMotion::trans_move_speed_correct(boma);
-                let lr = PostureModule::lr(boma);
+            if boma.is_main_motion_updating_energy() {
+                let speed = boma.trans_move_speed();
                 let energy_speed = energy.get_speed();
                 let accel = PaddedVec2::new(speed.z - energy_speed.x, speed.y - energy_speed.y);
                 energy.speed_max = PaddedVec2::new(-energy_speed.x, -energy_speed.y);
@@ -339,165 +743,193 @@ pub unsafe extern "Rust" fn update_stop(energy: &mut FighterKineticEnergyStop, b
                 energy.accel = accel;
             }
             if energy.reset_type == ItemDashThrow
-            && MotionModule::frame(boma) > WorkModule::get_param_int(boma, smash::hash40("common"), smash::hash40("item_dash_throw_brake_dec_frame")) as f32
+            && boma.motion_frame() > boma.get_param_int(smash::hash40("common"), smash::hash40("item_dash_throw_brake_dec_frame")) as f32
             {
-                let brake = WorkModule::get_param_float(boma, smash::hash40("ground_brake"), 0)
-                                    * WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("item_dash_throw_brake_mul"))
-                                    * WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("item_dash_throw_brake_dec"));
+                let brake = boma.get_param_float(smash::hash40("ground_brake"), 0)
+                                    * boma.get_param_float(smash::hash40("common"), smash::hash40("item_dash_throw_brake_mul"))
+                                    * boma.get_param_float(smash::hash40("common"), smash::hash40("item_dash_throw_brake_dec"));
                 energy.speed_brake = PaddedVec2::new(brake, 0.0);
             }
         },
         CaptureBeetle => {
-            if LinkModule::is_link(boma, *LINK_NO_CAPTURE) {
-                energy.speed = FighterKineticEnergyStop::get_parent_sum_speed_correct(boma, *LINK_NO_CAPTURE, 1);
-                return true;
+            if boma.is_link(*LINK_NO_CAPTURE) {
+                let speed = boma.get_parent_sum_speed(*LINK_NO_CAPTURE, 1);
+                energy.speed = speed * crate::config::get().grab.beetle_follow_mul;
+                return;
             }
         },
-        _ => return false
     }
 
-    energy.process(boma);
+    // The accel of the stop energies only ever slows the fighter down, so only the brake is scaled by the ground's
+    // material (see EnergyConfig::traction). It is restored after processing
+    let traction = &crate::config::get().traction;
+    if !traction.is_empty() && boma.situation_kind() == *SITUATION_KIND_GROUND {
+        let brake_mul = traction.get(boma.ground_material()).brake_mul;
+        if backup_brake.is_none() {
+            backup_brake = Some(energy.speed_brake);
+        }
+        energy.speed_brake.x *= brake_mul;
+    }
+
+    boma.process_energy(energy);
 
-    let status_module = *(boma as *const BattleObjectModuleAccessor as *const u64).add(0x8);
-    if !*(status_module as *const bool).add(0x12a) {
-        if StatusModule::situation_kind(boma) == *SITUATION_KIND_AIR {
-            let horizontal_limit = WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("common_air_speed_x_limit"));
+    if !boma.is_ignore_common_speed_limit() {
+        if boma.situation_kind() == *SITUATION_KIND_AIR {
+            let horizontal_limit = boma.get_param_float(smash::hash40("common"), smash::hash40("common_air_speed_x_limit"));
             let vertical_limit = if energy.speed.y <= 0.0 {
-                WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("air_speed_down_limit"))
+                boma.get_param_float(smash::hash40("common"), smash::hash40("air_speed_down_limit"))
             } else {
-                WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("air_speed_up_limit"))
+                boma.get_param_float(smash::hash40("common"), smash::hash40("air_speed_up_limit"))
             };
 
             if horizontal_limit < energy.speed.x.abs() {
-                energy.speed.x = vertical_limit * energy.speed.x.signum();
+                energy.speed.x = horizontal_limit * energy.speed.x.signum();
             }
 
             if vertical_limit < energy.speed.y.abs() {
                 energy.speed.y = vertical_limit * energy.speed.y.signum();
             }
-        } else if StatusModule::situation_kind(boma) == *SITUATION_KIND_GROUND {
-            let speed_limit = WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("ground_speed_limit"));
+        } else if boma.situation_kind() == *SITUATION_KIND_GROUND {
+            let speed_limit = boma.get_param_float(smash::hash40("common"), smash::hash40("ground_speed_limit"));
             if speed_limit < energy.speed.x.abs() {
                 energy.speed.x = speed_limit * energy.speed.x.signum();
             }
         }
     }
 
-    true
-
-    // println!("{:?}", energy.reset_type);
-
-
-    // false
+    if let Some(brake) = backup_brake {
+        energy.speed_brake = brake;
+    }
 }
 
-#[cfg(feature = "dev-plugin")]
-#[no_mangle]
-pub unsafe extern "Rust" fn initialize_stop(energy: &mut FighterKineticEnergyStop, boma: &mut BattleObjectModuleAccessor) -> bool {
+#[cfg(any(feature = "dev-plugin", test))]
+pub unsafe fn initialize_stop_energy<A: FighterAccessor + ?Sized>(energy: &mut FighterKineticEnergyStop, boma: &mut A) {
     use EnergyStopResetType::*;
     
     match energy.reset_type {
-        Ground | CatchCut | ItemSwingDash | ItemDashThrow => {
-            let ground_brake = WorkModule::get_param_float(boma, smash::hash40("ground_brake"), 0);
+        Ground | CatchCut | ItemSwingDash | ItemDashThrow | GlidLanding => {
+            let ground_brake = boma.get_param_float(smash::hash40("ground_brake"), 0);
             let mut multiplier = match energy.reset_type {
-                CatchCut => WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("capture_cut_brake_mul")),
-                ItemSwingDash => WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("item_dash_swing_brake_mul")),
-                ItemDashThrow => WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("item_dash_throw_brake_mul")),
+                CatchCut => boma.get_param_float(smash::hash40("common"), smash::hash40("capture_cut_brake_mul")),
+                ItemSwingDash => boma.get_param_float(smash::hash40("common"), smash::hash40("item_dash_swing_brake_mul")),
+                ItemDashThrow => boma.get_param_float(smash::hash40("common"), smash::hash40("item_dash_throw_brake_mul")),
+                GlidLanding if crate::config::get().glide.enabled => crate::config::get().glide.landing_brake_mul,
                 _ => 1.0
             };
             if energy._xBB {
-                multiplier *= WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("stop_over_speed_brake_mul"));
+                multiplier *= boma.get_param_float(smash::hash40("common"), smash::hash40("stop_over_speed_brake_mul"));
             }
             energy.speed_brake = PaddedVec2::new(ground_brake * multiplier, 0.0);
             energy.speed_limit = PaddedVec2::new(
-                WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("ground_speed_limit")),
+                boma.get_param_float(smash::hash40("common"), smash::hash40("ground_speed_limit")),
                 0.0
             );
         },
         DamageGround | GuardDamage | DamageGroundOrbit => {
-            let brake = WorkModule::get_param_float(boma, smash::hash40("ground_brake"), 0)
-                                * WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("damage_ground_mul"));
+            let brake = boma.get_param_float(smash::hash40("ground_brake"), 0)
+                                * boma.get_param_float(smash::hash40("common"), smash::hash40("damage_ground_mul"));
             energy.speed_brake = PaddedVec2::new(brake, 0.0);
             energy.speed_limit = PaddedVec2::new(
-                WorkModule::get_param_float(boma, smash::hash40("battle_object"), smash::hash40("damage_speed_limit")),
+                boma.get_param_float(smash::hash40("battle_object"), smash::hash40("damage_speed_limit")),
                 0.0
             );
         },
         Air | AirXNormalMax => {
             energy.speed_limit = PaddedVec2::new(
-                WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("air_speed_x_limit")),
+                boma.get_param_float(smash::hash40("common"), smash::hash40("air_speed_x_limit")),
                 0.0
             );
             energy.speed_brake = PaddedVec2::new(
-                WorkModule::get_param_float(boma, smash::hash40("air_brake_x"), 0),
+                boma.get_param_float(smash::hash40("air_brake_x"), 0),
                 0.0
             );
         },
         AirEscape => {
             energy.speed_limit = PaddedVec2::new(
-                WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("air_speed_x_limit")),
+                boma.get_param_float(smash::hash40("common"), smash::hash40("air_speed_x_limit")),
                 0.0
             );
             energy.speed_brake = PaddedVec2::new(
-                WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("escape_air_brake")),
+                boma.get_param_float(smash::hash40("common"), smash::hash40("escape_air_brake")),
                 0.0
             );
         },
         Run => {
             energy.speed_limit = PaddedVec2::new(
-                WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("ground_speed_limit")),
+                boma.get_param_float(smash::hash40("common"), smash::hash40("ground_speed_limit")),
                 0.0
             );
         },
         RunBrake => {
-            let brake = WorkModule::get_param_float(boma, smash::hash40("ground_brake"), 0)
-                                * WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("run_brake_brake_mul"));
+            let brake = boma.get_param_float(smash::hash40("ground_brake"), 0)
+                                * boma.get_param_float(smash::hash40("common"), smash::hash40("run_brake_brake_mul"));
             energy.speed_brake = PaddedVec2::new(
                 brake,
                 0.0
             );
             energy.speed_limit = PaddedVec2::new(
-                WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("ground_speed_limit")),
+                boma.get_param_float(smash::hash40("common"), smash::hash40("ground_speed_limit")),
                 0.0
             );
         },
         CatchDash => {
-            let brake = WorkModule::get_param_float(boma, smash::hash40("ground_brake"), 0)
-                                * WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("catch_dash_brake_mul"));
+            // If a slide distance is configured then the brake is picked to stop us there
+            let brake = match crate::config::get().grab.catch_dash_brake(energy.speed.x) {
+                Some(brake) => brake,
+                None => boma.get_param_float(smash::hash40("ground_brake"), 0)
+                            * boma.get_param_float(smash::hash40("common"), smash::hash40("catch_dash_brake_mul"))
+            };
             energy.speed_brake = PaddedVec2::new(
                 brake,
                 0.0
             );
             energy.speed_limit = PaddedVec2::new(
-                WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("ground_speed_limit")),
+                boma.get_param_float(smash::hash40("common"), smash::hash40("ground_speed_limit")),
                 0.0
             );
         },
+        SwimBrake => {
+            let params = &crate::config::get().swim;
+            let brake = boma.get_param_float(smash::hash40("common"), smash::hash40("swim_brake")) * params.brake_mul;
+            energy.speed_brake = PaddedVec2::new(
+                brake,
+                params.vertical_brake()
+            );
+        },
         ShieldRebound => {
-            let brake = WorkModule::get_param_float(boma, smash::hash40("ground_brake"), 0)
-                                * WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("shield_rebound_ground_brake"));
+            let brake = boma.get_param_float(smash::hash40("ground_brake"), 0)
+                                * boma.get_param_float(smash::hash40("common"), smash::hash40("shield_rebound_ground_brake"));
             energy.speed_brake = PaddedVec2::new(
                 brake,
                 0.0
             );
             energy.speed_limit = PaddedVec2::new(
-                WorkModule::get_param_float(boma, smash::hash40("battle_object"), smash::hash40("damage_speed_limit")),
+                boma.get_param_float(smash::hash40("battle_object"), smash::hash40("damage_speed_limit")),
                 0.0
             );
         },
-        _ => {}
+        // The tether holds the fighter where they are, so any speed they had going into it is braked out. When tethers are
+        // enabled the swing drives the speed instead
+        AirLassoHang if !crate::config::get().tether.enabled => {
+            energy.speed_brake = PaddedVec2::new(
+                boma.get_param_float(smash::hash40("air_brake_x"), 0),
+                boma.get_param_float(smash::hash40("air_brake_y"), 0)
+            );
+        },
+        // These are either set up entirely in setup_stop or have their brake set by the status
+        DamageAir | DamageAirIce | DamageOther | DamageKnockBack | AirBrake | AirBrakeAlways | Capture | GlidStart
+        | Free | CaptureBeetle | AirLassoHang | AirLassoRewind | EscapeAirSlide | DamageAirOrbit => {}
     }
-    true
 }
 
-#[cfg(feature = "dev-plugin")]
-#[no_mangle]
-pub unsafe extern "Rust" fn setup_stop(energy: &mut FighterKineticEnergyStop, reset_type: EnergyStopResetType, initial_speed: &PaddedVec2, unk: u64, boma: &mut BattleObjectModuleAccessor) -> bool {
+#[cfg(any(feature = "dev-plugin", test))]
+pub unsafe fn setup_stop_energy<A: FighterAccessor + ?Sized>(energy: &mut FighterKineticEnergyStop, reset_type: EnergyStopResetType, initial_speed: &PaddedVec2, unk: u64, boma: &mut A) {
     use EnergyStopResetType::*;
 
+    // Rewinding keeps everything from the hang, the only thing that changes is how we update
     if reset_type == AirLassoRewind {
         energy.reset_type = reset_type;
-        return true;
+        return;
     }
 
     energy.speed = PaddedVec2::zeros();
@@ -509,99 +941,50 @@ pub unsafe extern "Rust" fn setup_stop(energy: &mut FighterKineticEnergyStop, re
     energy.reset_type = reset_type;
     energy.speed = *initial_speed;
 
+    // A glide or a hang only lasts as long as the reset type it was set up for, reeling in has already kept the hang's
+    fighter_state::update(boma, |fighter| {
+        fighter.glide = None;
+        fighter.tether_caught_speed = None;
+    });
+
     match reset_type {
         Ground | CatchCut | ItemSwingDash | ItemDashThrow => {
+            if reset_type == CatchCut {
+                if let Some(speed) = crate::config::get().grab.release_speed(energy.speed, boma.lr()) {
+                    energy.speed = speed;
+                }
+            }
+
             let speed = energy.get_speed();
-            let adjusted_speed = energy::KineticEnergy::adjust_speed_for_ground_normal(speed, boma);
+            let adjusted_speed = boma.adjust_speed_for_ground_normal(speed);
             *speed = adjusted_speed;
 
-            // get magnitude of speed vector
-            let magnitude = (adjusted_speed.x.powi(2) + adjusted_speed.y.powi(2)).sqrt();
-
-            energy._xBB = WorkModule::get_param_float(boma, smash::hash40("walk_speed_max"), 0) < magnitude;
+            energy._xBB = boma.get_param_float(smash::hash40("walk_speed_max"), 0) < adjusted_speed.mag();
         },
-        DamageGround | GuardDamage | Run | RunBrake | CatchDash | ShieldRebound => {
+        // Whatever speed we were gliding with is carried into the landing, with some of the fall turned into horizontal speed
+        GlidLanding if crate::config::get().glide.enabled => {
+            let speed = crate::config::get().glide.landing_speed(*initial_speed);
+            energy.speed = boma.adjust_speed_for_ground_normal(&speed);
+        },
+        GlidStart if crate::config::get().glide.enabled => {
+            let params = &crate::config::get().glide;
+            let state = params.start(*initial_speed);
+            fighter_state::update(boma, |fighter| fighter.glide = Some(state));
+            energy.speed = params.speed(state, boma.lr());
+            energy.speed_max = PaddedVec2::new(-1.0, -1.0);
+        },
+        DamageGround | GuardDamage | Run | RunBrake | CatchDash | ShieldRebound | GlidLanding | DamageGroundOrbit => {
             let speed = energy.get_speed();
-            *speed = energy::KineticEnergy::adjust_speed_for_ground_normal(speed, boma);
+            *speed = boma.adjust_speed_for_ground_normal(speed);
         },
         DamageKnockBack => loop { // easier to follow if I structure this as a loop
-            if StatusModule::situation_kind(boma) != *SITUATION_KIND_GROUND {
-                break;
-            }
-
-            let damage_log = DamageModule::damage_log(boma);
-            let object_id = *(damage_log as *const u32).add(0x84 / 0x4);
-            let object = get_battle_object_from_id(object_id);
-            if object.is_null() {
-                println!("DamageKnockBack: object is null!");
-                break;
-            }
-
-            let vtable_method: extern "C" fn(*mut BattleObject) -> bool = std::mem::transmute(**(object as *const *const u64));
-            if vtable_method(object) || *(object as *const u8).add(0x3A) <= 3 {
-                break;
-            }
-
-            let area_kind = JostleModule::area_kind(boma);
-            if !AreaModule::is_exist_area_instance(boma, area_kind as i32) {
-                break;
-            }
-
-            let area_kind = JostleModule::area_kind((*object).module_accessor);
-            if !AreaModule::is_exist_area_instance((*object).module_accessor, area_kind as i32) {
+            if boma.situation_kind() != *SITUATION_KIND_GROUND {
                 break;
             }
 
-            let area_module = *((*object).module_accessor as *const u64).add(0xC0 / 0x8);
-            let get_area: extern "C" fn(u64, i32) -> u64 = std::mem::transmute(*(*(area_module as *const *const u64)).add(0x118 / 0x8));
-            let area = get_area(area_module, area_kind as i32);
-            let our_pos = PostureModule::pos(boma);
-            let their_pos = PostureModule::pos((*object).module_accessor);
-
-            let does_model_have_joint = |boma: *mut BattleObjectModuleAccessor, hash: Hash40| {
-                let model_module = *(boma as *const u64).add(0x78 / 0x8);
-                let function: extern "C" fn(u64, Hash40) -> bool = std::mem::transmute(*(*(model_module as *const *const u64)).add(0x320 / 0x8));
-                function(model_module, hash)
-            };
-
-            let (is_overlapping, other_pos) /* maybe */ = if (*our_pos).x >= (*their_pos).x {
-                let x_pos = *(area as *const f32).add(0x50 / 0x4);
-                let x_pos = if does_model_have_joint((*object).module_accessor, Hash40::new_raw(0x14d5b6ea53)) {
-                    let mut pos = Vector3f { x: 0.0, y: 0.0, z: 0.0 };
-                    ModelModule::joint_global_position((*object).module_accessor, Hash40::new_raw(0x14d5b6ea53), &mut pos, true);
-                    pos.x.max(x_pos)
-                } else {
-                    x_pos
-                };
-
-                let x_pos = if does_model_have_joint((*object).module_accessor, Hash40::new_raw(0x142fb9d730)) {
-                    let mut pos = Vector3f { x: 0.0, y: 0.0, z: 0.0 };
-                    ModelModule::joint_global_position((*object).module_accessor, Hash40::new_raw(0x142fb9d730), &mut pos, true);
-                    pos.x.max(x_pos)
-                } else {
-                    x_pos
-                };
-
-                ((*our_pos).x < x_pos, x_pos)
-            } else {
-                let x_pos = *(area as *const f32).add(0x40 / 0x4);
-                let x_pos = if does_model_have_joint((*object).module_accessor, Hash40::new_raw(0x14d5b6ea53)) {
-                    let mut pos = Vector3f { x: 0.0, y: 0.0, z: 0.0 };
-                    ModelModule::joint_global_position((*object).module_accessor, Hash40::new_raw(0x14d5b6ea53), &mut pos, true);
-                    pos.x.min(x_pos)
-                } else {
-                    x_pos
-                };
-
-                let x_pos = if does_model_have_joint((*object).module_accessor, Hash40::new_raw(0x142fb9d730)) {
-                    let mut pos = Vector3f { x: 0.0, y: 0.0, z: 0.0 };
-                    ModelModule::joint_global_position((*object).module_accessor, Hash40::new_raw(0x142fb9d730), &mut pos, true);
-                    pos.x.min(x_pos)
-                } else {
-                    x_pos
-                };
-
-                (x_pos < (*our_pos).x, x_pos)
+            let (overlap, hitstop_frames) = match boma.damage_knock_back_push_out() {
+                Some(push_out) => push_out,
+                None => break
             };
 
             energy.elapsed_hitstop_frames = 0.0;
@@ -609,42 +992,41 @@ pub unsafe extern "Rust" fn setup_stop(energy: &mut FighterKineticEnergyStop, re
             energy._xAC = 0.0;
             energy._xB0 = 0.0;
 
-            let overlap = if !is_overlapping {
-                0.0
-            } else {
-                other_pos - (*our_pos).x
-            };
+            let params = &crate::config::get().knock_back;
+            let frame_rate = boma.get_param_float(smash::hash40("common"), smash::hash40("damage_knock_back_hitstop_frame_rate"));
 
-            let hitstop_frames = *(damage_log as *const i32).add(0x4C / 4);
-
-            let frame_rate = WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("damage_knock_back_hitstop_frame_rate"));
-            
-            energy.hitstop_frames = (frame_rate * 0.01 * hitstop_frames as f32).max(1.0);
+            energy.hitstop_frames = params.frames(frame_rate, hitstop_frames);
             energy._xB0 = overlap;
-            let speed_rate = WorkModule::get_param_float(boma, smash::hash40("common"), smash::hash40("damage_knock_back_speed_x_rate"));
+            let speed_rate = boma.get_param_float(smash::hash40("common"), smash::hash40("damage_knock_back_speed_x_rate"));
             energy.speed = PaddedVec2::new(
-                overlap * speed_rate * 0.01,
+                overlap * params.curve(speed_rate).evaluate(0.0),
                 0.0
-            );            
+            );
 
             break;
         },
         AirLassoHang => {
             energy.damage_target_speed = *initial_speed;
+            if crate::config::get().tether.enabled {
+                fighter_state::update(boma, |fighter| fighter.tether_caught_speed = Some(*initial_speed));
+                energy.speed_max = PaddedVec2::new(-1.0, -1.0);
+            }
         },
         EscapeAirSlide => {
             let energy_speed = *energy.get_speed();
             energy.damage_target_speed = *initial_speed;
-            let speed = WorkModule::get_param_float(boma, smash::hash40("escape_air_slide_speed"), 0);
-            let accel = WorkModule::get_param_float(boma, smash::hash40("escape_air_slide_accel"), 0);
-            energy.speed = PaddedVec2::new(energy_speed.x * speed, energy_speed.y * speed);
-            energy.speed_brake = PaddedVec2::new((energy_speed.x * accel).abs(), (energy_speed.y * accel).abs());
+            let speed = boma.get_param_float(smash::hash40("escape_air_slide_speed"), 0);
+            let accel = boma.get_param_float(smash::hash40("escape_air_slide_accel"), 0);
+            energy.speed = energy_speed * speed;
+            energy.speed_brake = (energy_speed * accel).abs();
             energy.speed_limit = PaddedVec2::new(-1.0, -1.0);
-        }
-        _ => {},
+        },
+        DamageAir | DamageAirIce | DamageOther | Air | AirXNormalMax | AirEscape | AirBrake | AirBrakeAlways | Capture
+        | SwimBrake | GlidStart | Free | CaptureBeetle | AirLassoRewind | DamageAirOrbit => {},
     }
 
-    energy.initialize(boma);
+    // Our initialize replaces the one the vtable points to, so call it directly
+    initialize_stop_energy(energy, boma);
     energy._xBA = false;
     energy._xB8 = 0;
     energy.is_target_pos = false;
@@ -652,49 +1034,38 @@ pub unsafe extern "Rust" fn setup_stop(energy: &mut FighterKineticEnergyStop, re
     energy.needs_to_sync_damage_speed = false;
     energy.should_start_interpolation = false;
     energy.interpolation_frames_remaining = 0;
-
-    true
 }
 
 #[cfg(not(feature = "dev-plugin"))]
-#[skyline::hook(offset = 0x6d6630)]
-unsafe fn update_stop_hook(energy: &mut FighterKineticEnergyStop, boma: &mut BattleObjectModuleAccessor) {
+unsafe extern "C" fn update_stop_hook(energy: &mut FighterKineticEnergyStop, boma: &mut BattleObjectModuleAccessor) {
     extern "Rust" {
-        fn update_stop(energy: &mut FighterKineticEnergyStop, boma: &mut BattleObjectModuleAccessor) -> bool;
+        fn update_stop(energy: &mut FighterKineticEnergyStop, boma: &mut BattleObjectModuleAccessor);
     }
 
     if super::SHOULD_RUN {
-        if !update_stop(energy, boma) {
-            call_original!(energy, boma)
-        }
+        update_stop(energy, boma);
     }
 }
 
 #[cfg(not(feature = "dev-plugin"))]
-#[skyline::hook(offset = 0x6d80e0)]
-unsafe fn initialize_stop_hook(energy: &mut FighterKineticEnergyStop, boma: &mut BattleObjectModuleAccessor) {
+unsafe extern "C" fn initialize_stop_hook(energy: &mut FighterKineticEnergyStop, boma: &mut BattleObjectModuleAccessor) {
     extern "Rust" {
-        fn initialize_stop(energy: &mut FighterKineticEnergyStop, boma: &mut BattleObjectModuleAccessor) -> bool;
+        fn initialize_stop(energy: &mut FighterKineticEnergyStop, boma: &mut BattleObjectModuleAccessor);
     }
 
     if super::SHOULD_RUN {
-        if !initialize_stop(energy, boma) {
-            call_original!(energy, boma)
-        }
+        initialize_stop(energy, boma);
     }
 }
 
 #[cfg(not(feature = "dev-plugin"))]
-#[skyline::hook(offset = 0x6d8540)]
-unsafe fn setup_stop_hook(energy: &mut FighterKineticEnergyStop, reset_type: EnergyStopResetType, initial_speed: &PaddedVec2, unk: u64, boma: &mut BattleObjectModuleAccessor) {
+unsafe extern "C" fn setup_stop_hook(energy: &mut FighterKineticEnergyStop, reset_type: EnergyStopResetType, initial_speed: &PaddedVec2, unk: u64, boma: &mut BattleObjectModuleAccessor) {
     extern "Rust" {
-        fn setup_stop(energy: &mut FighterKineticEnergyStop, reset_type: EnergyStopResetType, initial_speed: &PaddedVec2, unk: u64, boma: &mut BattleObjectModuleAccessor) -> bool;
+        fn setup_stop(energy: &mut FighterKineticEnergyStop, reset_type: EnergyStopResetType, initial_speed: &PaddedVec2, unk: u64, boma: &mut BattleObjectModuleAccessor);
     }
 
     if super::SHOULD_RUN {
-        if !setup_stop(energy, reset_type, initial_speed, unk, boma) {
-            call_original!(energy, reset_type, initial_speed, unk, boma)
-        }
+        setup_stop(energy, reset_type, initial_speed, unk, boma);
     }
     
 }
@@ -702,10 +1073,17 @@ unsafe fn setup_stop_hook(energy: &mut FighterKineticEnergyStop, reset_type: Ene
 pub fn install() {
     #[cfg(not(feature = "dev-plugin"))]
     {
-        skyline::install_hooks!(
-            update_stop_hook,
-            initialize_stop_hook,
-            setup_stop_hook
-        );
+        use crate::offsets::{self, Function::*};
+
+        if !offsets::all_resolved(&[UpdateStop, InitializeStop, SetupStop, ProcessEnergy, GetBattleObjectFromId, AdjustSpeedForGroundNormal]) {
+            energy_log!(Level::Error, Context::new(Module::Stop), "Not installing the stop energy hooks");
+            return;
+        }
+
+        unsafe {
+            offsets::install_hook(UpdateStop, update_stop_hook as *const ());
+            offsets::install_hook(InitializeStop, initialize_stop_hook as *const ());
+            offsets::install_hook(SetupStop, setup_stop_hook as *const ());
+        }
     }
 }
\ No newline at end of file
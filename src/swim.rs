@@ -0,0 +1,92 @@
+/// Values used by the water movement of the control and stop energies. The horizontal values still
+/// come from the game's `swim_*` common params, these are the pieces the game either hardcodes or
+/// doesn't have at all.
+///
+/// The game leaves the vertical speed of a swimming fighter alone, so the vertical movement here is
+/// only used once `enabled` is set
+#[derive(Debug, Copy, Clone)]
+#[repr(C)]
+pub struct SwimParams {
+    /// Lets the stick steer swimming fighters up and down, and SwimRise and SwimDrown move them vertically
+    pub enabled: bool,
+    /// The upwards acceleration applied while rising to the surface (SwimRise)
+    pub rise_accel: f32,
+    /// The maximum upwards speed while rising to the surface (SwimRise)
+    pub rise_speed_max: f32,
+    /// Multiplier on `swim_accel_mul` while rising, so fighters can't fully drift on the way up. Only used when enabled
+    pub rise_accel_x_mul: f32,
+    /// The stick y value that needs to be exceeded before vertical control kicks in
+    pub stick_y_deadzone: f32,
+    /// The vertical acceleration at full stick tilt while swimming
+    pub vertical_accel: f32,
+    /// The vertical speed reached at full stick tilt while swimming
+    pub vertical_speed_max: f32,
+    /// The brake applied to vertical speed once the stick is released, for both the control and stop energies
+    pub vertical_brake: f32,
+    /// Constant upwards acceleration while the stick is neutral. The game's default is no buoyancy
+    pub buoyancy_accel: f32,
+    /// The maximum speed that buoyancy alone can reach
+    pub buoyancy_speed_max: f32,
+    /// The downwards acceleration while drowning
+    pub drown_sink_accel: f32,
+    /// The maximum downwards speed while drowning
+    pub drown_sink_speed: f32,
+    /// Multiplier on `swim_brake` while drowning
+    pub drown_brake_mul: f32,
+    /// Multiplier on `swim_brake` for the stop energy (SwimBrake)
+    pub brake_mul: f32,
+}
+
+impl SwimParams {
+    pub const DEFAULT: Self = Self {
+        enabled: false,
+        rise_accel: 0.05,
+        rise_speed_max: 1.0,
+        rise_accel_x_mul: 0.5,
+        stick_y_deadzone: 0.25,
+        vertical_accel: 0.04,
+        vertical_speed_max: 0.6,
+        vertical_brake: 0.04,
+        buoyancy_accel: 0.0,
+        buoyancy_speed_max: 0.0,
+        drown_sink_accel: 0.0,
+        drown_sink_speed: 0.0,
+        drown_brake_mul: 1.0,
+        brake_mul: 1.0,
+    };
+
+    /// Gets the vertical acceleration and maximum speed for the Swim reset type
+    /// # Arguments
+    /// * `stick_y` - The vertical stick value of the fighter
+    /// # Returns
+    /// The vertical acceleration and the vertical maximum speed
+    pub fn swim_vertical(&self, stick_y: f32) -> (f32, f32) {
+        if stick_y.abs() <= self.stick_y_deadzone {
+            // A neutral stick lets buoyancy take over, and with no buoyancy our speed is braked back to 0
+            return (self.buoyancy_accel, self.buoyancy_speed_max);
+        }
+
+        // lerp from the deadzone so that the speed doesn't jump once we leave it
+        let rate = (stick_y.abs() - self.stick_y_deadzone) / (1.0 - self.stick_y_deadzone);
+        (rate * self.vertical_accel * stick_y.signum(), rate * self.vertical_speed_max)
+    }
+
+    /// Gets the brake of the vertical speed, which the game doesn't have
+    pub fn vertical_brake(&self) -> f32 {
+        if self.enabled {
+            self.vertical_brake
+        } else {
+            0.0
+        }
+    }
+
+    /// Gets the vertical acceleration and maximum speed for the SwimRise reset type
+    pub fn rise_vertical(&self) -> (f32, f32) {
+        (self.rise_accel, self.rise_speed_max)
+    }
+
+    /// Gets the vertical acceleration and maximum speed for the SwimDrown reset type
+    pub fn drown_vertical(&self) -> (f32, f32) {
+        (-self.drown_sink_accel, self.drown_sink_speed)
+    }
+}
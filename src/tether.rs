@@ -0,0 +1,141 @@
+use super::energy::PaddedVec2;
+
+/// Values used by the stop energy while hanging from a tether (AirLassoHang) and reeling back in (AirLassoRewind)
+///
+/// The game brakes a hanging fighter to a stop and only processes the energy while reeling in, so the swing and the reel
+/// here are only used once `enabled` is set
+#[derive(Debug, Copy, Clone)]
+#[repr(C)]
+pub struct TetherParams {
+    /// Swings hanging fighters around the tether's anchor and reels them in towards it
+    pub enabled: bool,
+    /// The fraction of the swing speed that is lost every frame while hanging
+    pub swing_damping: f32,
+    /// The downwards pull applied every frame while hanging
+    pub hang_gravity: f32,
+    /// The fastest that the fighter can be swung by gravity while hanging, a tether that catches a faster fighter
+    /// lets them keep the speed they were caught at
+    pub hang_speed_max: f32,
+    /// The speed the fighter is reeled in at
+    pub reel_speed: f32,
+    /// How quickly the fighter reaches `reel_speed`, per frame
+    pub reel_accel: f32,
+    /// The angle (in radians, from the direction the fighter is facing) that the fighter is reeled in at when the tether's
+    /// anchor can't be found
+    pub reel_angle: f32,
+}
+
+impl TetherParams {
+    pub const DEFAULT: Self = Self {
+        enabled: false,
+        swing_damping: 0.1,
+        hang_gravity: 0.02,
+        hang_speed_max: 1.0,
+        reel_speed: 1.2,
+        reel_accel: 0.15,
+        reel_angle: 1.2,
+    };
+
+    /// Gets the speed for the next frame of hanging from a tether
+    /// # Arguments
+    /// * `speed` - The speed from the previous frame
+    /// * `caught_speed` - The speed the fighter was moving at when the tether caught
+    /// * `anchor` - Where the tether is anchored, relative to the fighter
+    pub fn hang_speed(&self, speed: PaddedVec2, caught_speed: PaddedVec2, anchor: Option<PaddedVec2>) -> PaddedVec2 {
+        let mut speed = speed * (1.0 - self.swing_damping) - PaddedVec2::new(0.0, self.hang_gravity);
+
+        // The tether is taut, so it cancels anything that would take the fighter further from the anchor, which leaves
+        // them swinging around it
+        if let Some(anchor) = anchor {
+            let towards_anchor = anchor.normalize();
+            let along = speed.dot(towards_anchor);
+            if along < 0.0 {
+                speed -= towards_anchor * along;
+            }
+        }
+
+        speed.clamp_magnitude(self.hang_speed_max.max(caught_speed.mag()))
+    }
+
+    /// Gets the speed for the next frame of reeling in on a tether
+    /// # Arguments
+    /// * `speed` - The speed from the previous frame
+    /// * `anchor` - Where the tether is anchored, relative to the fighter
+    /// * `lr` - The direction the fighter is facing
+    pub fn rewind_speed(&self, speed: PaddedVec2, anchor: Option<PaddedVec2>, lr: f32) -> PaddedVec2 {
+        let target = match anchor {
+            // don't reel the fighter past the anchor
+            Some(anchor) if anchor.mag() > 0.0 => anchor.normalize() * self.reel_speed.min(anchor.mag()),
+            _ => PaddedVec2::new(
+                lr * self.reel_speed * self.reel_angle.cos(),
+                self.reel_speed * self.reel_angle.sin()
+            )
+        };
+
+        // accelerate towards the reel speed without overshooting it
+        speed + (target - speed).clamp_magnitude(self.reel_accel)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn hang_speed_is_capped() {
+        let params = TetherParams::DEFAULT;
+        let speed = params.hang_speed(PaddedVec2::new(10.0, -10.0), PaddedVec2::zeros(), None);
+        assert!(speed.mag() <= params.hang_speed_max + 1.0e-5);
+
+        // a fast catch keeps its speed
+        let speed = params.hang_speed(PaddedVec2::new(2.0, 0.0), PaddedVec2::new(2.0, 0.0), None);
+        assert!(speed.mag() > params.hang_speed_max);
+    }
+
+    #[test]
+    fn hang_swings_around_anchor() {
+        let params = TetherParams::DEFAULT;
+
+        // hanging straight below the anchor, falling is cancelled by the tether
+        let speed = params.hang_speed(PaddedVec2::zeros(), PaddedVec2::zeros(), Some(PaddedVec2::new(0.0, 10.0)));
+        assert!(speed.mag() < 1.0e-5);
+
+        // off to the side of the anchor, gravity swings the fighter in towards below it
+        let anchor = PaddedVec2::new(10.0, 10.0);
+        let mut speed = PaddedVec2::zeros();
+        for _ in 0..10 {
+            speed = params.hang_speed(speed, PaddedVec2::zeros(), Some(anchor));
+            assert!(speed.dot(anchor) >= -1.0e-5);
+        }
+        assert!(speed.x > 0.0);
+        assert!(speed.y < 0.0);
+    }
+
+    #[test]
+    fn rewind_reaches_reel_speed() {
+        let params = TetherParams::DEFAULT;
+        let mut speed = PaddedVec2::new(0.0, -1.0);
+        for _ in 0..60 {
+            speed = params.rewind_speed(speed, None, -1.0);
+        }
+        assert!((speed.mag() - params.reel_speed).abs() < 1.0e-5);
+        assert!(speed.x < 0.0);
+        assert!(speed.y > 0.0);
+    }
+
+    #[test]
+    fn rewind_reels_towards_anchor() {
+        let params = TetherParams::DEFAULT;
+        let anchor = PaddedVec2::new(30.0, 40.0);
+        let mut speed = PaddedVec2::zeros();
+        for _ in 0..60 {
+            speed = params.rewind_speed(speed, Some(anchor), -1.0);
+        }
+        assert!((speed.mag() - params.reel_speed).abs() < 1.0e-5);
+        assert!((speed.normalize() - anchor.normalize()).mag() < 1.0e-5);
+
+        // close to the anchor, the reel slows down so that it doesn't overshoot it
+        let speed = params.rewind_speed(PaddedVec2::zeros(), Some(PaddedVec2::new(0.1, 0.0)), 1.0);
+        assert!(speed.x <= 0.1);
+    }
+}
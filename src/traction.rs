@@ -0,0 +1,100 @@
+/// How a ground material changes the grounded energies
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[repr(C)]
+pub struct MaterialTraction {
+    /// The material, as returned by `GroundModule::get_touch_material_type`
+    pub material: i32,
+    pub brake_mul: f32,
+    pub accel_mul: f32,
+}
+
+impl MaterialTraction {
+    pub const NONE: Self = Self {
+        material: -1,
+        brake_mul: 1.0,
+        accel_mul: 1.0,
+    };
+}
+
+/// Values used by the grounded energies to make some ground materials more or less slippery than others.
+///
+/// The game only does this for the DamageAirIce stop reset type, and every material that isn't in the table keeps
+/// the game's behavior
+#[derive(Debug, Copy, Clone)]
+#[repr(C)]
+pub struct TractionParams {
+    pub materials: [Option<MaterialTraction>; 16],
+}
+
+impl TractionParams {
+    pub const DEFAULT: Self = Self {
+        materials: [None; 16],
+    };
+
+    /// Gets the traction of a material
+    /// # Arguments
+    /// * `material` - The material the fighter is standing on, or `None` if they aren't touching the ground
+    pub fn get(&self, material: Option<i32>) -> MaterialTraction {
+        material
+            .and_then(|material| self.materials.iter().flatten().find(|entry| entry.material == material))
+            .copied()
+            .unwrap_or(MaterialTraction::NONE)
+    }
+
+    /// Checks if any material has an entry, so that the ground doesn't need to be checked when none do
+    pub fn is_empty(&self) -> bool {
+        self.materials.iter().all(Option::is_none)
+    }
+
+    /// Adds or replaces the entry of a material, returns false if there is no room left
+    pub fn set(&mut self, traction: MaterialTraction) -> bool {
+        let slot = self.materials
+            .iter()
+            .position(|entry| entry.map_or(false, |entry| entry.material == traction.material))
+            .or_else(|| self.materials.iter().position(Option::is_none));
+
+        match slot {
+            Some(slot) => {
+                self.materials[slot] = Some(traction);
+                true
+            },
+            None => false
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const ICE: i32 = 5;
+    const GRASS: i32 = 2;
+
+    #[test]
+    fn lookup() {
+        let mut params = TractionParams::DEFAULT;
+        assert!(params.is_empty());
+        assert!(params.set(MaterialTraction { material: ICE, brake_mul: 0.2, accel_mul: 0.5 }));
+        assert!(params.set(MaterialTraction { material: GRASS, brake_mul: 1.1, accel_mul: 1.0 }));
+        assert!(!params.is_empty());
+
+        assert_eq!(params.get(Some(ICE)).brake_mul, 0.2);
+        assert_eq!(params.get(Some(GRASS)).brake_mul, 1.1);
+        assert_eq!(params.get(Some(0)), MaterialTraction::NONE);
+        assert_eq!(params.get(None), MaterialTraction::NONE);
+    }
+
+    #[test]
+    fn set_replaces() {
+        let mut params = TractionParams::DEFAULT;
+        assert!(params.set(MaterialTraction { material: ICE, brake_mul: 0.2, accel_mul: 0.5 }));
+        assert!(params.set(MaterialTraction { material: ICE, brake_mul: 0.1, accel_mul: 0.5 }));
+        assert_eq!(params.get(Some(ICE)).brake_mul, 0.1);
+        assert_eq!(params.materials.iter().flatten().count(), 1);
+
+        let mut params = TractionParams::DEFAULT;
+        assert!((0..16).all(|material| params.set(MaterialTraction { material, ..MaterialTraction::NONE })));
+        assert!(!params.set(MaterialTraction { material: 16, ..MaterialTraction::NONE }));
+        assert!(params.set(MaterialTraction { material: 3, brake_mul: 2.0, ..MaterialTraction::NONE }));
+    }
+}
@@ -0,0 +1,46 @@
+//! Compares the energy hash logs of two machines and prints the first frame and field where they diverged.
+//!
+//! ```text
+//! energy_hash_diff left.log right.log
+//! ```
+//! Exits with 0 when the logs agree, 1 when they diverged and 2 when they couldn't be read
+
+// only the reading side is used here, the plugin writes the logs
+#[allow(dead_code)]
+#[path = "../../../src/hash_log.rs"]
+mod hash_log;
+
+use std::process::exit;
+
+fn read_log(path: &str) -> Vec<(hash_log::FrameRecord, u64)> {
+    match std::fs::read_to_string(path) {
+        Ok(text) => hash_log::parse_log(&text),
+        Err(error) => {
+            eprintln!("couldn't read {}: {}", path, error);
+            exit(2);
+        }
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() != 3 {
+        eprintln!("usage: {} <left log> <right log>", args[0]);
+        exit(2);
+    }
+
+    let left = read_log(&args[1]);
+    let right = read_log(&args[2]);
+    if left.is_empty() || right.is_empty() {
+        eprintln!("no frame records in {}", if left.is_empty() { &args[1] } else { &args[2] });
+        exit(2);
+    }
+
+    match hash_log::first_divergence(&left, &right) {
+        Some(divergence) => {
+            println!("{}", divergence);
+            exit(1);
+        },
+        None => println!("{} and {} agree on every frame they share", args[1], args[2])
+    }
+}
@@ -0,0 +1,143 @@
+//! Makes the signatures for the functions in src/offsets.rs from a dump of the game's text section.
+//!
+//! ```text
+//! make_signatures main.text 0x6d3610 0x6d4040 ...
+//! make_signatures --update src/offsets.rs main.text 13.0.1
+//! ```
+//! The first form prints the shortest unique signature at each offset. The second makes one at the fallback offset of
+//! every function in src/offsets.rs for the version the dump is from, and writes them into the file. An offset that
+//! doesn't look like the start of a function is skipped, since the fallback is probably for another version.
+//! Exits with 0 when every offset got a signature, 1 when some didn't and 2 when a file couldn't be read or written
+
+// only the generating side is used here, the plugin does the searching
+#[allow(dead_code)]
+#[path = "../../../src/signature.rs"]
+mod signature;
+
+use std::process::exit;
+
+use signature::{is_function_start, Signature};
+
+/// The longest signature that is tried before giving up on an offset
+const MAX_LEN: usize = 128;
+
+fn parse_offset(arg: &str) -> Option<usize> {
+    let arg = arg.trim_start_matches("0x").trim_start_matches("0X");
+    usize::from_str_radix(arg, 16).ok()
+}
+
+fn read(path: &str) -> Vec<u8> {
+    std::fs::read(path).unwrap_or_else(|error| {
+        eprintln!("couldn't read {}: {}", path, error);
+        exit(2);
+    })
+}
+
+/// Finds the name of the constant that holds `version`, e.g. `V13_0_1` for 13.0.1
+fn version_constant(source: &str, version: &str) -> Option<String> {
+    source.lines().find_map(|line| {
+        let (name, value) = line.trim().strip_prefix("const ")?.split_once(": &str = ")?;
+        if value.trim_end_matches(';').trim_matches('"') == version {
+            Some(name.to_string())
+        } else {
+            None
+        }
+    })
+}
+
+/// Replaces the signatures of the functions in `source`, returning the new source and whether every function got one
+fn update(source: &str, text: &[u8], version: &str) -> Option<(String, bool)> {
+    let constant = version_constant(source, version)?;
+    let mut all_found = true;
+    let mut updated = String::new();
+    for line in source.split_inclusive('\n') {
+        let info = line.trim_start().starts_with("FunctionInfo {");
+        let fallback = line
+            .split_once(&format!("({}, ", constant))
+            .and_then(|(_, rest)| rest.split_once(')'))
+            .and_then(|(offset, _)| parse_offset(offset));
+        let signature = line
+            .split_once("signature: ")
+            .and_then(|(_, rest)| rest.split_once(", fallbacks"))
+            .map(|(signature, _)| signature);
+
+        let (offset, signature) = match (info, fallback, signature) {
+            (true, Some(offset), Some(signature)) => (offset, signature),
+            _ => {
+                updated.push_str(line);
+                continue;
+            }
+        };
+
+        let name = line.split_once("name: \"").and_then(|(_, rest)| rest.split_once('"')).map_or("?", |(name, _)| name);
+        if !is_function_start(text, offset) {
+            println!("{}: {:#x} doesn't look like the start of a function", name, offset);
+            all_found = false;
+            updated.push_str(line);
+            continue;
+        }
+
+        match Signature::generate(text, offset, MAX_LEN) {
+            Some(generated) => {
+                println!("{}: \"{}\"", name, generated);
+                updated.push_str(&line.replacen(signature, &format!("Some(\"{}\")", generated), 1));
+            },
+            None => {
+                println!("{}: no unique signature within {} bytes", name, MAX_LEN);
+                all_found = false;
+                updated.push_str(line);
+            }
+        }
+    }
+
+    Some((updated, all_found))
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() == 5 && args[1] == "--update" {
+        let source = String::from_utf8(read(&args[2])).unwrap_or_else(|_| {
+            eprintln!("{} is not UTF-8", args[2]);
+            exit(2);
+        });
+        let (updated, all_found) = update(&source, &read(&args[3]), &args[4]).unwrap_or_else(|| {
+            eprintln!("{} has no fallbacks for {}", args[2], args[4]);
+            exit(2);
+        });
+        if let Err(error) = std::fs::write(&args[2], updated) {
+            eprintln!("couldn't write {}: {}", args[2], error);
+            exit(2);
+        }
+        exit(if all_found { 0 } else { 1 });
+    }
+
+    if args.len() < 3 {
+        eprintln!("usage: {0} <text section> <offset>...\n       {0} --update <offsets.rs> <text section> <version>", args[0]);
+        exit(2);
+    }
+
+    let text = read(&args[1]);
+
+    let mut all_found = true;
+    for arg in &args[2..] {
+        let offset = match parse_offset(arg) {
+            Some(offset) => offset,
+            None => {
+                eprintln!("{} is not a hex offset", arg);
+                exit(2);
+            }
+        };
+
+        match Signature::generate(&text, offset, MAX_LEN) {
+            Some(signature) => println!("{:#x}: \"{}\"", offset, signature),
+            None => {
+                println!("{:#x}: no unique signature within {} bytes", offset, MAX_LEN);
+                all_found = false;
+            }
+        }
+    }
+
+    if !all_found {
+        exit(1);
+    }
+}